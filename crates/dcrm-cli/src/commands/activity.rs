@@ -0,0 +1,193 @@
+use super::{Result, Session, activity_id, contact_id, deal_id, optional};
+use crate::output::{self, local_date, short_id};
+use crate::parse;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use dcrm_core::actions;
+use dcrm_core::models::{
+    Activity, ActivityDetails, ActivityType, CallDetails, CallOutcome, Direction, EmailDetails,
+};
+
+pub const HEADERS: &[&str] = &["ID", "TYPE", "TITLE", "DUE", "DONE"];
+
+#[derive(Subcommand)]
+pub enum ActivityCommand {
+    /// Log a task, call, email, meeting or note
+    Log {
+        #[arg(value_parser = parse::activity_type)]
+        activity_type: ActivityType,
+        title: String,
+        /// Contact by id, id prefix or email
+        #[arg(long)]
+        contact: Option<String>,
+        /// Deal by id or id prefix
+        #[arg(long)]
+        deal: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        /// YYYY-MM-DD, "YYYY-MM-DD HH:MM" (local time) or RFC 3339
+        #[arg(long, value_parser = parse::date_time)]
+        due: Option<DateTime<Utc>>,
+        /// Log it as already completed
+        #[arg(long)]
+        done: bool,
+        /// Call length in minutes
+        #[arg(long)]
+        duration: Option<u32>,
+        /// How a call went: connected, voicemail or no-answer
+        #[arg(long, value_parser = parse::outcome)]
+        outcome: Option<CallOutcome>,
+        /// Inbound or outbound, for calls and emails
+        #[arg(long, value_parser = parse::direction)]
+        direction: Option<Direction>,
+        /// Email subject
+        #[arg(long)]
+        subject: Option<String>,
+    },
+    /// Mark an activity as completed
+    Complete {
+        /// Id or id prefix
+        activity: String,
+    },
+    /// List activities
+    List {
+        /// Only open activities past their due date
+        #[arg(long)]
+        overdue: bool,
+        /// Only open activities
+        #[arg(long)]
+        pending: bool,
+        #[arg(long = "type", value_parser = parse::activity_type)]
+        activity_type: Option<ActivityType>,
+        /// Contact by id, id prefix or email
+        #[arg(long)]
+        contact: Option<String>,
+        /// Deal by id or id prefix
+        #[arg(long)]
+        deal: Option<String>,
+    },
+}
+
+pub fn run(command: ActivityCommand, session: &mut Session) -> Result {
+    match command {
+        ActivityCommand::Log {
+            activity_type,
+            title,
+            contact,
+            deal,
+            description,
+            due,
+            done,
+            duration,
+            outcome,
+            direction,
+            subject,
+        } => {
+            let mut activity = Activity::new(activity_type, title);
+            activity.details = details(activity_type, duration, outcome, direction, subject)?;
+            if let Some(contact) = contact {
+                activity.contact_id = Some(contact_id(&session.data, &contact)?);
+            }
+            if let Some(deal) = deal {
+                activity.deal_id = Some(deal_id(&session.data, &deal)?);
+            }
+            activity.description = description.and_then(optional);
+            activity.due_date = due;
+            activity.completed = done;
+
+            actions::add_activity(&mut session.data, activity.clone())?;
+            session.save()?;
+            report(session, &activity.id, "Logged")
+        }
+        ActivityCommand::Complete { activity } => {
+            let id = activity_id(&session.data, &activity)?;
+            let completed = session.data.activity_by_id(&id).is_some_and(|a| a.completed);
+            if !completed {
+                actions::toggle_activity_completed(&mut session.data, &id);
+                session.save()?;
+            }
+            report(session, &id, if completed { "Already completed" } else { "Completed" })
+        }
+        ActivityCommand::List { overdue, pending, activity_type, contact, deal } => {
+            let data = &session.data;
+            let contact = contact.map(|c| contact_id(data, &c)).transpose()?;
+            let deal = deal.map(|d| deal_id(data, &d)).transpose()?;
+            let activities: Vec<&Activity> = data
+                .activities
+                .iter()
+                .filter(|a| contact.is_none() || a.contact_id == contact)
+                .filter(|a| deal.is_none() || a.deal_id == deal)
+                .filter(|a| activity_type.is_none_or(|t| a.activity_type == t))
+                .filter(|a| !pending || !a.completed)
+                .filter(|a| !overdue || a.is_overdue())
+                .collect();
+
+            if session.json {
+                return output::json(&activities);
+            }
+            let rows: Vec<Vec<String>> = activities.iter().map(|a| row(a)).collect();
+            output::table(HEADERS, &rows, "No activities.");
+            Ok(())
+        }
+    }
+}
+
+/// The type-specific fields, rejecting flags that don't apply to `activity_type`.
+fn details(
+    activity_type: ActivityType,
+    duration: Option<u32>,
+    outcome: Option<CallOutcome>,
+    direction: Option<Direction>,
+    subject: Option<String>,
+) -> Result<ActivityDetails> {
+    let only = |flag: &str, types: &str| -> Result<ActivityDetails> {
+        Err(format!("{} only applies to {}", flag, types).into())
+    };
+
+    match activity_type {
+        ActivityType::Call => {
+            if subject.is_some() {
+                return only("--subject", "emails");
+            }
+            Ok(ActivityDetails::Call(CallDetails {
+                direction: direction.unwrap_or_default(),
+                duration_minutes: duration,
+                outcome,
+            }))
+        }
+        ActivityType::Email => {
+            if duration.is_some() || outcome.is_some() {
+                return only("--duration and --outcome", "calls");
+            }
+            Ok(ActivityDetails::Email(EmailDetails {
+                direction: direction.unwrap_or_default(),
+                subject: subject.unwrap_or_default(),
+            }))
+        }
+        _ if duration.is_some() || outcome.is_some() => only("--duration and --outcome", "calls"),
+        _ if direction.is_some() => only("--direction", "calls and emails"),
+        _ if subject.is_some() => only("--subject", "emails"),
+        ActivityType::Meeting => Ok(ActivityDetails::Meeting(Default::default())),
+        _ => Ok(ActivityDetails::None),
+    }
+}
+
+pub fn row(activity: &Activity) -> Vec<String> {
+    vec![
+        short_id(&activity.id).to_string(),
+        activity.activity_type.to_string(),
+        activity.title.clone(),
+        activity.due_date.map(local_date).unwrap_or_default(),
+        if activity.completed { "yes" } else { "" }.to_string(),
+    ]
+}
+
+/// Prints the activity a command changed.
+fn report(session: &Session, id: &str, verb: &str) -> Result {
+    let activity = session.data.activity_by_id(id).ok_or("activity not found")?;
+    if session.json {
+        return output::json(activity);
+    }
+    println!("{} {} ({})", verb, activity.title, short_id(&activity.id));
+    Ok(())
+}
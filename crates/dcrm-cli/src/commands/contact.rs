@@ -0,0 +1,214 @@
+use super::{Result, Session, activity, clean_tags, contact_id, deal, optional};
+use crate::output::{self, short_id};
+use clap::Subcommand;
+use dcrm_core::actions;
+use dcrm_core::models::{Contact, DeleteMode};
+use serde_json::json;
+
+#[derive(Subcommand)]
+pub enum ContactCommand {
+    /// Add a contact
+    Add {
+        #[arg(long)]
+        first: String,
+        #[arg(long)]
+        last: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        phone: Option<String>,
+        #[arg(long)]
+        company: Option<String>,
+        #[arg(long)]
+        position: Option<String>,
+        /// Tag to add; repeat for more
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// List contacts
+    List {
+        /// Only contacts with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Show a contact with their deals and activities
+    Show {
+        /// Id, id prefix or email address
+        contact: String,
+    },
+    /// Change a contact's fields; an empty value clears an optional field
+    Edit {
+        /// Id, id prefix or email address
+        contact: String,
+        #[arg(long)]
+        first: Option<String>,
+        #[arg(long)]
+        last: Option<String>,
+        #[arg(long)]
+        email: Option<String>,
+        #[arg(long)]
+        phone: Option<String>,
+        #[arg(long)]
+        company: Option<String>,
+        #[arg(long)]
+        position: Option<String>,
+        /// Replaces the tags; repeat for more, or pass "" to remove them all
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// Move a contact to the trash, unlinking their deals and activities
+    Delete {
+        /// Id, id prefix or email address
+        contact: String,
+        /// Delete the contact's activities and the deals they are the primary contact on
+        #[arg(long, conflicts_with = "reassign")]
+        cascade: bool,
+        /// Give the contact's deals and activities to this contact instead
+        #[arg(long, value_name = "CONTACT")]
+        reassign: Option<String>,
+    },
+}
+
+pub fn run(command: ContactCommand, session: &mut Session) -> Result {
+    match command {
+        ContactCommand::Add { first, last, email, phone, company, position, tags, notes } => {
+            let mut contact = Contact::new(first, last, email);
+            contact.phone = phone.and_then(optional);
+            contact.company = company.and_then(optional);
+            contact.position = position.and_then(optional);
+            contact.tags = clean_tags(tags);
+            contact.notes = notes.and_then(optional);
+
+            actions::add_contact(&mut session.data, contact.clone())?;
+            session.save()?;
+            report(session, &contact, "Added")
+        }
+        ContactCommand::List { tag } => list(session, tag),
+        ContactCommand::Show { contact } => show(session, &contact),
+        ContactCommand::Edit { contact, first, last, email, phone, company, position, tags, notes } => {
+            let id = contact_id(&session.data, &contact)?;
+            let mut contact = session.data.contact_by_id(&id).cloned().ok_or("contact not found")?;
+            if let Some(first) = first {
+                contact.first_name = first;
+            }
+            if let Some(last) = last {
+                contact.last_name = last;
+            }
+            if let Some(email) = email {
+                contact.email = email;
+            }
+            if let Some(phone) = phone {
+                contact.phone = optional(phone);
+            }
+            if let Some(company) = company {
+                contact.company = optional(company);
+            }
+            if let Some(position) = position {
+                contact.position = optional(position);
+            }
+            if !tags.is_empty() {
+                contact.tags = clean_tags(tags);
+            }
+            if let Some(notes) = notes {
+                contact.notes = optional(notes);
+            }
+            contact.updated_at = chrono::Utc::now();
+
+            actions::update_contact(&mut session.data, contact.clone())?;
+            session.save()?;
+            report(session, &contact, "Updated")
+        }
+        ContactCommand::Delete { contact, cascade, reassign } => {
+            let id = contact_id(&session.data, &contact)?;
+            let mode = match reassign {
+                Some(other) => {
+                    let other = contact_id(&session.data, &other)?;
+                    if other == id {
+                        return Err("can't reassign a contact's records to itself".into());
+                    }
+                    DeleteMode::Reassign(other)
+                }
+                None if cascade => DeleteMode::Cascade,
+                None => DeleteMode::Unlink,
+            };
+
+            let contact = session.data.contact_by_id(&id).cloned().ok_or("contact not found")?;
+            actions::delete_contact(&mut session.data, &id, mode);
+            session.save()?;
+            report(session, &contact, "Deleted")
+        }
+    }
+}
+
+/// Prints the contact a command changed.
+fn report(session: &Session, contact: &Contact, verb: &str) -> Result {
+    if session.json {
+        return output::json(contact);
+    }
+    println!("{} {} ({})", verb, contact.full_name(), short_id(&contact.id));
+    Ok(())
+}
+
+fn list(session: &Session, tag: Option<String>) -> Result {
+    let contacts: Vec<&Contact> = match &tag {
+        Some(tag) => session.data.contacts_with_tag(tag),
+        None => session.data.contacts.iter().collect(),
+    };
+    if session.json {
+        return output::json(&contacts);
+    }
+
+    let rows: Vec<Vec<String>> = contacts
+        .iter()
+        .map(|c| {
+            vec![
+                short_id(&c.id).to_string(),
+                c.full_name(),
+                c.email.clone(),
+                c.company.clone().unwrap_or_default(),
+                c.tags.join(", "),
+            ]
+        })
+        .collect();
+    output::table(&["ID", "NAME", "EMAIL", "COMPANY", "TAGS"], &rows, "No contacts.");
+    Ok(())
+}
+
+fn show(session: &Session, key: &str) -> Result {
+    let data = &session.data;
+    let id = contact_id(data, key)?;
+    let contact = data.contact_by_id(&id).ok_or("contact not found")?;
+    let deals = data.deals_for_contact(&id);
+    let activities = data.activities_for_contact(&id);
+
+    if session.json {
+        return output::json(&json!({
+            "contact": contact,
+            "deals": deals,
+            "activities": activities,
+        }));
+    }
+
+    println!("{} ({})", contact.full_name(), short_id(&contact.id));
+    output::fields(&[
+        ("Email", contact.email.clone()),
+        ("Phone", contact.phone.clone().unwrap_or_default()),
+        ("Company", contact.company.clone().unwrap_or_default()),
+        ("Position", contact.position.clone().unwrap_or_default()),
+        ("Tags", contact.tags.join(", ")),
+        ("Notes", contact.notes.clone().unwrap_or_default()),
+    ]);
+
+    println!("\nDeals");
+    let rows: Vec<Vec<String>> = deals.iter().map(|d| deal::row(d)).collect();
+    output::table(deal::HEADERS, &rows, "No deals.");
+
+    println!("\nActivities");
+    let rows: Vec<Vec<String>> = activities.iter().map(|a| activity::row(a)).collect();
+    output::table(activity::HEADERS, &rows, "No activities.");
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use super::{Result, Session, contact_id, deal_id, optional};
+use crate::output::{self, short_id};
+use crate::parse;
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use dcrm_core::actions;
+use dcrm_core::models::{CloseInfo, Currency, Deal, DealParticipant, DealRole, DealStage, Money};
+
+pub const HEADERS: &[&str] = &["ID", "TITLE", "COMPANY", "STAGE", "VALUE", "PROB"];
+
+#[derive(Subcommand)]
+pub enum DealCommand {
+    /// Add a deal
+    Add {
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        company: String,
+        /// Amount, e.g. 1500 or 1,500.50
+        #[arg(long)]
+        value: String,
+        /// Currency code; the reporting currency when omitted
+        #[arg(long, value_parser = parse::currency)]
+        currency: Option<Currency>,
+        /// Contact on the deal, by id, id prefix or email; repeat for more. The first is primary.
+        #[arg(long = "contact", value_name = "CONTACT")]
+        contacts: Vec<String>,
+        /// An open stage to start in
+        #[arg(long, value_parser = parse::stage, default_value = "Lead")]
+        stage: DealStage,
+        /// Chance of winning, 0-100
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        probability: Option<u8>,
+        /// Expected close date
+        #[arg(long, value_parser = parse::date_time)]
+        close_date: Option<DateTime<Utc>>,
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// Move a deal to another stage; Won and Lost need a close reason
+    MoveStage {
+        /// Id or id prefix
+        deal: String,
+        #[arg(value_parser = parse::stage)]
+        stage: DealStage,
+        /// Why the deal was won or lost
+        #[arg(long)]
+        reason: Option<String>,
+        /// Note on the close
+        #[arg(long)]
+        note: Option<String>,
+        /// Amount the deal closed at; its current value when omitted
+        #[arg(long)]
+        final_value: Option<String>,
+    },
+    /// List deals
+    List {
+        #[arg(long, value_parser = parse::stage)]
+        stage: Option<DealStage>,
+        /// Only deals with this contact, by id, id prefix or email
+        #[arg(long)]
+        contact: Option<String>,
+    },
+}
+
+pub fn run(command: DealCommand, session: &mut Session) -> Result {
+    match command {
+        DealCommand::Add { title, company, value, currency, contacts, stage, probability, close_date, notes } => {
+            if !DealStage::active().contains(&stage) {
+                return Err("new deals start in an open stage; close them with `deal move-stage`".into());
+            }
+            let currency = currency.unwrap_or(session.data.settings.reporting_currency);
+            let value = Money::parse(&value, currency).ok_or_else(|| format!("'{}' is not an amount", value))?;
+
+            let mut deal = Deal::new(title, company, value);
+            for contact in contacts {
+                let contact_id = contact_id(&session.data, &contact)?;
+                if !deal.has_participant(&contact_id) {
+                    deal.participants.push(DealParticipant { contact_id, role: DealRole::default() });
+                }
+            }
+            deal.set_stage(stage);
+            if let Some(probability) = probability {
+                deal.probability = probability;
+            }
+            deal.expected_close = close_date;
+            deal.notes = notes.and_then(optional);
+
+            actions::add_deal(&mut session.data, deal.clone())?;
+            session.save()?;
+            report(session, &deal.id, "Added")
+        }
+        DealCommand::MoveStage { deal, stage, reason, note, final_value } => {
+            let id = deal_id(&session.data, &deal)?;
+            let current = session.data.deal_by_id(&id).cloned().ok_or("deal not found")?;
+
+            if DealStage::active().contains(&stage) {
+                actions::update_deal_stage(&mut session.data, &id, stage);
+            } else {
+                let reasons = session.data.settings.close_reasons(stage);
+                let reason = reason.and_then(optional).ok_or_else(|| {
+                    format!("marking a deal as {} needs --reason (e.g. {})", stage, reasons.join(", "))
+                })?;
+                let final_value = match final_value {
+                    Some(amount) => Money::parse(&amount, current.value.currency)
+                        .ok_or_else(|| format!("'{}' is not an amount", amount))?,
+                    None => current.value,
+                };
+                let close = CloseInfo {
+                    reason,
+                    note: note.and_then(optional),
+                    closed_at: Utc::now(),
+                    final_value,
+                };
+                actions::close_deal(&mut session.data, &id, stage, close)?;
+            }
+            session.save()?;
+            report(session, &id, "Moved")
+        }
+        DealCommand::List { stage, contact } => {
+            let data = &session.data;
+            let mut deals: Vec<&Deal> = match contact {
+                Some(contact) => data.deals_for_contact(&contact_id(data, &contact)?),
+                None => data.deals.iter().collect(),
+            };
+            if let Some(stage) = stage {
+                deals.retain(|d| d.stage == stage);
+            }
+            deals.sort_by_key(|d| (d.stage.order(), d.stage == DealStage::Lost, d.rank));
+
+            if session.json {
+                return output::json(&deals);
+            }
+            let rows: Vec<Vec<String>> = deals.iter().map(|d| row(d)).collect();
+            output::table(HEADERS, &rows, "No deals.");
+            Ok(())
+        }
+    }
+}
+
+pub fn row(deal: &Deal) -> Vec<String> {
+    vec![
+        short_id(&deal.id).to_string(),
+        deal.title.clone(),
+        deal.company.clone(),
+        deal.stage.to_string(),
+        deal.value.format_precise(),
+        format!("{}%", deal.probability),
+    ]
+}
+
+/// Prints the deal a command changed, as it now stands.
+fn report(session: &Session, id: &str, verb: &str) -> Result {
+    let deal = session.data.deal_by_id(id).ok_or("deal not found")?;
+    if session.json {
+        return output::json(deal);
+    }
+    println!("{} {} ({}) • {}", verb, deal.title, short_id(&deal.id), deal.stage);
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! Subcommands and the helpers they share.
+
+mod activity;
+mod contact;
+mod deal;
+
+use crate::output;
+use clap::Subcommand;
+use dcrm_core::models::{self, AppData, Delivery, FileChanged};
+use dcrm_core::search::search;
+use dcrm_webhooks::RetryPolicy;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub type Result<T = ()> = std::result::Result<T, Box<dyn Error>>;
+
+/// Tries at saving the delivery log while other programs keep writing the file.
+const SAVE_ATTEMPTS: u32 = 3;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Add, list, show, edit and delete contacts
+    #[command(subcommand)]
+    Contact(contact::ContactCommand),
+    /// Add, list and move deals through the pipeline
+    #[command(subcommand)]
+    Deal(deal::DealCommand),
+    /// Log, complete and list activities
+    #[command(subcommand)]
+    Activity(activity::ActivityCommand),
+    /// Search contacts, deals and activities
+    Search { query: String },
+    /// Show the dashboard figures
+    Stats,
+}
+
+/// The open data file and how to print results.
+pub struct Session {
+    pub data: AppData,
+    pub json: bool,
+    path: PathBuf,
+}
+
+impl Session {
+    /// Writes the data back to the file it was read from, then tries each webhook
+    /// the change triggered once and saves the outcome to the delivery log.
+    /// Deliveries worth retrying stay pending for the desktop app to send when it
+    /// next starts.
+    ///
+    /// Only the first write can fail the command. Once the change is saved, a
+    /// problem logging the deliveries is reported as a warning.
+    pub fn save(&mut self) -> Result {
+        let jobs = self.data.take_deliveries();
+        models::save_to(&mut self.data, &self.path)?;
+        if jobs.is_empty() {
+            return Ok(());
+        }
+
+        let delivered = dcrm_webhooks::deliver_all(jobs, &RetryPolicy::once());
+        if let Err(e) = self.log_deliveries(delivered) {
+            eprintln!("warning: couldn't save the webhook delivery log: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Stores delivery outcomes in the log. If another program wrote the file
+    /// meanwhile, they are stored over its latest contents instead.
+    fn log_deliveries(&mut self, delivered: Vec<Delivery>) -> Result {
+        let mut attempts = 1;
+        loop {
+            for delivery in &delivered {
+                self.data.record_delivery(delivery.clone());
+            }
+            match models::save_to(&mut self.data, &self.path) {
+                Err(e) if e.is::<FileChanged>() && attempts < SAVE_ATTEMPTS => {
+                    self.data = models::open_data(&self.path)?;
+                    attempts += 1;
+                }
+                saved => return saved,
+            }
+        }
+    }
+}
+
+pub fn run(command: Command, path: &Path, json: bool) -> Result {
+    let mut session = Session {
+        data: models::open_data(path)?,
+        json,
+        path: path.to_path_buf(),
+    };
+
+    match command {
+        Command::Contact(command) => contact::run(command, &mut session),
+        Command::Deal(command) => deal::run(command, &mut session),
+        Command::Activity(command) => activity::run(command, &mut session),
+        Command::Search { query } => run_search(&session, &query),
+        Command::Stats => run_stats(&session),
+    }
+}
+
+fn run_search(session: &Session, query: &str) -> Result {
+    let results = search(&session.data, query);
+
+    if session.json {
+        return output::json(&results);
+    }
+
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|r| {
+            vec![
+                output::short_id(r.id()).to_string(),
+                r.result_type().to_string(),
+                r.title(),
+                r.subtitle(),
+            ]
+        })
+        .collect();
+    output::table(&["ID", "TYPE", "TITLE", "DETAILS"], &rows, "No matches.");
+    Ok(())
+}
+
+fn run_stats(session: &Session) -> Result {
+    let summary = session.data.summary();
+    if session.json {
+        return output::json(&summary);
+    }
+
+    let percent = |rate: Option<f64>| rate.map(|r| format!("{:.0}%", r)).unwrap_or_else(|| "–".to_string());
+    output::fields(&[
+        ("Contacts", summary.contacts.to_string()),
+        ("Active deals", summary.active_deals.to_string()),
+        ("Pipeline value", summary.pipeline_value.format_precise()),
+        ("Weighted pipeline", summary.weighted_pipeline_value.format_precise()),
+        ("Won value", summary.won_value.format_precise()),
+        ("Won / lost", format!("{} / {}", summary.won_deals, summary.lost_deals)),
+        ("Win rate", percent(summary.win_rate)),
+        ("Pending tasks", summary.pending_tasks.to_string()),
+        ("Overdue", summary.overdue_activities.to_string()),
+        ("Calls", summary.calls.to_string()),
+        ("Connect rate", percent(summary.connect_rate)),
+        ("Meetings", summary.meetings.to_string()),
+        ("Emails sent", summary.emails_sent.to_string()),
+        ("Emails received", summary.emails_received.to_string()),
+    ]);
+    Ok(())
+}
+
+// ============================================================================
+// Record lookup
+// ============================================================================
+
+/// Picks the id equal to `key`, or the only id starting with it.
+fn find_id<'a>(ids: impl Iterator<Item = &'a str>, kind: &str, key: &str) -> Result<String> {
+    if key.is_empty() {
+        return Err(format!("no {} id given", kind).into());
+    }
+
+    let matches: Vec<&str> = ids.filter(|id| id.starts_with(key)).collect();
+    if matches.contains(&key) {
+        return Ok(key.to_string());
+    }
+    match matches.as_slice() {
+        [id] => Ok(id.to_string()),
+        [] => Err(format!("no {} matches '{}'", kind, key).into()),
+        _ => Err(format!("'{}' is ambiguous: {} {} ids start with it", key, matches.len(), kind).into()),
+    }
+}
+
+/// Resolves a contact by id, id prefix or email address.
+pub fn contact_id(data: &AppData, key: &str) -> Result<String> {
+    if key.contains('@') {
+        return data
+            .contacts
+            .iter()
+            .find(|c| c.email.eq_ignore_ascii_case(key))
+            .map(|c| c.id.clone())
+            .ok_or_else(|| format!("no contact has the email '{}'", key).into());
+    }
+    find_id(data.contacts.iter().map(|c| c.id.as_str()), "contact", key)
+}
+
+pub fn deal_id(data: &AppData, key: &str) -> Result<String> {
+    find_id(data.deals.iter().map(|d| d.id.as_str()), "deal", key)
+}
+
+pub fn activity_id(data: &AppData, key: &str) -> Result<String> {
+    find_id(data.activities.iter().map(|a| a.id.as_str()), "activity", key)
+}
+
+/// `None` for an empty value, so `--phone ""` clears a field.
+fn optional(value: String) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Trimmed, non-empty tags in the order given, without repeats.
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !cleaned.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            cleaned.push(tag.to_string());
+        }
+    }
+    cleaned
+}
@@ -0,0 +1,42 @@
+//! `dcrm-cli`: scripts the CRM from the command line.
+//!
+//! Reads and writes the same data file as the desktop app, so changes show up
+//! there the next time it starts. Every command can print JSON with `--json`.
+
+mod commands;
+mod output;
+mod parse;
+
+use clap::Parser;
+use commands::Command;
+use dcrm_core::models;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "dcrm-cli", version, about = "Script your DCRM contacts, deals and activities")]
+struct Cli {
+    /// Data file to use instead of the desktop app's
+    #[arg(long, global = true, env = "DCRM_DATA", value_name = "FILE")]
+    data: Option<PathBuf>,
+
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let path = cli.data.unwrap_or_else(models::data_path);
+
+    match commands::run(cli.command, &path, cli.json) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Text and JSON printing.
+
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::error::Error;
+
+/// The first eight characters of an id, enough to pick a record in most stores.
+pub fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+pub fn local_date(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+}
+
+pub fn json(value: &impl Serialize) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Prints rows under `headers` in left-aligned columns, or `empty` when there are none.
+pub fn table(headers: &[&str], rows: &[Vec<String>], empty: &str) {
+    if rows.is_empty() {
+        println!("{}", empty);
+        return;
+    }
+
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+
+    line(headers.to_vec());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
+
+/// Prints `label: value` lines, skipping empty values.
+pub fn fields(fields: &[(&str, String)]) {
+    let width = fields.iter().map(|(label, _)| label.len()).max().unwrap_or(0) + 1;
+    for (label, value) in fields.iter().filter(|(_, v)| !v.is_empty()) {
+        println!("{:<width$} {}", format!("{}:", label), value, width = width);
+    }
+}
@@ -0,0 +1,68 @@
+//! Argument parsers for the model types.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use dcrm_core::models::{ActivityType, CallOutcome, Currency, DealStage, Direction};
+use std::fmt::Display;
+
+/// Lower-case letters and digits only, so `no-answer` matches `No answer`.
+fn simplify(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Finds the option whose display name matches `input`, ignoring case and punctuation.
+fn choice<T: Display>(input: &str, options: Vec<T>) -> Result<T, String> {
+    let wanted = simplify(input);
+    let names: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+
+    options
+        .into_iter()
+        .find(|o| simplify(&o.to_string()) == wanted)
+        .ok_or_else(|| format!("expected one of: {}", names.join(", ")))
+}
+
+pub fn stage(input: &str) -> Result<DealStage, String> {
+    choice(input, DealStage::all())
+}
+
+pub fn activity_type(input: &str) -> Result<ActivityType, String> {
+    choice(input, ActivityType::all())
+}
+
+pub fn outcome(input: &str) -> Result<CallOutcome, String> {
+    choice(input, CallOutcome::all())
+}
+
+pub fn direction(input: &str) -> Result<Direction, String> {
+    choice(input, Direction::all())
+}
+
+pub fn currency(input: &str) -> Result<Currency, String> {
+    choice(input, Currency::all())
+}
+
+/// Accepts RFC 3339, `YYYY-MM-DD HH:MM` in local time, or `YYYY-MM-DD` for the
+/// end of that day in local time.
+pub fn date_time(input: &str) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(input) {
+        return Ok(at.with_timezone(&Utc));
+    }
+
+    let local = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(23, 59, 59))
+        })
+        .ok_or("expected YYYY-MM-DD, \"YYYY-MM-DD HH:MM\" or an RFC 3339 timestamp")?;
+
+    local
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .ok_or_else(|| format!("{} does not exist in the local timezone", input))
+}
@@ -0,0 +1,211 @@
+//! Runs the built binary against a throwaway data file.
+
+use dcrm_core::models::{self, AppData, Webhook, WebhookEvent};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// A data file under the system temp directory, removed when dropped.
+struct Store(PathBuf);
+
+impl Store {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("dcrm-cli-test-{}", uuid::Uuid::new_v4()));
+        Self(dir.join("data.json"))
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_dcrm-cli"))
+            .arg("--data")
+            .arg(&self.0)
+            .args(args)
+            .output()
+            .expect("failed to run dcrm-cli")
+    }
+
+    /// Runs a command that should succeed and returns its JSON output.
+    fn json(&self, args: &[&str]) -> Value {
+        let output = self.run(&[args, &["--json"]].concat());
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice(&output.stdout).expect("output is not JSON")
+    }
+
+    /// Runs a command that should fail and returns its error message.
+    fn error(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        assert!(!output.status.success());
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    }
+
+    fn add_ada(&self) -> String {
+        let contact = self.json(&[
+            "contact", "add", "--first", "Ada", "--last", "Lovelace", "--email", "ada@example.com",
+            "--tag", "vip",
+        ]);
+        contact["id"].as_str().unwrap().to_string()
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        if let Some(dir) = self.0.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[test]
+fn contacts_can_be_added_edited_and_deleted() {
+    let store = Store::new();
+    let id = store.add_ada();
+
+    let listed = store.json(&["contact", "list", "--tag", "VIP"]);
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+
+    // Edit by id prefix; an empty value clears the field
+    store.json(&["contact", "edit", &id[..8], "--company", "Analytical Engines", "--phone", "+44 20 7946 0000"]);
+    let edited = store.json(&["contact", "edit", "ada@example.com", "--phone", ""]);
+    assert_eq!(edited["company"], "Analytical Engines");
+    assert!(edited["phone"].is_null());
+
+    store.json(&["contact", "delete", &id]);
+    assert!(store.json(&["contact", "list"]).as_array().unwrap().is_empty());
+}
+
+#[test]
+fn invalid_input_fails_without_saving() {
+    let store = Store::new();
+    let message = store.error(&["contact", "add", "--first", "Ada", "--last", "L", "--email", "nope"]);
+    assert!(message.contains("email"), "{}", message);
+    assert!(!store.0.exists());
+
+    let message = store.error(&["deal", "list", "--stage", "Someday"]);
+    assert!(message.contains("Negotiation"), "{}", message);
+}
+
+#[test]
+fn deals_move_through_the_pipeline() {
+    let store = Store::new();
+    let ada = store.add_ada();
+    let deal = store.json(&[
+        "deal", "add", "--title", "Engine", "--company", "Acme", "--value", "1,500", "--contact",
+        "ada@example.com",
+    ]);
+    let id = deal["id"].as_str().unwrap();
+    assert_eq!(deal["stage"], "Lead");
+    assert_eq!(deal["value"]["amount"], "1500");
+
+    let moved = store.json(&["deal", "move-stage", id, "proposal"]);
+    assert_eq!(moved["probability"], 50);
+
+    assert!(store.error(&["deal", "move-stage", id, "won"]).contains("--reason"));
+    let won = store.json(&["deal", "move-stage", id, "won", "--reason", "Price", "--final-value", "1200"]);
+    assert_eq!(won["close"]["reason"], "Price");
+    assert_eq!(won["value"]["amount"], "1200");
+
+    let shown = store.json(&["contact", "show", &ada]);
+    assert_eq!(shown["deals"].as_array().unwrap().len(), 1);
+    assert_eq!(store.json(&["deal", "list", "--stage", "won"]).as_array().unwrap().len(), 1);
+    assert_eq!(store.json(&["stats"])["won_deals"], 1);
+}
+
+#[test]
+fn activities_are_logged_completed_and_filtered() {
+    let store = Store::new();
+    store.add_ada();
+    let call = store.json(&[
+        "activity", "log", "call", "Intro call", "--contact", "ada@example.com", "--duration", "15",
+        "--outcome", "no-answer",
+    ]);
+    assert_eq!(call["details"]["Call"]["outcome"], "NoAnswer");
+    store.json(&["activity", "log", "task", "Send deck", "--due", "2000-01-01"]);
+
+    let overdue = store.json(&["activity", "list", "--overdue"]);
+    assert_eq!(overdue.as_array().unwrap().len(), 1);
+    let task = overdue[0]["id"].as_str().unwrap();
+
+    // Completing twice leaves the activity completed
+    store.json(&["activity", "complete", task]);
+    assert_eq!(store.json(&["activity", "complete", task])["completed"], true);
+    assert!(store.json(&["activity", "list", "--pending", "--type", "task"]).as_array().unwrap().is_empty());
+
+    assert!(store.error(&["activity", "log", "task", "Wrong", "--duration", "5"]).contains("calls"));
+}
+
+#[test]
+fn search_finds_records_by_name() {
+    let store = Store::new();
+    store.add_ada();
+
+    let results = store.json(&["search", "lovelace"]);
+    assert_eq!(results[0]["type"], "Contact");
+    assert_eq!(results[0]["title"], "Ada Lovelace");
+
+    let text = store.run(&["search", "lovelace"]);
+    assert!(String::from_utf8_lossy(&text.stdout).contains("Ada Lovelace"));
+}
+
+#[test]
+fn changes_fire_webhooks_before_exiting() {
+    let store = Store::new();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    let mut data = AppData::new();
+    data.settings.webhooks.push(Webhook::new(url, "s3cret".into(), vec![WebhookEvent::ContactCreated]));
+    models::save_to(&mut data, &store.0).unwrap();
+
+    // Answer the one request with 204 and keep its first line and headers
+    let receiver = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let head: Vec<String> = BufReader::new(stream.try_clone().unwrap())
+            .lines()
+            .map(Result::unwrap)
+            .take_while(|line| !line.is_empty())
+            .collect();
+        stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n").unwrap();
+        head
+    });
+    store.add_ada();
+
+    let head = receiver.join().unwrap();
+    assert_eq!(head[0], "POST /hooks HTTP/1.1");
+    assert!(head.iter().any(|h| h.eq_ignore_ascii_case("x-dcrm-event: contact.created")));
+    let saved = models::open_data(&store.0).unwrap();
+    assert_eq!(saved.deliveries.len(), 1);
+    assert_eq!(saved.deliveries[0].last_attempt().unwrap().status_code, Some(204));
+}
+
+#[test]
+fn delivery_log_survives_an_edit_made_while_delivering() {
+    let store = Store::new();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    let mut data = AppData::new();
+    data.settings.webhooks.push(Webhook::new(url, "s3cret".into(), vec![WebhookEvent::ContactCreated]));
+    models::save_to(&mut data, &store.0).unwrap();
+
+    // Another program saves the file between the command's change and its delivery log
+    let path = store.0.clone();
+    let receiver = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        BufReader::new(stream.try_clone().unwrap())
+            .lines()
+            .map(Result::unwrap)
+            .take_while(|line| !line.is_empty())
+            .for_each(drop);
+        let mut data = models::open_data(&path).unwrap();
+        data.settings.user_name = "Grace".into();
+        models::save_to(&mut data, &path).unwrap();
+        stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n").unwrap();
+    });
+    store.add_ada();
+    receiver.join().unwrap();
+
+    let saved = models::open_data(&store.0).unwrap();
+    assert_eq!(saved.contacts.len(), 1);
+    assert_eq!(saved.settings.user_name, "Grace");
+    assert_eq!(saved.deliveries.len(), 1);
+    assert_eq!(saved.deliveries[0].last_attempt().unwrap().status_code, Some(204));
+}
@@ -0,0 +1,174 @@
+//! Lookup and edit timings for the record store at 10k and 100k contacts.
+//!
+//! Run with `cargo bench -p dcrm-core --bench store`. Each size gets as many deals as contacts
+//! and twice as many activities.
+
+use dcrm_core::models::{
+    Activity, ActivityType, AppData, Contact, Currency, Deal, DealParticipant, DealRole, Money, Records,
+};
+use rust_decimal::Decimal;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZES: [usize; 2] = [10_000, 100_000];
+const LOOKUPS: usize = 10_000;
+const TAGS: [&str; 5] = ["enterprise", "startup", "partner", "churn-risk", "vip"];
+
+fn build(size: usize) -> AppData {
+    let mut data = AppData::new();
+
+    for i in 0..size {
+        let mut contact = Contact::new(
+            format!("First{}", i),
+            format!("Last{}", i),
+            format!("person{}@example.com", i),
+        );
+        contact.tags.push(TAGS[i % TAGS.len()].to_string());
+        data.contacts.push(contact);
+    }
+
+    for i in 0..size {
+        let mut deal = Deal::new(
+            format!("Deal {}", i),
+            format!("Company {}", i % 500),
+            Money::new(Decimal::from(1_000 + i as i64), Currency::USD),
+        );
+        deal.participants.push(DealParticipant {
+            contact_id: data.contacts[i].id.clone(),
+            role: DealRole::default(),
+        });
+        data.deals.push(deal);
+    }
+
+    for i in 0..size * 2 {
+        let mut activity = Activity::new(ActivityType::Task, format!("Follow up {}", i));
+        activity.contact_id = Some(data.contacts[i % size].id.clone());
+        activity.deal_id = Some(data.deals[(i * 7) % size].id.clone());
+        data.activities.push(activity);
+    }
+
+    data
+}
+
+/// Runs `f` once per id and returns the average time per call.
+fn time_per_call(ids: &[String], mut f: impl FnMut(&str)) -> Duration {
+    let start = Instant::now();
+    for id in ids {
+        f(id);
+    }
+    start.elapsed() / ids.len() as u32
+}
+
+fn report(name: &str, took: Duration) {
+    println!("  {:<34} {:>12.2?}", name, took);
+}
+
+fn main() {
+    for size in SIZES {
+        println!("{} contacts, {} deals, {} activities", size, size, size * 2);
+
+        let mut data = build(size);
+        let (contacts, deals, activities) = (data.contacts.to_vec(), data.deals.to_vec(), data.activities.to_vec());
+        let start = Instant::now();
+        data.contacts = Records::from(contacts);
+        data.deals = Records::from(deals);
+        data.activities = Records::from(activities);
+        report("index on load", start.elapsed());
+
+        // Spread the probes across the whole list so scans pay their average cost
+        let step = (size / LOOKUPS).max(1);
+        let contact_ids: Vec<String> = data.contacts.iter().step_by(step).map(|c| c.id.clone()).collect();
+        let deal_ids: Vec<String> = data.deals.iter().step_by(step).map(|d| d.id.clone()).collect();
+        // Scans are slow enough that a hundred spread-out probes will do
+        let scan_ids: Vec<String> = contact_ids.iter().step_by(contact_ids.len() / 100).cloned().collect();
+
+        report(
+            "contact_by_id (indexed)",
+            time_per_call(&contact_ids, |id| {
+                black_box(data.contact_by_id(id));
+            }),
+        );
+        report(
+            "contact_by_id (linear scan)",
+            time_per_call(&scan_ids, |id| {
+                black_box(data.contacts.iter().find(|c| c.id == id));
+            }),
+        );
+        report(
+            "deal_by_id (indexed)",
+            time_per_call(&deal_ids, |id| {
+                black_box(data.deal_by_id(id));
+            }),
+        );
+        report(
+            "deals_for_contact",
+            time_per_call(&contact_ids, |id| {
+                black_box(data.deals_for_contact(id));
+            }),
+        );
+        report(
+            "activities_for_contact (indexed)",
+            time_per_call(&contact_ids, |id| {
+                black_box(data.activities_for_contact(id));
+            }),
+        );
+        report(
+            "activities_for_contact (scan)",
+            time_per_call(&scan_ids, |id| {
+                black_box(
+                    data.activities
+                        .iter()
+                        .filter(|a| a.contact_id.as_deref() == Some(id))
+                        .count(),
+                );
+            }),
+        );
+        report(
+            "activities_for_deal",
+            time_per_call(&deal_ids, |id| {
+                black_box(data.activities_for_deal(id));
+            }),
+        );
+
+        let start = Instant::now();
+        for tag in TAGS {
+            black_box(data.contacts_with_tag(tag));
+        }
+        report("contacts_with_tag (per tag)", start.elapsed() / TAGS.len() as u32);
+
+        // Recorded changes: the change itself plus its journal and history entry
+        let edits = &scan_ids[..20];
+        report(
+            "recorded edit",
+            time_per_call(edits, |id| {
+                data.record("Edit contact", |d| {
+                    d.contacts.update(id, |contact| contact.notes = Some("Called back".to_string()));
+                });
+            }),
+        );
+        report(
+            "recorded add",
+            time_per_call(edits, |id| {
+                data.record("Add contact", |d| {
+                    d.contacts.push(Contact::new("New".into(), id.into(), "new@example.com".into()));
+                });
+            }),
+        );
+        report(
+            "recorded delete (moves records)",
+            time_per_call(edits, |id| {
+                data.record("Delete contact", |d| {
+                    d.contacts.remove(id);
+                });
+            }),
+        );
+
+        let start = Instant::now();
+        for _ in edits {
+            data.undo();
+        }
+        report("undo delete (moves records)", start.elapsed() / edits.len() as u32);
+
+        println!();
+    }
+}
@@ -0,0 +1,372 @@
+//! Changes the app makes to its data.
+//!
+//! Each action validates its input, runs as one undoable step through
+//! [`AppData::record`] and logs field edits to the change history. Changes that
+//! webhooks can subscribe to are queued with [`AppData::emit`]. None of them
+//! save; callers persist the data afterwards.
+
+use crate::models::{
+    Activity, ActivityType, AppData, CloseInfo, Contact, ContactRelationship, Deal, DealStage, DeleteMode,
+    Frequency, Product, RecordKind, RecurrenceEnd, Settings, ValidationErrors, Webhook, WebhookEvent,
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+// ============================================================================
+// Contacts
+// ============================================================================
+
+pub fn add_contact(data: &mut AppData, contact: Contact) -> Result<(), ValidationErrors> {
+    contact.validate()?;
+    data.emit(WebhookEvent::ContactCreated, &contact);
+    data.record(format!("Add {}", contact.full_name()), |d| d.contacts.push(contact));
+    Ok(())
+}
+
+pub fn update_contact(data: &mut AppData, contact: Contact) -> Result<(), ValidationErrors> {
+    contact.validate()?;
+    if data.contact_by_id(&contact.id).is_some() {
+        data.emit(WebhookEvent::ContactUpdated, &contact);
+    }
+    data.record(format!("Edit {}", contact.full_name()), |d| {
+        let id = contact.id.clone();
+        if let Some(before) = d.contacts.update(&id, |existing| std::mem::replace(existing, contact)) {
+            d.audit_contact(&before);
+        }
+    });
+    Ok(())
+}
+
+pub fn delete_contact(data: &mut AppData, id: &str, mode: DeleteMode) {
+    let Some(contact) = data.contact_by_id(id).cloned() else {
+        return;
+    };
+    data.emit(WebhookEvent::ContactDeleted, &contact);
+    data.record_destructive(format!("Delete {}", contact.full_name()), |d| d.remove_contact(id, &mode));
+}
+
+/// Saves `merged` over the surviving contact and folds the duplicate into it.
+pub fn merge_contacts(data: &mut AppData, merged: Contact, duplicate_id: &str) -> Result<(), ValidationErrors> {
+    merged.validate()?;
+    data.record_destructive(format!("Merge into {}", merged.full_name()), |d| {
+        d.merge_contacts(merged, duplicate_id)
+    });
+    Ok(())
+}
+
+/// Links two contacts, unless they are the same contact or already linked this way.
+pub fn add_relationship(data: &mut AppData, relationship: ContactRelationship) {
+    if relationship.from_id == relationship.to_id {
+        return;
+    }
+    if data.relationships.iter().any(|r| r.duplicates(&relationship)) {
+        return;
+    }
+    data.record("Add relationship", |d| d.relationships.push(relationship));
+}
+
+pub fn delete_relationship(data: &mut AppData, id: &str) {
+    data.record_destructive("Remove relationship", |d| {
+        d.relationships.remove(id);
+    });
+}
+
+// ============================================================================
+// Deals
+// ============================================================================
+
+pub fn add_deal(data: &mut AppData, mut deal: Deal) -> Result<(), ValidationErrors> {
+    deal.validate()?;
+    data.record(format!("Add {}", deal.title), |d| {
+        deal.rank = d.next_rank(deal.stage);
+        d.deals.push(deal);
+    });
+    Ok(())
+}
+
+pub fn update_deal(data: &mut AppData, mut deal: Deal) -> Result<(), ValidationErrors> {
+    deal.validate()?;
+    let from = data.deal_by_id(&deal.id).map(|d| d.stage);
+    let id = deal.id.clone();
+    data.record(format!("Edit {}", deal.title), |d| {
+        let rank = d.next_rank(deal.stage);
+        let before = d.deals.update(&id, |existing| {
+            // A stage change from the edit form drops the deal at the bottom of its new column
+            if existing.stage != deal.stage {
+                deal.rank = rank;
+            }
+            // Reopening a deal discards the previous close details
+            if DealStage::active().contains(&deal.stage) {
+                deal.close = None;
+            }
+            std::mem::replace(existing, deal)
+        });
+        if let Some(before) = before {
+            d.audit_deal(&before);
+        }
+    });
+    emit_stage_change(data, &id, from);
+    Ok(())
+}
+
+fn set_deal_stage(data: &mut AppData, deal_id: &str, new_stage: DealStage) {
+    let rank = data.next_rank(new_stage);
+    let before = data.deals.update(deal_id, |deal| {
+        let before = deal.clone();
+        if deal.stage != new_stage {
+            deal.rank = rank;
+        }
+        deal.set_stage(new_stage);
+
+        // Update probability based on stage
+        deal.probability = match new_stage {
+            DealStage::Lead => 10,
+            DealStage::Qualified => 25,
+            DealStage::Proposal => 50,
+            DealStage::Negotiation => 75,
+            DealStage::Won => 100,
+            DealStage::Lost => 0,
+        };
+
+        // Reopening a deal discards the previous close details
+        if DealStage::active().contains(&new_stage) {
+            deal.close = None;
+        }
+        before
+    });
+    if let Some(before) = before {
+        data.audit_deal(&before);
+    }
+}
+
+/// Queues the stage change webhooks if the deal has left `from`, plus won or lost
+/// when it closed.
+fn emit_stage_change(data: &mut AppData, deal_id: &str, from: Option<DealStage>) {
+    let Some(deal) = data.deal_by_id(deal_id) else {
+        return;
+    };
+    let Some(from) = from.filter(|stage| *stage != deal.stage) else {
+        return;
+    };
+    let to = deal.stage;
+    let payload = json!({ "deal": deal, "from": from, "to": to });
+
+    data.emit(WebhookEvent::DealStageChanged, &payload);
+    match to {
+        DealStage::Won => data.emit(WebhookEvent::DealWon, &payload),
+        DealStage::Lost => data.emit(WebhookEvent::DealLost, &payload),
+        _ => {}
+    }
+}
+
+pub fn update_deal_stage(data: &mut AppData, deal_id: &str, new_stage: DealStage) {
+    let from = data.deal_by_id(deal_id).map(|d| d.stage);
+    data.record(format!("Move to {}", new_stage), |d| set_deal_stage(d, deal_id, new_stage));
+    emit_stage_change(data, deal_id, from);
+}
+
+pub fn close_deal(data: &mut AppData, deal_id: &str, stage: DealStage, close: CloseInfo) -> Result<(), ValidationErrors> {
+    close.validate()?;
+    let from = data.deal_by_id(deal_id).map(|d| d.stage);
+    data.record(format!("Mark as {}", stage), |d| {
+        let rank = d.next_rank(stage);
+        let before = d.deals.update(deal_id, |deal| {
+            let before = deal.clone();
+            if deal.stage != stage {
+                deal.rank = rank;
+            }
+            deal.set_stage(stage);
+            deal.probability = if stage == DealStage::Won { 100 } else { 0 };
+            deal.value = close.final_value;
+            deal.close = Some(close);
+            deal.updated_at = Utc::now();
+            before
+        });
+        if let Some(before) = before {
+            d.audit_deal(&before);
+        }
+    });
+    emit_stage_change(data, deal_id, from);
+    Ok(())
+}
+
+/// Moves a deal on the board: into an active `stage` if it isn't there yet,
+/// then to `index` within that column (the bottom when `None`).
+pub fn move_deal(data: &mut AppData, deal_id: &str, stage: DealStage, index: Option<usize>) {
+    let from = data.deal_by_id(deal_id).map(|d| d.stage);
+    data.record("Move deal", |d| {
+        if from.is_some_and(|s| s != stage) {
+            set_deal_stage(d, deal_id, stage);
+        }
+        d.rank_deal(deal_id, index);
+    });
+    emit_stage_change(data, deal_id, from);
+}
+
+pub fn delete_deal(data: &mut AppData, id: &str, mode: DeleteMode) {
+    let title = data.deal_by_id(id).map(|d| d.title.clone()).unwrap_or_default();
+    data.record_destructive(format!("Delete {}", title), |d| d.remove_deal(id, &mode));
+}
+
+// ============================================================================
+// Activities
+// ============================================================================
+
+pub fn add_activity(data: &mut AppData, activity: Activity) -> Result<(), ValidationErrors> {
+    activity.validate()?;
+    data.record(format!("Add {}", activity.title), |d| d.activities.push(activity));
+    Ok(())
+}
+
+pub fn update_activity(data: &mut AppData, activity: Activity) -> Result<(), ValidationErrors> {
+    activity.validate()?;
+    data.record(format!("Edit {}", activity.title), |d| {
+        let id = activity.id.clone();
+        if let Some(before) = d.activities.update(&id, |existing| std::mem::replace(existing, activity)) {
+            d.audit_activity(&before);
+        }
+    });
+    Ok(())
+}
+
+/// Flips an activity between open and completed. Completing a recurring activity
+/// schedules its next occurrence.
+pub fn toggle_activity_completed(data: &mut AppData, id: &str) {
+    let completed = data.record("Toggle completed", |d| {
+        let mut completed = None;
+        let before = d.activities.update(id, |activity| {
+            let before = activity.clone();
+            activity.completed = !activity.completed;
+            activity.updated_at = Utc::now();
+            if activity.completed {
+                completed = Some(activity.clone());
+            }
+            before
+        });
+        if let Some(before) = before {
+            d.audit_activity(&before);
+        }
+
+        // Completing a recurring activity schedules the next one, unless it already exists
+        if let Some(next) = completed.as_ref().and_then(|a| a.next_occurrence()) {
+            let exists = next.recurrence.as_ref().is_some_and(|rule| {
+                d.activities.iter().any(|a| {
+                    a.recurrence.as_ref().is_some_and(|r| {
+                        r.series_id == rule.series_id && r.occurrence >= rule.occurrence
+                    })
+                })
+            });
+            if !exists {
+                d.activities.push(next);
+            }
+        }
+        completed
+    });
+
+    if let Some(task) = completed.filter(|a| a.activity_type == ActivityType::Task) {
+        data.emit(WebhookEvent::TaskCompleted, &task);
+    }
+}
+
+/// Applies a new repeat rule to the open occurrences of a series.
+pub fn update_series(data: &mut AppData, series_id: &str, frequency: Frequency, interval: u32, end: RecurrenceEnd) {
+    data.record("Edit series", |d| {
+        d.activities.update_where(
+            |a| !a.completed && a.series_id() == Some(series_id),
+            |activity| {
+                if let Some(rule) = &mut activity.recurrence {
+                    rule.frequency = frequency;
+                    rule.interval = interval.max(1);
+                    rule.end = end;
+                    activity.updated_at = Utc::now();
+                }
+            },
+        );
+    });
+}
+
+/// Stops a series: open occurrences are removed, completed ones are kept as plain activities.
+pub fn cancel_series(data: &mut AppData, series_id: &str) {
+    data.record_destructive("Stop series", |d| {
+        d.activities
+            .retain(|a| a.completed || a.series_id() != Some(series_id));
+        d.activities
+            .update_where(|a| a.series_id() == Some(series_id), |activity| activity.recurrence = None);
+    });
+}
+
+/// Marks every reminder that has come due by `now` as sent and returns those
+/// activities for notification.
+///
+/// This is bookkeeping rather than a user change, so it isn't recorded for undo.
+pub fn take_due_reminders(data: &mut AppData, now: DateTime<Utc>) -> Vec<Activity> {
+    let mut due = Vec::new();
+    data.activities.update_where(
+        |activity| activity.reminder_due(now),
+        |activity| {
+            activity.reminder_sent = true;
+            due.push(activity.clone());
+        },
+    );
+    due
+}
+
+pub fn delete_activity(data: &mut AppData, id: &str) {
+    let title = data.activity_by_id(id).map(|a| a.title.clone()).unwrap_or_default();
+    data.record_destructive(format!("Delete {}", title), |d| d.remove_activity(id));
+}
+
+// ============================================================================
+// Webhooks
+// ============================================================================
+
+pub fn add_webhook(data: &mut AppData, webhook: Webhook) -> Result<(), ValidationErrors> {
+    webhook.validate()?;
+    data.record("Add webhook", |d| d.settings.webhooks.push(webhook));
+    Ok(())
+}
+
+pub fn toggle_webhook(data: &mut AppData, id: &str) {
+    data.record("Toggle webhook", |d| {
+        if let Some(webhook) = d.settings.webhooks.iter_mut().find(|w| w.id == id) {
+            webhook.enabled = !webhook.enabled;
+        }
+    });
+}
+
+pub fn delete_webhook(data: &mut AppData, id: &str) {
+    data.record_destructive("Delete webhook", |d| d.settings.webhooks.retain(|w| w.id != id));
+}
+
+// ============================================================================
+// Trash, Products & Settings
+// ============================================================================
+
+pub fn restore_record(data: &mut AppData, kind: RecordKind, id: &str) {
+    data.record(format!("Restore {}", kind.display_name().to_lowercase()), |d| d.restore(kind, id));
+}
+
+pub fn purge_record(data: &mut AppData, kind: RecordKind, id: &str) {
+    data.record_destructive(
+        format!("Delete {} forever", kind.display_name().to_lowercase()),
+        |d| d.purge(kind, id),
+    );
+}
+
+pub fn empty_trash(data: &mut AppData) {
+    data.record_destructive("Empty trash", |d| d.empty_trash());
+}
+
+pub fn add_product(data: &mut AppData, product: Product) {
+    data.record(format!("Add {}", product.name), |d| d.products.push(product));
+}
+
+pub fn delete_product(data: &mut AppData, id: &str) {
+    data.record_destructive("Delete product", |d| {
+        d.products.remove(id);
+    });
+}
+
+pub fn update_settings(data: &mut AppData, settings: Settings) {
+    data.record("Change settings", |d| d.settings = settings);
+}
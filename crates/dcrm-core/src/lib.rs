@@ -0,0 +1,9 @@
+//! Data model, storage and business logic behind DCRM.
+//!
+//! Nothing here depends on a UI: [`models`] holds the records, statistics and
+//! persistence, [`actions`] the changes the app makes to them (validated, undoable
+//! and audited), and [`search`] the fuzzy finder.
+
+pub mod actions;
+pub mod models;
+pub mod search;
@@ -0,0 +1,239 @@
+//! Field-level change history for contacts, deals and activities.
+
+use super::{Activity, AppData, Contact, Deal, RecordKind, csv_field};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Entries kept in the log before the oldest are dropped.
+const MAX_AUDIT_ENTRIES: usize = 10_000;
+
+/// One field's value before and after an edit, formatted for display.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Every field changed by a single edit of one record.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub id: String,
+    pub kind: RecordKind,
+    pub record_id: String,
+    pub at: DateTime<Utc>,
+    /// Who made the change: the name from settings, or the OS user.
+    pub actor: String,
+    pub changes: Vec<FieldChange>,
+}
+
+// ============================================================================
+// Diffing
+// ============================================================================
+
+#[derive(Default)]
+struct Diff(Vec<FieldChange>);
+
+impl Diff {
+    fn field(&mut self, field: &str, old: impl ToString, new: impl ToString) {
+        let (old, new) = (old.to_string(), new.to_string());
+        if old != new {
+            self.0.push(FieldChange {
+                field: field.to_string(),
+                old,
+                new,
+            });
+        }
+    }
+}
+
+fn optional(value: &Option<String>) -> String {
+    value.clone().unwrap_or_default()
+}
+
+fn date(value: Option<DateTime<Utc>>) -> String {
+    value.map(|d| d.format("%b %d, %Y").to_string()).unwrap_or_default()
+}
+
+fn contact_changes(before: &Contact, after: &Contact) -> Vec<FieldChange> {
+    let mut diff = Diff::default();
+    diff.field("First name", &before.first_name, &after.first_name);
+    diff.field("Last name", &before.last_name, &after.last_name);
+    diff.field("Email", &before.email, &after.email);
+    diff.field("Phone", optional(&before.phone), optional(&after.phone));
+    diff.field("Company", optional(&before.company), optional(&after.company));
+    diff.field("Position", optional(&before.position), optional(&after.position));
+    diff.field("Tags", before.tags.join(", "), after.tags.join(", "));
+    diff.field("Notes", optional(&before.notes), optional(&after.notes));
+    diff.0
+}
+
+impl AppData {
+    fn contact_name(&self, id: Option<&str>) -> String {
+        id.and_then(|id| self.contact_by_id(id))
+            .map(|c| c.full_name())
+            .unwrap_or_default()
+    }
+
+    fn deal_changes(&self, before: &Deal, after: &Deal) -> Vec<FieldChange> {
+        let stakeholders = |deal: &Deal| {
+            deal.participants
+                .iter()
+                .map(|p| format!("{} ({})", self.contact_name(Some(&p.contact_id)), p.role))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let line_items = |deal: &Deal| {
+            deal.line_items
+                .iter()
+                .map(|item| format!("{} × {}", item.quantity, item.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut diff = Diff::default();
+        diff.field("Title", &before.title, &after.title);
+        diff.field("Company", &before.company, &after.company);
+        diff.field("Value", before.value.format_precise(), after.value.format_precise());
+        diff.field("Stage", before.stage, after.stage);
+        diff.field("Probability", format!("{}%", before.probability), format!("{}%", after.probability));
+        diff.field("Expected close", date(before.expected_close), date(after.expected_close));
+        diff.field("Stakeholders", stakeholders(before), stakeholders(after));
+        diff.field("Line items", line_items(before), line_items(after));
+        diff.field(
+            "Close reason",
+            before.close.as_ref().map(|c| c.reason.clone()).unwrap_or_default(),
+            after.close.as_ref().map(|c| c.reason.clone()).unwrap_or_default(),
+        );
+        diff.field("Notes", optional(&before.notes), optional(&after.notes));
+        diff.0
+    }
+
+    fn activity_changes(&self, before: &Activity, after: &Activity) -> Vec<FieldChange> {
+        let deal_title = |id: &Option<String>| {
+            id.as_deref()
+                .and_then(|id| self.deal_by_id(id))
+                .map(|d| d.title.clone())
+                .unwrap_or_default()
+        };
+        let reminder = |a: &Activity| a.reminder.map(|r| r.to_string()).unwrap_or_default();
+        let repeats = |a: &Activity| a.recurrence.as_ref().map(|r| r.describe()).unwrap_or_default();
+
+        let mut diff = Diff::default();
+        diff.field("Type", before.activity_type, after.activity_type);
+        diff.field("Title", &before.title, &after.title);
+        diff.field("Description", optional(&before.description), optional(&after.description));
+        diff.field(
+            "Contact",
+            self.contact_name(before.contact_id.as_deref()),
+            self.contact_name(after.contact_id.as_deref()),
+        );
+        diff.field("Deal", deal_title(&before.deal_id), deal_title(&after.deal_id));
+        diff.field("Due", date(before.due_date), date(after.due_date));
+        diff.field("Reminder", reminder(before), reminder(after));
+        diff.field("Repeats", repeats(before), repeats(after));
+        diff.field(
+            "Completed",
+            if before.completed { "Yes" } else { "No" },
+            if after.completed { "Yes" } else { "No" },
+        );
+        diff.field(
+            "Details",
+            before.details.summary().unwrap_or_default(),
+            after.details.summary().unwrap_or_default(),
+        );
+        diff.0
+    }
+}
+
+// ============================================================================
+// Log
+// ============================================================================
+
+impl AppData {
+    /// Name recorded as the author of changes.
+    pub fn actor(&self) -> String {
+        let name = self.settings.user_name.trim();
+        if !name.is_empty() {
+            return name.to_string();
+        }
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "Unknown".to_string())
+    }
+
+    fn log_changes(&mut self, kind: RecordKind, record_id: &str, changes: Vec<FieldChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.audit_log.push(AuditEntry {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            record_id: record_id.to_string(),
+            at: Utc::now(),
+            actor: self.actor(),
+            changes,
+        });
+        let excess = self.audit_log.len().saturating_sub(MAX_AUDIT_ENTRIES);
+        self.audit_log.drain(..excess);
+    }
+
+    /// Logs how the stored contact with `before`'s id differs from `before`.
+    pub fn audit_contact(&mut self, before: &Contact) {
+        if let Some(after) = self.contact_by_id(&before.id) {
+            let changes = contact_changes(before, after);
+            self.log_changes(RecordKind::Contact, &before.id, changes);
+        }
+    }
+
+    /// Logs how the stored deal with `before`'s id differs from `before`.
+    pub fn audit_deal(&mut self, before: &Deal) {
+        if let Some(after) = self.deal_by_id(&before.id) {
+            let changes = self.deal_changes(before, after);
+            self.log_changes(RecordKind::Deal, &before.id, changes);
+        }
+    }
+
+    /// Logs how the stored activity with `before`'s id differs from `before`.
+    pub fn audit_activity(&mut self, before: &Activity) {
+        if let Some(after) = self.activity_by_id(&before.id) {
+            let changes = self.activity_changes(before, after);
+            self.log_changes(RecordKind::Activity, &before.id, changes);
+        }
+    }
+
+    /// Changes to one record, newest first.
+    pub fn audit_for(&self, kind: RecordKind, record_id: &str) -> Vec<AuditEntry> {
+        self.audit_log
+            .iter()
+            .rev()
+            .filter(|e| e.kind == kind && e.record_id == record_id)
+            .cloned()
+            .collect()
+    }
+}
+
+/// One row per changed field, oldest first.
+pub fn export_audit_csv(entries: &[AuditEntry]) -> String {
+    let mut csv = String::from("timestamp,actor,record_type,record_id,field,old_value,new_value\n");
+    let mut entries: Vec<&AuditEntry> = entries.iter().collect();
+    entries.sort_by_key(|e| e.at);
+
+    for entry in entries {
+        for change in &entry.changes {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                entry.at.to_rfc3339(),
+                csv_field(&entry.actor),
+                entry.kind,
+                csv_field(&entry.record_id),
+                csv_field(&change.field),
+                csv_field(&change.old),
+                csv_field(&change.new),
+            ));
+        }
+    }
+
+    csv
+}
@@ -0,0 +1,235 @@
+//! Finding contacts that describe the same person, and folding them into one.
+
+use super::{Activity, ActivityDetails, AppData, Contact, ContactRelationship, Deal};
+
+// ============================================================================
+// Matching
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchReason {
+    Email,
+    Phone,
+    Name,
+}
+
+impl MatchReason {
+    pub fn display_name(&self) -> &str {
+        match self {
+            MatchReason::Email => "Same email",
+            MatchReason::Phone => "Same phone",
+            MatchReason::Name => "Similar name and company",
+        }
+    }
+}
+
+impl std::fmt::Display for MatchReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// Two contacts that look like the same person.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicatePair {
+    pub first: Contact,
+    pub second: Contact,
+    pub reasons: Vec<MatchReason>,
+}
+
+/// Lower-cased, with any `+tag` dropped from the local part.
+fn normalize_email(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let local = local.split('+').next().unwrap_or(local);
+            format!("{}@{}", local, domain)
+        }
+        None => email,
+    }
+}
+
+/// The last ten digits, so the same number with or without a country code matches.
+fn normalize_phone(phone: &str) -> String {
+    let digits: Vec<char> = phone.chars().filter(char::is_ascii_digit).collect();
+    digits[digits.len().saturating_sub(10)..].iter().collect()
+}
+
+fn normalize_text(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Edit-distance similarity from 0.0 (nothing alike) to 1.0 (identical).
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+const NAME_THRESHOLD: f64 = 0.85;
+const COMPANY_THRESHOLD: f64 = 0.8;
+
+fn match_reasons(a: &Contact, b: &Contact) -> Vec<MatchReason> {
+    let mut reasons = Vec::new();
+
+    let email = normalize_email(&a.email);
+    if !email.is_empty() && email == normalize_email(&b.email) {
+        reasons.push(MatchReason::Email);
+    }
+
+    if let (Some(pa), Some(pb)) = (&a.phone, &b.phone) {
+        let phone = normalize_phone(pa);
+        if phone.len() >= 7 && phone == normalize_phone(pb) {
+            reasons.push(MatchReason::Phone);
+        }
+    }
+
+    // A missing company doesn't rule a match out; imports often leave it blank
+    let name_a = normalize_text(&a.full_name());
+    let name_b = normalize_text(&b.full_name());
+    let same_company = match (&a.company, &b.company) {
+        (Some(ca), Some(cb)) => similarity(&normalize_text(ca), &normalize_text(cb)) >= COMPANY_THRESHOLD,
+        _ => true,
+    };
+    if !name_a.is_empty() && similarity(&name_a, &name_b) >= NAME_THRESHOLD && same_company {
+        reasons.push(MatchReason::Name);
+    }
+
+    reasons
+}
+
+// ============================================================================
+// Merging
+// ============================================================================
+
+impl AppData {
+    /// Every pair of live contacts with at least one matching signal.
+    pub fn find_duplicates(&self) -> Vec<DuplicatePair> {
+        let mut pairs = Vec::new();
+        for (i, a) in self.contacts.iter().enumerate() {
+            for b in &self.contacts[i + 1..] {
+                let reasons = match_reasons(a, b);
+                if !reasons.is_empty() {
+                    pairs.push(DuplicatePair {
+                        first: a.clone(),
+                        second: b.clone(),
+                        reasons,
+                    });
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Replaces the surviving contact with `merged` and points everything that
+    /// referenced `duplicate_id` at it, including records in the trash. The
+    /// duplicate is removed for good.
+    pub fn merge_contacts(&mut self, merged: Contact, duplicate_id: &str) {
+        let survivor_id = merged.id.clone();
+        if survivor_id == duplicate_id {
+            return;
+        }
+
+        let repoint_deal = |deal: &mut Deal| {
+            if deal.has_participant(&survivor_id) {
+                deal.participants.retain(|p| p.contact_id != duplicate_id);
+            } else if let Some(p) = deal.participants.iter_mut().find(|p| p.contact_id == duplicate_id) {
+                p.contact_id = survivor_id.clone();
+            }
+        };
+        let on_deal = |deal: &Deal| deal.has_participant(duplicate_id);
+        self.deals.update_where(on_deal, repoint_deal);
+        self.trash.deals.update_where(on_deal, repoint_deal);
+
+        let repoint_activity = |activity: &mut Activity| {
+            if activity.contact_id.as_deref() == Some(duplicate_id) {
+                activity.contact_id = Some(survivor_id.clone());
+            }
+            if let ActivityDetails::Meeting(meeting) = &mut activity.details {
+                for attendee in &mut meeting.attendees {
+                    if attendee == duplicate_id {
+                        *attendee = survivor_id.clone();
+                    }
+                }
+                let mut seen = Vec::new();
+                meeting.attendees.retain(|id| {
+                    let first = !seen.contains(id);
+                    seen.push(id.clone());
+                    first
+                });
+            }
+        };
+        let on_activity = |activity: &Activity| {
+            activity.contact_id.as_deref() == Some(duplicate_id)
+                || matches!(&activity.details, ActivityDetails::Meeting(m) if m.attendees.iter().any(|a| a == duplicate_id))
+        };
+        self.activities.update_where(on_activity, repoint_activity);
+        self.trash.activities.update_where(on_activity, repoint_activity);
+
+        self.relationships.update_where(
+            |r| r.from_id == duplicate_id || r.to_id == duplicate_id,
+            |relationship| {
+                if relationship.from_id == duplicate_id {
+                    relationship.from_id = survivor_id.clone();
+                }
+                if relationship.to_id == duplicate_id {
+                    relationship.to_id = survivor_id.clone();
+                }
+            },
+        );
+        // Re-pointing can turn a link between the two into a self-link, or repeat one
+        let mut kept: Vec<ContactRelationship> = Vec::new();
+        self.relationships.retain(|relationship| {
+            let keep = relationship.from_id != relationship.to_id
+                && !kept.iter().any(|r| r.duplicates(relationship));
+            if keep {
+                kept.push(relationship.clone());
+            }
+            keep
+        });
+
+        self.contacts.remove(duplicate_id);
+        self.trash.contacts.remove(duplicate_id);
+        self.contacts.update(&survivor_id, |existing| *existing = merged);
+    }
+}
+
+/// Tags from both contacts without repeats, keeping the survivor's order first.
+pub fn merge_tags(a: &[String], b: &[String]) -> Vec<String> {
+    let mut tags = a.to_vec();
+    for tag in b {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+/// Both sets of notes, separated by a blank line when both exist.
+pub fn merge_notes(a: Option<&str>, b: Option<&str>) -> Option<String> {
+    match (a.filter(|n| !n.trim().is_empty()), b.filter(|n| !n.trim().is_empty())) {
+        (Some(a), Some(b)) if a.trim() == b.trim() => Some(a.to_string()),
+        (Some(a), Some(b)) => Some(format!("{}\n\n{}", a, b)),
+        (Some(n), None) | (None, Some(n)) => Some(n.to_string()),
+        (None, None) => None,
+    }
+}
@@ -0,0 +1,224 @@
+//! Undo and redo.
+//!
+//! While a change made through [`AppData::record`] runs, every record list
+//! journals what it does to each record: an insert or removal with the record and
+//! its position, or an edit with the record before and after. Undo reverts those
+//! operations latest first, redo makes them again in order, so neither has to copy
+//! or compare the untouched records.
+
+use super::index::Op;
+use super::{Activity, AppData, Contact, ContactRelationship, Deal, Product, Record, Records, Settings};
+use std::collections::HashSet;
+
+/// Steps kept on each stack before the oldest are dropped.
+const MAX_HISTORY: usize = 100;
+
+// ============================================================================
+// Changes
+// ============================================================================
+
+/// Everything one step did.
+#[derive(Debug, Clone, Default)]
+struct Changes {
+    contacts: Vec<Op<Contact>>,
+    deals: Vec<Op<Deal>>,
+    activities: Vec<Op<Activity>>,
+    products: Vec<Op<Product>>,
+    relationships: Vec<Op<ContactRelationship>>,
+    trashed_contacts: Vec<Op<Contact>>,
+    trashed_deals: Vec<Op<Deal>>,
+    trashed_activities: Vec<Op<Activity>>,
+    /// Settings before and after, if they changed.
+    settings: Option<(Settings, Settings)>,
+}
+
+impl Changes {
+    fn start(data: &mut AppData) {
+        data.contacts.start_journal();
+        data.deals.start_journal();
+        data.activities.start_journal();
+        data.products.start_journal();
+        data.relationships.start_journal();
+        data.trash.contacts.start_journal();
+        data.trash.deals.start_journal();
+        data.trash.activities.start_journal();
+    }
+
+    fn take(data: &mut AppData) -> Self {
+        Self {
+            contacts: data.contacts.take_journal(),
+            deals: data.deals.take_journal(),
+            activities: data.activities.take_journal(),
+            products: data.products.take_journal(),
+            relationships: data.relationships.take_journal(),
+            trashed_contacts: data.trash.contacts.take_journal(),
+            trashed_deals: data.trash.deals.take_journal(),
+            trashed_activities: data.trash.activities.take_journal(),
+            settings: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+            && self.deals.is_empty()
+            && self.activities.is_empty()
+            && self.products.is_empty()
+            && self.relationships.is_empty()
+            && self.trashed_contacts.is_empty()
+            && self.trashed_deals.is_empty()
+            && self.trashed_activities.is_empty()
+            && self.settings.is_none()
+    }
+
+    /// Reverts the step when `undo` is set and replays it otherwise, logging field
+    /// changes to edited records in the audit log.
+    fn apply(&self, data: &mut AppData, undo: bool) {
+        let contacts = touched(&data.contacts, &self.contacts);
+        let deals = touched(&data.deals, &self.deals);
+        let activities = touched(&data.activities, &self.activities);
+
+        fn run<T: Record>(list: &mut Records<T>, ops: &[Op<T>], undo: bool) {
+            if undo { list.revert(ops) } else { list.replay(ops) }
+        }
+        run(&mut data.contacts, &self.contacts, undo);
+        run(&mut data.deals, &self.deals, undo);
+        run(&mut data.activities, &self.activities, undo);
+        run(&mut data.products, &self.products, undo);
+        run(&mut data.relationships, &self.relationships, undo);
+        run(&mut data.trash.contacts, &self.trashed_contacts, undo);
+        run(&mut data.trash.deals, &self.trashed_deals, undo);
+        run(&mut data.trash.activities, &self.trashed_activities, undo);
+        if let Some((before, after)) = &self.settings {
+            data.settings = if undo { before } else { after }.clone();
+        }
+
+        for contact in &contacts {
+            data.audit_contact(contact);
+        }
+        for deal in &deals {
+            data.audit_deal(deal);
+        }
+        for activity in &activities {
+            data.audit_activity(activity);
+        }
+    }
+}
+
+/// Current copies of the records `ops` touch.
+fn touched<T: Record>(list: &Records<T>, ops: &[Op<T>]) -> Vec<T> {
+    let mut seen = HashSet::new();
+    ops.iter()
+        .filter(|op| seen.insert(op.id()))
+        .filter_map(|op| list.get(op.id()).cloned())
+        .collect()
+}
+
+// ============================================================================
+// History
+// ============================================================================
+
+/// One undoable step.
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub label: String,
+    /// Deletes and merges, which get an "Undo" toast.
+    pub destructive: bool,
+    changes: Changes,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+    /// Bumped whenever a new step is recorded.
+    revision: u64,
+    /// Set while a step runs, so steps recorded inside it join it.
+    recording: bool,
+}
+
+impl History {
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|c| c.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|c| c.label.as_str())
+    }
+
+    /// The most recently recorded step, while nothing has been undone since.
+    pub fn last(&self) -> Option<&Command> {
+        if self.redo.is_empty() { self.undo.last() } else { None }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn push(&mut self, command: Command) {
+        self.undo.push(command);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.revision += 1;
+    }
+}
+
+impl AppData {
+    /// Runs `change` as one undoable step.
+    pub fn record<R>(&mut self, label: impl Into<String>, change: impl FnOnce(&mut AppData) -> R) -> R {
+        self.record_step(label.into(), false, change)
+    }
+
+    /// Like [`AppData::record`], for steps that remove data.
+    pub fn record_destructive<R>(
+        &mut self,
+        label: impl Into<String>,
+        change: impl FnOnce(&mut AppData) -> R,
+    ) -> R {
+        self.record_step(label.into(), true, change)
+    }
+
+    fn record_step<R>(&mut self, label: String, destructive: bool, change: impl FnOnce(&mut AppData) -> R) -> R {
+        if self.history.recording {
+            return change(self);
+        }
+
+        let settings = self.settings.clone();
+        Changes::start(self);
+        self.history.recording = true;
+        let result = change(self);
+        self.history.recording = false;
+        let mut changes = Changes::take(self);
+        if settings != self.settings {
+            changes.settings = Some((settings, self.settings.clone()));
+        }
+
+        if !changes.is_empty() {
+            self.history.push(Command {
+                label,
+                destructive,
+                changes,
+            });
+        }
+        result
+    }
+
+    /// Reverts the latest step and returns its label.
+    pub fn undo(&mut self) -> Option<String> {
+        let command = self.history.undo.pop()?;
+        command.changes.apply(self, true);
+        let label = command.label.clone();
+        self.history.redo.push(command);
+        Some(label)
+    }
+
+    /// Replays the latest undone step and returns its label.
+    pub fn redo(&mut self) -> Option<String> {
+        let command = self.history.redo.pop()?;
+        command.changes.apply(self, false);
+        let label = command.label.clone();
+        self.history.undo.push(command);
+        Some(label)
+    }
+}
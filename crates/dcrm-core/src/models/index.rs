@@ -0,0 +1,433 @@
+//! Record lists with id and link lookups.
+//!
+//! Every list in [`AppData`] is a [`Records`]: the records in display order, plus
+//! maps from ids to positions and from links (a deal's participants, an activity's
+//! contact and deal, a contact's tags) to the ids filed under them. The records
+//! can be read like a slice, but only changed through methods that keep the maps
+//! in step, so lookups never go stale. While a step is being recorded, those
+//! methods also journal each change for undo.
+
+use super::{Activity, AppData, Contact, ContactRelationship, Deal, Product};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+
+/// A kind of reference a record can be looked up by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Link {
+    Contact,
+    Deal,
+    /// Keyed by lower-cased tag.
+    Tag,
+}
+
+/// Something stored in a [`Records`] list.
+pub trait Record: Clone + PartialEq {
+    fn id(&self) -> &str;
+
+    /// The keys this record is filed under.
+    fn links(&self) -> Vec<(Link, String)> {
+        Vec::new()
+    }
+}
+
+impl Record for Contact {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn links(&self) -> Vec<(Link, String)> {
+        self.tags.iter().map(|tag| (Link::Tag, tag.to_ascii_lowercase())).collect()
+    }
+}
+
+impl Record for Deal {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn links(&self) -> Vec<(Link, String)> {
+        self.participants.iter().map(|p| (Link::Contact, p.contact_id.clone())).collect()
+    }
+}
+
+impl Record for Activity {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn links(&self) -> Vec<(Link, String)> {
+        let contact = self.contact_id.iter().map(|id| (Link::Contact, id.clone()));
+        let deal = self.deal_id.iter().map(|id| (Link::Deal, id.clone()));
+        contact.chain(deal).collect()
+    }
+}
+
+impl Record for Product {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Record for ContactRelationship {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+// ============================================================================
+// Records
+// ============================================================================
+
+/// One change to a [`Records`] list, with what's needed to revert or replay it.
+#[derive(Debug, Clone)]
+pub(super) enum Op<T> {
+    Insert { at: usize, item: T },
+    Remove { at: usize, item: T },
+    Update { before: T, after: T },
+}
+
+impl<T: Record> Op<T> {
+    pub(super) fn id(&self) -> &str {
+        match self {
+            Op::Insert { item, .. } | Op::Remove { item, .. } => item.id(),
+            Op::Update { after, .. } => after.id(),
+        }
+    }
+}
+
+/// An ordered list of records, indexed by id and by link.
+///
+/// Serializes as a plain list.
+#[derive(Debug, Clone)]
+pub struct Records<T> {
+    items: Vec<T>,
+    positions: HashMap<String, usize>,
+    /// Ids filed under each link key.
+    links: HashMap<Link, HashMap<String, HashSet<String>>>,
+    /// Changes made since [`Records::start_journal`], while a step is recorded.
+    journal: Option<Vec<Op<T>>>,
+}
+
+impl<T> Default for Records<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            positions: HashMap::new(),
+            links: HashMap::new(),
+            journal: None,
+        }
+    }
+}
+
+impl<T: Record> From<Vec<T>> for Records<T> {
+    fn from(items: Vec<T>) -> Self {
+        let mut records = Self {
+            items,
+            ..Self::default()
+        };
+        records.reposition(0);
+        let filed: Vec<(String, Vec<(Link, String)>)> =
+            records.items.iter().map(|item| (item.id().to_string(), item.links())).collect();
+        for (id, links) in filed {
+            records.file(&id, links);
+        }
+        records
+    }
+}
+
+impl<T> Deref for Records<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Records<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Records<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Serialize> Serialize for Records<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.items.serialize(serializer)
+    }
+}
+
+impl<'de, T: Record + Deserialize<'de>> Deserialize<'de> for Records<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Records::from)
+    }
+}
+
+impl<T: Record> Records<T> {
+    pub fn get(&self, id: &str) -> Option<&T> {
+        self.positions.get(id).map(|&i| &self.items[i])
+    }
+
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.positions.get(id).copied()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.positions.contains_key(id)
+    }
+
+    /// Records filed under `key`, in list order.
+    pub fn linked(&self, link: Link, key: &str) -> Vec<&T> {
+        let Some(ids) = self.links.get(&link).and_then(|keys| keys.get(key)) else {
+            return Vec::new();
+        };
+        let mut positions: Vec<usize> = ids.iter().filter_map(|id| self.position(id)).collect();
+        positions.sort_unstable();
+        positions.into_iter().map(|i| &self.items[i]).collect()
+    }
+
+    /// Ids of the records filed under `key`, in list order.
+    pub fn linked_ids(&self, link: Link, key: &str) -> Vec<String> {
+        self.linked(link, key).into_iter().map(|item| item.id().to_string()).collect()
+    }
+
+    pub fn push(&mut self, item: T) {
+        let at = self.items.len();
+        self.insert(at, item);
+    }
+
+    /// Inserts `item` at `index`, or at the end if the list is shorter.
+    pub fn insert(&mut self, index: usize, item: T) {
+        let index = index.min(self.items.len());
+        if let Some(journal) = &mut self.journal {
+            journal.push(Op::Insert { at: index, item: item.clone() });
+        }
+        self.file(item.id(), item.links());
+        self.items.insert(index, item);
+        self.reposition(index);
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<T> {
+        let index = self.position(id)?;
+        let item = self.items.remove(index);
+        if let Some(journal) = &mut self.journal {
+            journal.push(Op::Remove { at: index, item: item.clone() });
+        }
+        self.positions.remove(id);
+        self.unfile(&item);
+        self.reposition(index);
+        Some(item)
+    }
+
+    /// Removes every record `keep` rejects and returns them.
+    pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) -> Vec<T> {
+        let Some(first) = self.items.iter().position(|item| !keep(item)) else {
+            return Vec::new();
+        };
+        let mut removed = Vec::new();
+        let mut ops = Vec::new();
+        let tail = self.items.split_off(first);
+        for (i, item) in tail.into_iter().enumerate() {
+            if i > 0 && keep(&item) {
+                self.items.push(item);
+            } else {
+                self.positions.remove(item.id());
+                self.unfile(&item);
+                if self.journal.is_some() {
+                    ops.push(Op::Remove { at: first + i, item: item.clone() });
+                }
+                removed.push(item);
+            }
+        }
+        // Journaled last to first, so reverting puts each back at its original position
+        if let Some(journal) = &mut self.journal {
+            journal.extend(ops.into_iter().rev());
+        }
+        self.reposition(first);
+        removed
+    }
+
+    pub fn clear(&mut self) -> Vec<T> {
+        self.retain(|_| false)
+    }
+
+    /// Changes the record with `id` in place, refiling it if its links changed.
+    pub fn update<R>(&mut self, id: &str, change: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let index = self.position(id)?;
+        Some(self.change_at(index, change))
+    }
+
+    /// Changes every record passing `filter`.
+    pub fn update_where(&mut self, filter: impl Fn(&T) -> bool, mut change: impl FnMut(&mut T)) {
+        for index in 0..self.items.len() {
+            if filter(&self.items[index]) {
+                self.change_at(index, &mut change);
+            }
+        }
+    }
+
+    /// Starts journaling changes, dropping any earlier journal.
+    pub(super) fn start_journal(&mut self) {
+        self.journal = Some(Vec::new());
+    }
+
+    /// Stops journaling and returns the changes made since it started.
+    pub(super) fn take_journal(&mut self) -> Vec<Op<T>> {
+        self.journal.take().unwrap_or_default()
+    }
+
+    /// Undoes journaled changes, latest first.
+    pub(super) fn revert(&mut self, ops: &[Op<T>]) {
+        for op in ops.iter().rev() {
+            match op {
+                Op::Insert { item, .. } => {
+                    self.remove(item.id());
+                }
+                Op::Remove { at, item } => self.insert(*at, item.clone()),
+                Op::Update { before, after } => {
+                    self.update(after.id(), |item| *item = before.clone());
+                }
+            }
+        }
+    }
+
+    /// Makes journaled changes again, in order.
+    pub(super) fn replay(&mut self, ops: &[Op<T>]) {
+        for op in ops {
+            match op {
+                Op::Insert { at, item } => self.insert(*at, item.clone()),
+                Op::Remove { item, .. } => {
+                    self.remove(item.id());
+                }
+                Op::Update { before, after } => {
+                    self.update(before.id(), |item| *item = after.clone());
+                }
+            }
+        }
+    }
+
+    /// Runs `change` on the record at `index`, journaling it and refiling its links.
+    fn change_at<R>(&mut self, index: usize, change: impl FnOnce(&mut T) -> R) -> R {
+        let id = self.items[index].id().to_string();
+        let links = self.items[index].links();
+        let before = self.journal.is_some().then(|| self.items[index].clone());
+        let result = change(&mut self.items[index]);
+        if let Some(before) = before
+            && before != self.items[index]
+            && let Some(journal) = &mut self.journal
+        {
+            journal.push(Op::Update {
+                before,
+                after: self.items[index].clone(),
+            });
+        }
+        self.refile(index, &id, links);
+        result
+    }
+
+    fn refile(&mut self, index: usize, id: &str, before: Vec<(Link, String)>) {
+        let new_id = self.items[index].id().to_string();
+        let after = self.items[index].links();
+        if new_id == id && before == after {
+            return;
+        }
+        if new_id != id {
+            self.positions.remove(id);
+            self.positions.insert(new_id.clone(), index);
+        }
+        for (link, key) in before {
+            self.unlink(link, &key, id);
+        }
+        self.file(&new_id, after);
+    }
+
+    /// Points the ids from `from` onwards at their current positions.
+    fn reposition(&mut self, from: usize) {
+        for (i, item) in self.items.iter().enumerate().skip(from) {
+            match self.positions.get_mut(item.id()) {
+                Some(position) => *position = i,
+                None => {
+                    self.positions.insert(item.id().to_string(), i);
+                }
+            }
+        }
+    }
+
+    fn file(&mut self, id: &str, links: Vec<(Link, String)>) {
+        for (link, key) in links {
+            self.links.entry(link).or_default().entry(key).or_default().insert(id.to_string());
+        }
+    }
+
+    fn unfile(&mut self, item: &T) {
+        for (link, key) in item.links() {
+            self.unlink(link, &key, item.id());
+        }
+    }
+
+    fn unlink(&mut self, link: Link, key: &str, id: &str) {
+        let Some(keys) = self.links.get_mut(&link) else {
+            return;
+        };
+        if let Some(ids) = keys.get_mut(key) {
+            ids.remove(id);
+            if ids.is_empty() {
+                keys.remove(key);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Lookups
+// ============================================================================
+
+impl AppData {
+    pub fn contact_by_id(&self, id: &str) -> Option<&Contact> {
+        self.contacts.get(id)
+    }
+
+    pub fn deal_by_id(&self, id: &str) -> Option<&Deal> {
+        self.deals.get(id)
+    }
+
+    pub fn activity_by_id(&self, id: &str) -> Option<&Activity> {
+        self.activities.get(id)
+    }
+
+    pub fn deals_for_contact(&self, contact_id: &str) -> Vec<&Deal> {
+        self.deals.linked(Link::Contact, contact_id)
+    }
+
+    pub fn activities_for_contact(&self, contact_id: &str) -> Vec<&Activity> {
+        self.activities.linked(Link::Contact, contact_id)
+    }
+
+    pub fn activities_for_deal(&self, deal_id: &str) -> Vec<&Activity> {
+        self.activities.linked(Link::Deal, deal_id)
+    }
+
+    /// Contacts carrying `tag`, compared case-insensitively.
+    pub fn contacts_with_tag(&self, tag: &str) -> Vec<&Contact> {
+        self.contacts.linked(Link::Tag, &tag.to_ascii_lowercase())
+    }
+
+    /// Every tag in use, in first-seen order.
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.contacts.iter().flat_map(|c| &c.tags) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+}
@@ -0,0 +1,2581 @@
+use chrono::{DateTime, Duration, Local, Months, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+mod audit;
+mod duplicates;
+mod history;
+mod index;
+mod money;
+mod summary;
+mod validation;
+mod webhooks;
+
+pub use audit::{AuditEntry, export_audit_csv};
+pub use duplicates::{DuplicatePair, merge_notes, merge_tags};
+pub use history::History;
+pub use index::{Link, Record, Records};
+pub use money::{Currency, Money};
+pub use summary::{StageTotal, Summary};
+pub use validation::ValidationErrors;
+pub use webhooks::{Attempt, Delivery, DeliveryStatus, Event, Webhook, WebhookEvent};
+
+// ============================================================================
+// Contact Model
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Contact {
+    pub id: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub company: Option<String>,
+    pub position: Option<String>,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set while the contact sits in the trash.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Contact {
+    pub fn new(first_name: String, last_name: String, email: String) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            first_name,
+            last_name,
+            email,
+            phone: None,
+            company: None,
+            position: None,
+            tags: Vec::new(),
+            notes: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+    }
+
+    pub fn initials(&self) -> String {
+        let first = self.first_name.chars().next().unwrap_or('?');
+        let last = self.last_name.chars().next().unwrap_or('?');
+
+        format!("{}{}", first, last).to_uppercase()
+    }
+
+    /// Rough rank derived from the job title, lower is more senior. Used to order org charts.
+    pub fn seniority(&self) -> u8 {
+        let position = self.position.as_deref().unwrap_or("").to_lowercase();
+        let ranks: [(&[&str], u8); 6] = [
+            (&["founder", "ceo", "president", "owner"], 0),
+            (&["chief", "cto", "cfo", "coo", "cio"], 1),
+            (&["vp", "vice president"], 2),
+            (&["director", "head"], 3),
+            (&["manager", "lead"], 4),
+            (&["assistant"], 6),
+        ];
+
+        ranks
+            .iter()
+            .find(|(keywords, _)| keywords.iter().any(|k| position.contains(k)))
+            .map(|(_, rank)| *rank)
+            .unwrap_or(5)
+    }
+}
+
+impl Default for Contact {
+    fn default() -> Self {
+        Self::new(String::new(), String::new(), String::new())
+    }
+}
+
+// ============================================================================
+// Contact Relationships
+// ============================================================================
+
+/// How `from` relates to `to` in a [`ContactRelationship`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RelationshipKind {
+    ManagerOf,
+    AssistantOf,
+    ReferredBy,
+    Colleague,
+}
+
+impl RelationshipKind {
+    pub fn all() -> Vec<RelationshipKind> {
+        vec![
+            RelationshipKind::ManagerOf,
+            RelationshipKind::AssistantOf,
+            RelationshipKind::ReferredBy,
+            RelationshipKind::Colleague,
+        ]
+    }
+
+    /// Label as seen from the `from` contact, e.g. "Manager of".
+    pub fn display_name(&self) -> &str {
+        match self {
+            RelationshipKind::ManagerOf => "Manager of",
+            RelationshipKind::AssistantOf => "Assistant to",
+            RelationshipKind::ReferredBy => "Referred by",
+            RelationshipKind::Colleague => "Colleague of",
+        }
+    }
+
+    /// Label as seen from the `to` contact, e.g. "Reports to".
+    pub fn inverse_name(&self) -> &str {
+        match self {
+            RelationshipKind::ManagerOf => "Reports to",
+            RelationshipKind::AssistantOf => "Assisted by",
+            RelationshipKind::ReferredBy => "Referred",
+            RelationshipKind::Colleague => "Colleague of",
+        }
+    }
+
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, RelationshipKind::Colleague)
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        Self::all()
+            .into_iter()
+            .find(|k| k.display_name() == name)
+            .unwrap_or(RelationshipKind::Colleague)
+    }
+}
+
+impl std::fmt::Display for RelationshipKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// A directed link between two contacts. Each link is stored once and read from both
+/// ends, so the inverse side never drifts out of sync.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContactRelationship {
+    pub id: String,
+    pub from_id: String,
+    pub to_id: String,
+    pub kind: RelationshipKind,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ContactRelationship {
+    pub fn new(from_id: String, to_id: String, kind: RelationshipKind) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            from_id,
+            to_id,
+            kind,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn involves(&self, contact_id: &str) -> bool {
+        self.from_id == contact_id || self.to_id == contact_id
+    }
+
+    /// Same pair and kind, in either direction for symmetric kinds.
+    pub fn duplicates(&self, other: &ContactRelationship) -> bool {
+        if self.kind != other.kind {
+            return false;
+        }
+        let same = self.from_id == other.from_id && self.to_id == other.to_id;
+        let reversed = self.from_id == other.to_id && self.to_id == other.from_id;
+        same || (self.kind.is_symmetric() && reversed)
+    }
+}
+
+/// A contact and everyone reporting to them, as built by [`AppData::org_chart`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrgNode {
+    pub contact: Contact,
+    pub reports: Vec<OrgNode>,
+}
+
+// ============================================================================
+// Deal Model
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DealStage {
+    Lead,
+    Qualified,
+    Proposal,
+    Negotiation,
+    Won,
+    Lost,
+}
+
+impl DealStage {
+    pub fn all() -> Vec<DealStage> {
+        vec![
+            DealStage::Lead,
+            DealStage::Qualified,
+            DealStage::Proposal,
+            DealStage::Negotiation,
+            DealStage::Won,
+            DealStage::Lost,
+        ]
+    }
+
+    pub fn active() -> Vec<DealStage> {
+        vec![
+            DealStage::Lead,
+            DealStage::Qualified,
+            DealStage::Proposal,
+            DealStage::Negotiation,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            DealStage::Lead => "Lead",
+            DealStage::Qualified => "Qualified",
+            DealStage::Proposal => "Proposal",
+            DealStage::Negotiation => "Negotiation",
+            DealStage::Won => "Won",
+            DealStage::Lost => "Lost",
+        }
+    }
+
+    pub fn color(&self) -> &str {
+        match self {
+            DealStage::Lead => "#3b82f6",
+            DealStage::Qualified => "#8b5cf6",
+            DealStage::Proposal => "#f59e0b",
+            DealStage::Negotiation => "#ec4899",
+            DealStage::Won => "#10b981",
+            DealStage::Lost => "#ef4444",
+        }
+    }
+
+    /// Position of the stage in the pipeline. Won and Lost share the last slot.
+    pub fn order(&self) -> u8 {
+        match self {
+            DealStage::Lead => 0,
+            DealStage::Qualified => 1,
+            DealStage::Proposal => 2,
+            DealStage::Negotiation => 3,
+            DealStage::Won | DealStage::Lost => 4,
+        }
+    }
+
+    pub fn badge_class(&self) -> &str {
+        match self {
+            DealStage::Lead => "badge-lead",
+            DealStage::Qualified => "badge-qualified",
+            DealStage::Proposal => "badge-proposal",
+            DealStage::Negotiation => "badge-negotiation",
+            DealStage::Won => "badge-won",
+            DealStage::Lost => "badge-lost",
+        }
+    }
+}
+
+impl std::fmt::Display for DealStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StageChange {
+    pub from: Option<DealStage>,
+    pub to: DealStage,
+    pub at: DateTime<Utc>,
+}
+
+impl StageChange {
+    /// A move back towards the start of the pipeline, including reopening a closed deal.
+    pub fn is_regression(&self) -> bool {
+        self.from.is_some_and(|from| self.to.order() < from.order())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum DealRole {
+    Champion,
+    DecisionMaker,
+    EconomicBuyer,
+    Influencer,
+    Blocker,
+    Legal,
+    Procurement,
+    #[default]
+    Other,
+}
+
+impl DealRole {
+    pub fn all() -> Vec<DealRole> {
+        vec![
+            DealRole::Champion,
+            DealRole::DecisionMaker,
+            DealRole::EconomicBuyer,
+            DealRole::Influencer,
+            DealRole::Blocker,
+            DealRole::Legal,
+            DealRole::Procurement,
+            DealRole::Other,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            DealRole::Champion => "Champion",
+            DealRole::DecisionMaker => "Decision Maker",
+            DealRole::EconomicBuyer => "Economic Buyer",
+            DealRole::Influencer => "Influencer",
+            DealRole::Blocker => "Blocker",
+            DealRole::Legal => "Legal",
+            DealRole::Procurement => "Procurement",
+            DealRole::Other => "Other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        Self::all()
+            .into_iter()
+            .find(|r| r.display_name() == name)
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for DealRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DealParticipant {
+    pub contact_id: String,
+    #[serde(default)]
+    pub role: DealRole,
+}
+
+// ============================================================================
+// Product Catalog & Line Items
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BillingCycle {
+    #[default]
+    OneTime,
+    Monthly,
+    Annual,
+}
+
+impl BillingCycle {
+    pub fn all() -> Vec<BillingCycle> {
+        vec![
+            BillingCycle::OneTime,
+            BillingCycle::Monthly,
+            BillingCycle::Annual,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            BillingCycle::OneTime => "One-time",
+            BillingCycle::Monthly => "Monthly",
+            BillingCycle::Annual => "Annual",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Monthly" => BillingCycle::Monthly,
+            "Annual" => BillingCycle::Annual,
+            _ => BillingCycle::OneTime,
+        }
+    }
+}
+
+impl std::fmt::Display for BillingCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Product {
+    pub id: String,
+    pub name: String,
+    pub sku: Option<String>,
+    pub unit_price: Decimal,
+    pub billing: BillingCycle,
+}
+
+impl Product {
+    pub fn new(name: String, unit_price: Decimal, billing: BillingCycle) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            sku: None,
+            unit_price,
+            billing,
+        }
+    }
+}
+
+/// A priced line on a deal. Name and price are copied from the catalog so later
+/// price book changes don't rewrite existing deals.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LineItem {
+    pub id: String,
+    pub product_id: Option<String>,
+    pub name: String,
+    pub quantity: u32,
+    /// Price per unit, in the currency of the deal the item belongs to.
+    pub unit_price: Decimal,
+    /// Discount in percent, 0-100.
+    pub discount: Decimal,
+    pub billing: BillingCycle,
+}
+
+impl LineItem {
+    pub fn new(name: String, unit_price: Decimal, billing: BillingCycle) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            product_id: None,
+            name,
+            quantity: 1,
+            unit_price,
+            discount: Decimal::ZERO,
+            billing,
+        }
+    }
+
+    pub fn from_product(product: &Product) -> Self {
+        let mut item = Self::new(product.name.clone(), product.unit_price, product.billing);
+        item.product_id = Some(product.id.clone());
+        item
+    }
+
+    /// Price per billing period after discount.
+    pub fn total(&self) -> Decimal {
+        let discount = self.discount.clamp(Decimal::ZERO, Decimal::ONE_HUNDRED);
+        Decimal::from(self.quantity) * self.unit_price * (Decimal::ONE_HUNDRED - discount)
+            / Decimal::ONE_HUNDRED
+    }
+
+    /// Contribution to the deal value: one-time total or first-year recurring revenue.
+    pub fn contract_value(&self) -> Decimal {
+        match self.billing {
+            BillingCycle::OneTime | BillingCycle::Annual => self.total(),
+            BillingCycle::Monthly => self.total() * Decimal::from(12),
+        }
+    }
+
+    pub fn mrr(&self) -> Decimal {
+        match self.billing {
+            BillingCycle::OneTime => Decimal::ZERO,
+            BillingCycle::Monthly => self.total(),
+            BillingCycle::Annual => self.total() / Decimal::from(12),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CloseInfo {
+    pub reason: String,
+    pub note: Option<String>,
+    pub closed_at: DateTime<Utc>,
+    pub final_value: Money,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Deal {
+    pub id: String,
+    pub title: String,
+    /// Stakeholders on the deal. The first one is treated as the primary contact.
+    #[serde(default)]
+    pub participants: Vec<DealParticipant>,
+    /// Single contact reference written by versions before participants existed.
+    #[serde(default, rename = "contact_id", skip_serializing)]
+    legacy_contact_id: Option<String>,
+    pub company: String,
+    pub value: Money,
+    /// Currency code written by versions that stored `value` as a plain number.
+    #[serde(default, rename = "currency", skip_serializing)]
+    legacy_currency: Option<Currency>,
+    pub stage: DealStage,
+    /// Position within the stage's Kanban column, lowest first.
+    #[serde(default)]
+    pub rank: u32,
+    pub probability: u8,
+    pub expected_close: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub stage_history: Vec<StageChange>,
+    #[serde(default)]
+    pub close: Option<CloseInfo>,
+    #[serde(default)]
+    pub line_items: Vec<LineItem>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set while the deal sits in the trash.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Deal {
+    pub fn new(title: String, company: String, value: Money) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title,
+            participants: Vec::new(),
+            legacy_contact_id: None,
+            company,
+            value,
+            legacy_currency: None,
+            stage: DealStage::Lead,
+            rank: 0,
+            probability: 10,
+            expected_close: None,
+            notes: None,
+            stage_history: vec![StageChange {
+                from: None,
+                to: DealStage::Lead,
+                at: now,
+            }],
+            close: None,
+            line_items: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    /// Moves the deal to `stage`, recording the transition. No-op if the stage is unchanged.
+    pub fn set_stage(&mut self, stage: DealStage) {
+        if self.stage == stage {
+            return;
+        }
+
+        let now = Utc::now();
+        self.stage_history.push(StageChange {
+            from: Some(self.stage),
+            to: stage,
+            at: now,
+        });
+        self.stage = stage;
+        self.updated_at = now;
+    }
+
+    pub fn primary_contact_id(&self) -> Option<&str> {
+        self.participants.first().map(|p| p.contact_id.as_str())
+    }
+
+    pub fn has_participant(&self, contact_id: &str) -> bool {
+        self.participants.iter().any(|p| p.contact_id == contact_id)
+    }
+
+    pub fn role_of(&self, contact_id: &str) -> Option<DealRole> {
+        self.participants
+            .iter()
+            .find(|p| p.contact_id == contact_id)
+            .map(|p| p.role)
+    }
+
+    /// Adds `contact_id` with `role`, or updates the role if the contact is already on the deal.
+    pub fn add_participant(&mut self, contact_id: String, role: DealRole) {
+        match self.participants.iter_mut().find(|p| p.contact_id == contact_id) {
+            Some(existing) => existing.role = role,
+            None => self.participants.push(DealParticipant { contact_id, role }),
+        }
+    }
+
+    /// Derives `value` from the line items. Deals without line items keep their manual
+    /// value, and closed deals the value they closed at.
+    pub fn recalculate_value(&mut self) {
+        if let Some(close) = &self.close
+            && !DealStage::active().contains(&self.stage)
+        {
+            self.value = close.final_value;
+        } else if !self.line_items.is_empty() {
+            let total = self.line_items.iter().map(|i| i.contract_value()).sum();
+            self.value = Money::new(total, self.value.currency);
+        }
+    }
+
+    pub fn mrr(&self) -> Money {
+        let total = self.line_items.iter().map(|i| i.mrr()).sum();
+        Money::new(total, self.value.currency)
+    }
+
+    pub fn arr(&self) -> Money {
+        self.mrr().scale(Decimal::from(12))
+    }
+
+    pub fn stage_entered_at(&self) -> DateTime<Utc> {
+        self.stage_history
+            .last()
+            .map(|c| c.at)
+            .unwrap_or(self.created_at)
+    }
+
+    pub fn time_in_current_stage(&self) -> Duration {
+        Utc::now() - self.stage_entered_at()
+    }
+
+    /// Total time spent in each stage the deal has visited, including the current one.
+    pub fn time_in_stages(&self) -> Vec<(DealStage, Duration)> {
+        let mut totals: Vec<(DealStage, Duration)> = Vec::new();
+        let now = Utc::now();
+
+        for (i, change) in self.stage_history.iter().enumerate() {
+            let until = self
+                .stage_history
+                .get(i + 1)
+                .map(|next| next.at)
+                .unwrap_or(now);
+            let spent = until - change.at;
+
+            match totals.iter_mut().find(|(s, _)| *s == change.to) {
+                Some((_, total)) => *total += spent,
+                None => totals.push((change.to, spent)),
+            }
+        }
+
+        totals
+    }
+
+    /// Length of each visit the deal has finished, in order. Visits left in the same
+    /// instant, like the steps of a multi-stage jump, are skipped.
+    pub fn completed_stage_visits(&self) -> Vec<(DealStage, Duration)> {
+        self.stage_history
+            .windows(2)
+            .map(|pair| (pair[0].to, pair[1].at - pair[0].at))
+            .filter(|(_, spent)| *spent > Duration::zero())
+            .collect()
+    }
+
+    pub fn regressions(&self) -> Vec<&StageChange> {
+        self.stage_history
+            .iter()
+            .filter(|c| c.is_regression())
+            .collect()
+    }
+
+    pub fn weighted_value(&self) -> Money {
+        self.value
+            .scale(Decimal::from(self.probability) / Decimal::ONE_HUNDRED)
+    }
+
+    pub fn format_value(&self) -> String {
+        self.value.format_compact()
+    }
+}
+
+impl Default for Deal {
+    fn default() -> Self {
+        Self::new(String::new(), String::new(), Money::default())
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, mins) = (minutes / 1440, (minutes % 1440) / 60, minutes % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+// ============================================================================
+// Activity Model
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ActivityType {
+    Note,
+    Call,
+    Email,
+    Meeting,
+    Task,
+}
+
+impl ActivityType {
+    pub fn all() -> Vec<ActivityType> {
+        vec![
+            ActivityType::Task,
+            ActivityType::Call,
+            ActivityType::Email,
+            ActivityType::Meeting,
+            ActivityType::Note,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            ActivityType::Note => "Note",
+            ActivityType::Call => "Call",
+            ActivityType::Email => "Email",
+            ActivityType::Meeting => "Meeting",
+            ActivityType::Task => "Task",
+        }
+    }
+
+    pub fn icon(&self) -> &str {
+        match self {
+            ActivityType::Note => "📝",
+            ActivityType::Call => "📞",
+            ActivityType::Email => "✉️",
+            ActivityType::Meeting => "👥",
+            ActivityType::Task => "✓",
+        }
+    }
+}
+
+impl std::fmt::Display for ActivityType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// How long before the due date a reminder fires.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Reminder {
+    AtDueTime,
+    FifteenMinutes,
+    OneHour,
+    OneDay,
+}
+
+impl Reminder {
+    pub fn all() -> Vec<Reminder> {
+        vec![
+            Reminder::AtDueTime,
+            Reminder::FifteenMinutes,
+            Reminder::OneHour,
+            Reminder::OneDay,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            Reminder::AtDueTime => "At due time",
+            Reminder::FifteenMinutes => "15 minutes before",
+            Reminder::OneHour => "1 hour before",
+            Reminder::OneDay => "1 day before",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|r| r.display_name() == name)
+    }
+
+    pub fn offset(&self) -> Duration {
+        match self {
+            Reminder::AtDueTime => Duration::zero(),
+            Reminder::FifteenMinutes => Duration::minutes(15),
+            Reminder::OneHour => Duration::hours(1),
+            Reminder::OneDay => Duration::days(1),
+        }
+    }
+}
+
+impl std::fmt::Display for Reminder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Direction {
+    Inbound,
+    #[default]
+    Outbound,
+}
+
+impl Direction {
+    pub fn all() -> Vec<Direction> {
+        vec![Direction::Outbound, Direction::Inbound]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            Direction::Inbound => "Inbound",
+            Direction::Outbound => "Outbound",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Inbound" => Direction::Inbound,
+            _ => Direction::Outbound,
+        }
+    }
+}
+
+impl std::fmt::Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CallOutcome {
+    Connected,
+    Voicemail,
+    NoAnswer,
+}
+
+impl CallOutcome {
+    pub fn all() -> Vec<CallOutcome> {
+        vec![
+            CallOutcome::Connected,
+            CallOutcome::Voicemail,
+            CallOutcome::NoAnswer,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            CallOutcome::Connected => "Connected",
+            CallOutcome::Voicemail => "Voicemail",
+            CallOutcome::NoAnswer => "No answer",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|o| o.display_name() == name)
+    }
+}
+
+impl std::fmt::Display for CallOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CallDetails {
+    pub direction: Direction,
+    pub duration_minutes: Option<u32>,
+    pub outcome: Option<CallOutcome>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MeetingDetails {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub location: Option<String>,
+    /// Contact ids of the people attending.
+    pub attendees: Vec<String>,
+}
+
+impl MeetingDetails {
+    pub fn duration(&self) -> Option<Duration> {
+        let (start, end) = (self.start?, self.end?);
+        (end > start).then(|| end - start)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct EmailDetails {
+    pub direction: Direction,
+    pub subject: String,
+}
+
+/// Totals across the type-specific activity fields, for reporting.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ActivityStats {
+    pub calls: usize,
+    pub calls_by_outcome: Vec<(CallOutcome, usize)>,
+    pub talk_minutes: u32,
+    pub meetings: usize,
+    pub meeting_time: Duration,
+    pub emails_sent: usize,
+    pub emails_received: usize,
+}
+
+impl ActivityStats {
+    /// Share of calls with a recorded outcome that connected, as a percentage.
+    pub fn connect_rate(&self) -> Option<f64> {
+        let logged: usize = self.calls_by_outcome.iter().map(|(_, n)| n).sum();
+        let connected = self
+            .calls_by_outcome
+            .iter()
+            .find(|(o, _)| *o == CallOutcome::Connected)
+            .map(|(_, n)| *n)
+            .unwrap_or(0);
+
+        if logged == 0 {
+            None
+        } else {
+            Some(connected as f64 / logged as f64 * 100.0)
+        }
+    }
+}
+
+/// Fields that only make sense for one activity type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum ActivityDetails {
+    #[default]
+    None,
+    Call(CallDetails),
+    Meeting(MeetingDetails),
+    Email(EmailDetails),
+}
+
+impl ActivityDetails {
+    /// One-line description for lists, e.g. `Outbound • 12 min • Connected`.
+    pub fn summary(&self) -> Option<String> {
+        let parts: Vec<String> = match self {
+            ActivityDetails::None => Vec::new(),
+            ActivityDetails::Call(call) => {
+                let mut parts = vec![call.direction.to_string()];
+                if let Some(minutes) = call.duration_minutes {
+                    parts.push(format!("{} min", minutes));
+                }
+                if let Some(outcome) = call.outcome {
+                    parts.push(outcome.to_string());
+                }
+                parts
+            }
+            ActivityDetails::Meeting(meeting) => {
+                let mut parts = Vec::new();
+                if let Some(start) = meeting.start {
+                    let start = start.with_timezone(&Local);
+                    match meeting.end.filter(|end| *end > start) {
+                        Some(end) => parts.push(format!(
+                            "{}–{}",
+                            start.format("%b %d, %H:%M"),
+                            end.with_timezone(&Local).format("%H:%M")
+                        )),
+                        None => parts.push(start.format("%b %d, %H:%M").to_string()),
+                    }
+                }
+                if let Some(location) = &meeting.location {
+                    parts.push(location.clone());
+                }
+                if !meeting.attendees.is_empty() {
+                    parts.push(format!("{} attendees", meeting.attendees.len()));
+                }
+                parts
+            }
+            ActivityDetails::Email(email) => {
+                let mut parts = vec![email.direction.to_string()];
+                if !email.subject.is_empty() {
+                    parts.push(email.subject.clone());
+                }
+                parts
+            }
+        };
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" • "))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Frequency {
+    pub fn all() -> Vec<Frequency> {
+        vec![Frequency::Daily, Frequency::Weekly, Frequency::Monthly]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            Frequency::Daily => "Daily",
+            Frequency::Weekly => "Weekly",
+            Frequency::Monthly => "Monthly",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|f| f.display_name() == name)
+    }
+
+    fn unit(&self) -> &str {
+        match self {
+            Frequency::Daily => "days",
+            Frequency::Weekly => "weeks",
+            Frequency::Monthly => "months",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum RecurrenceEnd {
+    #[default]
+    Never,
+    OnDate(DateTime<Utc>),
+    AfterCount(u32),
+}
+
+/// Repeat rule shared by every occurrence of a series.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Repeat every `interval` days, weeks or months.
+    pub interval: u32,
+    pub end: RecurrenceEnd,
+    pub series_id: String,
+    /// 1-based position of this occurrence in the series.
+    pub occurrence: u32,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32, end: RecurrenceEnd) -> Self {
+        Self {
+            frequency,
+            interval: interval.max(1),
+            end,
+            series_id: Uuid::new_v4().to_string(),
+            occurrence: 1,
+        }
+    }
+
+    pub fn advance(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let n = self.interval.max(1);
+        match self.frequency {
+            Frequency::Daily => Some(from + Duration::days(n as i64)),
+            Frequency::Weekly => Some(from + Duration::weeks(n as i64)),
+            Frequency::Monthly => from.checked_add_months(Months::new(n)),
+        }
+    }
+
+    /// Short summary, e.g. `Every 2 weeks, 6 times`.
+    pub fn describe(&self) -> String {
+        let every = if self.interval <= 1 {
+            self.frequency.display_name().to_string()
+        } else {
+            format!("Every {} {}", self.interval, self.frequency.unit())
+        };
+
+        match self.end {
+            RecurrenceEnd::Never => every,
+            RecurrenceEnd::OnDate(date) => format!("{} until {}", every, date.format("%b %d, %Y")),
+            RecurrenceEnd::AfterCount(count) => format!("{}, {} times", every, count),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Activity {
+    pub id: String,
+    pub activity_type: ActivityType,
+    pub title: String,
+    pub description: Option<String>,
+    pub contact_id: Option<String>,
+    pub deal_id: Option<String>,
+    pub completed: bool,
+    pub due_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reminder: Option<Reminder>,
+    /// Set once the reminder notification has been shown.
+    #[serde(default)]
+    pub reminder_sent: bool,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub details: ActivityDetails,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Set while the activity sits in the trash.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Activity {
+    pub fn new(activity_type: ActivityType, title: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            activity_type,
+            title,
+            description: None,
+            contact_id: None,
+            deal_id: None,
+            completed: false,
+            due_date: None,
+            reminder: None,
+            reminder_sent: false,
+            recurrence: None,
+            details: ActivityDetails::None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    pub fn format_date(&self) -> String {
+        self.created_at.format("%b %d, %Y").to_string()
+    }
+
+    pub fn format_time(&self) -> String {
+        self.created_at.format("%H:%M").to_string()
+    }
+
+    /// Due date in local time, e.g. `Mar 04, 14:30`.
+    pub fn format_due(&self) -> Option<String> {
+        self.due_date
+            .map(|d| d.with_timezone(&Local).format("%b %d, %H:%M").to_string())
+    }
+
+    pub fn is_overdue(&self) -> bool {
+        !self.completed && self.due_date.is_some_and(|d| d < Utc::now())
+    }
+
+    pub fn reminder_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.due_date? - self.reminder?.offset())
+    }
+
+    pub fn reminder_due(&self, now: DateTime<Utc>) -> bool {
+        !self.completed && !self.reminder_sent && self.reminder_at().is_some_and(|at| at <= now)
+    }
+
+    pub fn series_id(&self) -> Option<&str> {
+        self.recurrence.as_ref().map(|r| r.series_id.as_str())
+    }
+
+    /// The following occurrence of a recurring activity, or `None` once the series has ended.
+    pub fn next_occurrence(&self) -> Option<Activity> {
+        let rule = self.recurrence.as_ref()?;
+        let due = rule.advance(self.due_date.unwrap_or(self.created_at))?;
+
+        match rule.end {
+            RecurrenceEnd::OnDate(until) if due > until => return None,
+            RecurrenceEnd::AfterCount(count) if rule.occurrence >= count => return None,
+            _ => {}
+        }
+
+        let now = Utc::now();
+        let mut next = self.clone();
+        next.id = Uuid::new_v4().to_string();
+        next.completed = false;
+        next.due_date = Some(due);
+        next.reminder_sent = false;
+        next.created_at = now;
+        next.updated_at = now;
+        if let Some(rule) = &mut next.recurrence {
+            rule.occurrence += 1;
+        }
+        Some(next)
+    }
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self::new(ActivityType::Note, String::new())
+    }
+}
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub won_reasons: Vec<String>,
+    pub lost_reasons: Vec<String>,
+    pub reporting_currency: Currency,
+    /// Units of the reporting currency per unit of each other currency.
+    pub exchange_rates: HashMap<Currency, Decimal>,
+    /// Days a record stays in the trash before it is purged; 0 keeps it forever.
+    pub trash_retention_days: u32,
+    /// Name recorded in the change history; the OS user when empty.
+    pub user_name: String,
+    pub webhooks: Vec<Webhook>,
+}
+
+impl Settings {
+    pub fn rate(&self, currency: Currency) -> Decimal {
+        if currency == self.reporting_currency {
+            Decimal::ONE
+        } else {
+            self.exchange_rates
+                .get(&currency)
+                .copied()
+                .unwrap_or(Decimal::ONE)
+        }
+    }
+
+    pub fn to_reporting(&self, money: Money) -> Money {
+        money.convert(self.reporting_currency, self.rate(money.currency))
+    }
+
+    /// Converts between any two currencies through the reporting currency.
+    pub fn convert(&self, money: Money, to: Currency) -> Money {
+        if money.currency == to {
+            return money;
+        }
+        let to_rate = self.rate(to);
+        if to_rate <= Decimal::ZERO {
+            return money;
+        }
+        money.convert(to, self.rate(money.currency) / to_rate)
+    }
+
+    /// Adds up `amounts` in `currency`, converting any that are in another one.
+    pub fn total(&self, amounts: impl IntoIterator<Item = Money>, currency: Currency) -> Money {
+        amounts.into_iter().fold(Money::zero(currency), |total, money| {
+            let converted = self.convert(money, currency);
+            Money::new(total.amount + converted.amount, currency)
+        })
+    }
+
+    /// Switches the reporting currency, rebasing the stored rates so conversions stay the same.
+    pub fn set_reporting_currency(&mut self, currency: Currency) {
+        let base = self.rate(currency);
+        if base <= Decimal::ZERO {
+            return;
+        }
+
+        let rates = Currency::all()
+            .into_iter()
+            .filter(|c| *c != currency)
+            .map(|c| (c, self.rate(c) / base))
+            .collect();
+        self.exchange_rates = rates;
+        self.reporting_currency = currency;
+    }
+
+    pub fn close_reasons(&self, stage: DealStage) -> &[String] {
+        match stage {
+            DealStage::Lost => &self.lost_reasons,
+            _ => &self.won_reasons,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            won_reasons: vec![
+                "Product fit".into(),
+                "Price".into(),
+                "Relationship".into(),
+                "Timing".into(),
+                "Other".into(),
+            ],
+            lost_reasons: vec![
+                "Price too high".into(),
+                "Lost to competitor".into(),
+                "No budget".into(),
+                "No decision".into(),
+                "Missing features".into(),
+                "Other".into(),
+            ],
+            reporting_currency: Currency::USD,
+            exchange_rates: HashMap::from([
+                (Currency::EUR, Decimal::new(108, 2)),
+                (Currency::GBP, Decimal::new(127, 2)),
+                (Currency::INR, Decimal::new(12, 3)),
+            ]),
+            trash_retention_days: 30,
+            user_name: String::new(),
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+// ============================================================================
+// App Data Store
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppData {
+    pub contacts: Records<Contact>,
+    pub deals: Records<Deal>,
+    pub activities: Records<Activity>,
+    #[serde(default)]
+    pub products: Records<Product>,
+    #[serde(default)]
+    pub relationships: Records<ContactRelationship>,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(default)]
+    pub trash: Trash,
+    #[serde(default)]
+    pub audit_log: Vec<AuditEntry>,
+    /// Recent webhook deliveries, oldest first.
+    #[serde(default)]
+    pub deliveries: Vec<Delivery>,
+    /// Undo and redo stacks for this session.
+    #[serde(skip)]
+    pub history: History,
+    /// Webhook events waiting for [`AppData::take_deliveries`].
+    #[serde(skip)]
+    outbox: Vec<Event>,
+    /// The data file as it was when this data was read from or saved to it.
+    #[serde(skip)]
+    file_stamp: Option<FileStamp>,
+}
+
+impl AppData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Statistics, all in the reporting currency
+    pub fn total_pipeline_value(&self) -> Money {
+        let values = self
+            .deals
+            .iter()
+            .filter(|d| {
+                matches!(
+                    d.stage,
+                    DealStage::Lead
+                        | DealStage::Qualified
+                        | DealStage::Proposal
+                        | DealStage::Negotiation
+                )
+            })
+            .map(|d| self.reporting_value(d));
+        self.settings.total(values, self.settings.reporting_currency)
+    }
+
+    pub fn weighted_pipeline_value(&self) -> Money {
+        let values = self
+            .deals
+            .iter()
+            .filter(|d| {
+                matches!(
+                    d.stage,
+                    DealStage::Lead
+                        | DealStage::Qualified
+                        | DealStage::Proposal
+                        | DealStage::Negotiation
+                )
+            })
+            .map(|d| self.settings.to_reporting(d.weighted_value()));
+        self.settings.total(values, self.settings.reporting_currency)
+    }
+
+    pub fn won_deals_value(&self) -> Money {
+        self.stage_value(DealStage::Won)
+    }
+
+    /// Deal value converted to the reporting currency.
+    pub fn reporting_value(&self, deal: &Deal) -> Money {
+        self.settings.to_reporting(deal.value)
+    }
+
+    pub fn stage_value(&self, stage: DealStage) -> Money {
+        let values = self
+            .deals_by_stage(stage)
+            .into_iter()
+            .map(|d| self.reporting_value(d));
+        self.settings.total(values, self.settings.reporting_currency)
+    }
+
+    /// Deals in `stage`, in board order.
+    pub fn deals_by_stage(&self, stage: DealStage) -> Vec<&Deal> {
+        let mut deals: Vec<&Deal> = self.deals.iter().filter(|d| d.stage == stage).collect();
+        deals.sort_by_key(|d| d.rank);
+        deals
+    }
+
+    /// Rank that places a deal at the bottom of `stage`'s column.
+    pub fn next_rank(&self, stage: DealStage) -> u32 {
+        self.deals
+            .iter()
+            .filter(|d| d.stage == stage)
+            .map(|d| d.rank + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Moves a deal to `index` among the other deals in its column (or to the
+    /// bottom when `None`) and renumbers the column.
+    pub fn rank_deal(&mut self, deal_id: &str, index: Option<usize>) {
+        let Some(stage) = self.deal_by_id(deal_id).map(|d| d.stage) else {
+            return;
+        };
+
+        let mut order: Vec<String> = self
+            .deals_by_stage(stage)
+            .into_iter()
+            .filter(|d| d.id != deal_id)
+            .map(|d| d.id.clone())
+            .collect();
+        let index = index.unwrap_or(order.len()).min(order.len());
+        order.insert(index, deal_id.to_string());
+
+        self.deals.update_where(
+            |d| d.stage == stage,
+            |deal| {
+                if let Some(rank) = order.iter().position(|id| *id == deal.id) {
+                    deal.rank = rank as u32;
+                }
+            },
+        );
+    }
+
+    pub fn active_deals_count(&self) -> usize {
+        self.deals
+            .iter()
+            .filter(|d| {
+                matches!(
+                    d.stage,
+                    DealStage::Lead
+                        | DealStage::Qualified
+                        | DealStage::Proposal
+                        | DealStage::Negotiation
+                )
+            })
+            .count()
+    }
+
+    pub fn activity_stats(&self) -> ActivityStats {
+        let mut stats = ActivityStats {
+            calls_by_outcome: CallOutcome::all().into_iter().map(|o| (o, 0)).collect(),
+            ..Default::default()
+        };
+
+        for activity in &self.activities {
+            match &activity.details {
+                ActivityDetails::Call(call) => {
+                    stats.calls += 1;
+                    stats.talk_minutes += call.duration_minutes.unwrap_or(0);
+                    if let Some((_, count)) = stats
+                        .calls_by_outcome
+                        .iter_mut()
+                        .find(|(o, _)| Some(*o) == call.outcome)
+                    {
+                        *count += 1;
+                    }
+                }
+                ActivityDetails::Meeting(meeting) => {
+                    stats.meetings += 1;
+                    stats.meeting_time += meeting.duration().unwrap_or_else(Duration::zero);
+                }
+                ActivityDetails::Email(email) => match email.direction {
+                    Direction::Outbound => stats.emails_sent += 1,
+                    Direction::Inbound => stats.emails_received += 1,
+                },
+                ActivityDetails::None => {}
+            }
+        }
+
+        stats
+    }
+
+    pub fn overdue_count(&self) -> usize {
+        self.activities.iter().filter(|a| a.is_overdue()).count()
+    }
+
+    pub fn pending_tasks_count(&self) -> usize {
+        self.activities
+            .iter()
+            .filter(|a| a.activity_type == ActivityType::Task && !a.completed)
+            .count()
+    }
+
+    pub fn product_by_id(&self, id: &str) -> Option<&Product> {
+        self.products.get(id)
+    }
+
+    /// Relationships touching `contact_id`, as (relationship id, label from this contact's side, other contact).
+    pub fn relationships_for(&self, contact_id: &str) -> Vec<(String, String, Contact)> {
+        self.relationships
+            .iter()
+            .filter_map(|r| {
+                let (label, other_id) = if r.from_id == contact_id {
+                    (r.kind.display_name(), &r.to_id)
+                } else if r.to_id == contact_id {
+                    (r.kind.inverse_name(), &r.from_id)
+                } else {
+                    return None;
+                };
+                self.contact_by_id(other_id)
+                    .map(|c| (r.id.clone(), label.to_string(), c.clone()))
+            })
+            .collect()
+    }
+
+    pub fn manager_of(&self, contact_id: &str) -> Option<&Contact> {
+        self.relationships
+            .iter()
+            .find(|r| r.kind == RelationshipKind::ManagerOf && r.to_id == contact_id)
+            .and_then(|r| self.contact_by_id(&r.from_id))
+    }
+
+    pub fn direct_reports(&self, contact_id: &str) -> Vec<&Contact> {
+        self.relationships
+            .iter()
+            .filter(|r| r.kind == RelationshipKind::ManagerOf && r.from_id == contact_id)
+            .filter_map(|r| self.contact_by_id(&r.to_id))
+            .collect()
+    }
+
+    /// Reporting tree for everyone at `company`. Contacts whose manager is outside the
+    /// company (or unknown) become roots; siblings are ordered by seniority.
+    pub fn org_chart(&self, company: &str) -> Vec<OrgNode> {
+        let members: Vec<&Contact> = self
+            .contacts
+            .iter()
+            .filter(|c| c.company.as_deref() == Some(company))
+            .collect();
+        let is_member = |id: &str| members.iter().any(|c| c.id == id);
+
+        let mut visited: Vec<String> = Vec::new();
+        let mut roots: Vec<&Contact> = members
+            .iter()
+            .copied()
+            .filter(|c| !self.manager_of(&c.id).is_some_and(|m| is_member(&m.id)))
+            .collect();
+        roots.sort_by_key(|c| c.seniority());
+
+        // Members stuck in a reporting cycle have no root; they are listed at the top level
+        let mut chart = Vec::new();
+        for contact in roots.into_iter().chain(members.iter().copied()) {
+            if !visited.contains(&contact.id) {
+                chart.push(self.org_node(contact, &is_member, &mut visited));
+            }
+        }
+        chart
+    }
+
+    fn org_node(
+        &self,
+        contact: &Contact,
+        is_member: &dyn Fn(&str) -> bool,
+        visited: &mut Vec<String>,
+    ) -> OrgNode {
+        visited.push(contact.id.clone());
+
+        let mut direct = self.direct_reports(&contact.id);
+        direct.retain(|c| is_member(&c.id));
+        direct.sort_by_key(|c| c.seniority());
+
+        let mut reports = Vec::new();
+        for report in direct {
+            if !visited.contains(&report.id) {
+                reports.push(self.org_node(report, is_member, visited));
+            }
+        }
+
+        OrgNode {
+            contact: contact.clone(),
+            reports,
+        }
+    }
+
+    /// Contacts taking part in a deal, with their roles, in participant order.
+    pub fn deal_stakeholders(&self, deal: &Deal) -> Vec<(Contact, DealRole)> {
+        deal.participants
+            .iter()
+            .filter_map(|p| self.contact_by_id(&p.contact_id).map(|c| (c.clone(), p.role)))
+            .collect()
+    }
+
+    /// Average time deals spend in `stage`, across the visits that have ended. Deals
+    /// still in the stage don't count until they move on.
+    pub fn average_time_in_stage(&self, stage: DealStage) -> Option<Duration> {
+        let durations: Vec<Duration> = self
+            .deals
+            .iter()
+            .flat_map(|d| d.completed_stage_visits())
+            .filter(|(s, _)| *s == stage)
+            .map(|(_, d)| d)
+            .collect();
+
+        if durations.is_empty() {
+            return None;
+        }
+
+        let total: Duration = durations.iter().fold(Duration::zero(), |acc, d| acc + *d);
+        Some(total / durations.len() as i32)
+    }
+
+    pub fn stage_regressions(&self) -> Vec<(&Deal, &StageChange)> {
+        self.deals
+            .iter()
+            .flat_map(|d| d.regressions().into_iter().map(move |c| (d, c)))
+            .collect()
+    }
+
+    /// Share of closed deals that were won, as a percentage.
+    pub fn win_rate(&self) -> Option<f64> {
+        let won = self.deals_by_stage(DealStage::Won).len();
+        let lost = self.deals_by_stage(DealStage::Lost).len();
+
+        if won + lost == 0 {
+            None
+        } else {
+            Some(won as f64 / (won + lost) as f64 * 100.0)
+        }
+    }
+
+    /// Closed deals in `stage` grouped by close reason, as (reason, count, value), largest count first.
+    pub fn close_reason_breakdown(&self, stage: DealStage) -> Vec<(String, usize, Money)> {
+        let mut breakdown: Vec<(String, usize, Money)> = Vec::new();
+
+        for deal in self.deals_by_stage(stage) {
+            let reason = deal
+                .close
+                .as_ref()
+                .map(|c| c.reason.clone())
+                .unwrap_or_else(|| "Unspecified".to_string());
+
+            match breakdown.iter_mut().find(|(r, _, _)| *r == reason) {
+                Some((_, count, value)) => {
+                    *count += 1;
+                    *value = self.settings.total([*value, deal.value], self.settings.reporting_currency);
+                }
+                None => breakdown.push((reason, 1, self.reporting_value(deal))),
+            }
+        }
+
+        breakdown.sort_by_key(|(_, count, _)| std::cmp::Reverse(*count));
+        breakdown
+    }
+
+    /// Fills in data that older save files don't carry.
+    pub fn normalize(&mut self) {
+        self.deals.update_where(|_| true, |deal| {
+            // Files from before `Money` stored plain numbers next to a deal-wide currency,
+            // so an untagged close amount was in that currency too
+            if let Some(currency) = deal.legacy_currency.take() {
+                deal.value.currency = currency;
+                if let Some(close) = &mut deal.close {
+                    close.final_value.currency = currency;
+                }
+            }
+            if let Some(contact_id) = deal.legacy_contact_id.take()
+                && !deal.has_participant(&contact_id)
+            {
+                deal.participants.insert(
+                    0,
+                    DealParticipant {
+                        contact_id,
+                        role: DealRole::default(),
+                    },
+                );
+            }
+
+            if deal.stage_history.is_empty() {
+                deal.stage_history.push(StageChange {
+                    from: None,
+                    to: deal.stage,
+                    at: deal.created_at,
+                });
+            }
+        });
+    }
+
+    pub fn recent_activities(&self, limit: usize) -> Vec<&Activity> {
+        let mut activities: Vec<&Activity> = self.activities.iter().collect();
+        activities.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+        activities.into_iter().take(limit).collect()
+    }
+
+    // Sample data for demo
+    pub fn with_sample_data() -> Self {
+        let mut data = Self::new();
+
+        // Sample Contacts
+        let contacts = vec![
+            {
+                let mut c = Contact::new(
+                    "Sarah".into(),
+                    "Chen".into(),
+                    "sarah.chen@techcorp.com".into(),
+                );
+                c.company = Some("TechCorp Solutions".into());
+                c.position = Some("VP of Engineering".into());
+                c.phone = Some("+1 (555) 123-4567".into());
+                c.tags = vec!["enterprise".into(), "hot-lead".into()];
+                c
+            },
+            {
+                let mut c = Contact::new(
+                    "Marcus".into(),
+                    "Johnson".into(),
+                    "m.johnson@innovate.io".into(),
+                );
+                c.company = Some("Innovate.io".into());
+                c.position = Some("CTO".into());
+                c.phone = Some("+1 (555) 234-5678".into());
+                c.tags = vec!["startup".into(), "referral".into()];
+                c
+            },
+            {
+                let mut c = Contact::new(
+                    "Emily".into(),
+                    "Rodriguez".into(),
+                    "emily.r@globalfinance.com".into(),
+                );
+                c.company = Some("Global Finance Inc".into());
+                c.position = Some("Director of Operations".into());
+                c.phone = Some("+1 (555) 345-6789".into());
+                c.tags = vec!["enterprise".into(), "finance".into()];
+                c
+            },
+            {
+                let mut c = Contact::new(
+                    "David".into(),
+                    "Kim".into(),
+                    "david.kim@startupxyz.com".into(),
+                );
+                c.company = Some("StartupXYZ".into());
+                c.position = Some("Founder & CEO".into());
+                c.phone = Some("+1 (555) 456-7890".into());
+                c.tags = vec!["startup".into(), "founder".into()];
+                c
+            },
+            {
+                let mut c = Contact::new(
+                    "Lisa".into(),
+                    "Thompson".into(),
+                    "lisa.t@medtech.health".into(),
+                );
+                c.company = Some("MedTech Health".into());
+                c.position = Some("Head of Product".into());
+                c.phone = Some("+1 (555) 567-8901".into());
+                c.tags = vec!["healthcare".into(), "enterprise".into()];
+                c
+            },
+            {
+                let mut c = Contact::new(
+                    "James".into(),
+                    "Park".into(),
+                    "james.park@techcorp.com".into(),
+                );
+                c.company = Some("TechCorp Solutions".into());
+                c.position = Some("Engineering Manager".into());
+                c.tags = vec!["enterprise".into()];
+                c
+            },
+        ];
+
+        // Sample Relationships
+        let relationships = vec![
+            ContactRelationship::new(
+                contacts[0].id.clone(),
+                contacts[5].id.clone(),
+                RelationshipKind::ManagerOf,
+            ),
+            ContactRelationship::new(
+                contacts[1].id.clone(),
+                contacts[0].id.clone(),
+                RelationshipKind::ReferredBy,
+            ),
+        ];
+
+        // Sample Products
+        let products = vec![
+            Product::new("Platform License".into(), Decimal::from(1200), BillingCycle::Annual),
+            Product::new("API Access".into(), Decimal::from(2500), BillingCycle::Monthly),
+            Product::new(
+                "Implementation Services".into(),
+                Decimal::from(15000),
+                BillingCycle::OneTime,
+            ),
+            Product::new("Premium Support".into(), Decimal::from(500), BillingCycle::Monthly),
+        ];
+
+        let usd = |amount: i64| Money::new(Decimal::from(amount), Currency::USD);
+
+        // Sample Deals
+        let deals = vec![
+            {
+                let mut d = Deal::new(
+                    "Enterprise Platform License".into(),
+                    "TechCorp Solutions".into(),
+                    usd(150000),
+                );
+                d.add_participant(contacts[0].id.clone(), DealRole::Champion);
+                d.add_participant(contacts[5].id.clone(), DealRole::Influencer);
+                d.set_stage(DealStage::Negotiation);
+                d.probability = 75;
+                d
+            },
+            {
+                let mut d = Deal::new(
+                    "API Integration Package".into(),
+                    "Innovate.io".into(),
+                    usd(45000),
+                );
+                d.add_participant(contacts[1].id.clone(), DealRole::DecisionMaker);
+                d.set_stage(DealStage::Proposal);
+                d.probability = 50;
+                d.line_items = vec![
+                    LineItem::from_product(&products[1]),
+                    LineItem::from_product(&products[2]),
+                ];
+                d.recalculate_value();
+                d
+            },
+            {
+                let mut d = Deal::new(
+                    "Financial Analytics Suite".into(),
+                    "Global Finance Inc".into(),
+                    usd(280000),
+                );
+                d.add_participant(contacts[2].id.clone(), DealRole::Influencer);
+                d.set_stage(DealStage::Qualified);
+                d.probability = 30;
+                d
+            },
+            {
+                let mut d = Deal::new(
+                    "Startup Growth Package".into(),
+                    "StartupXYZ".into(),
+                    usd(25000),
+                );
+                d.add_participant(contacts[3].id.clone(), DealRole::DecisionMaker);
+                d.set_stage(DealStage::Lead);
+                d.probability = 15;
+                d
+            },
+            {
+                let mut d = Deal::new(
+                    "Healthcare Compliance Module".into(),
+                    "MedTech Health".into(),
+                    Money::new(Decimal::from(95000), Currency::EUR),
+                );
+                d.add_participant(contacts[4].id.clone(), DealRole::Champion);
+                d.set_stage(DealStage::Proposal);
+                d.probability = 60;
+                d
+            },
+            {
+                let mut d = Deal::new(
+                    "Consulting Engagement Q1".into(),
+                    "TechCorp Solutions".into(),
+                    usd(50000),
+                );
+                d.add_participant(contacts[0].id.clone(), DealRole::Champion);
+                d.set_stage(DealStage::Won);
+                d.probability = 100;
+                d.close = Some(CloseInfo {
+                    reason: "Relationship".into(),
+                    note: Some("Expanded from the platform license conversations".into()),
+                    closed_at: Utc::now(),
+                    final_value: usd(50000),
+                });
+                d
+            },
+        ];
+
+        // Sample Activities
+        let activities = vec![
+            {
+                let mut a =
+                    Activity::new(ActivityType::Meeting, "Discovery call with Sarah".into());
+                a.contact_id = Some(contacts[0].id.clone());
+                a.deal_id = Some(deals[0].id.clone());
+                a.description = Some("Discussed enterprise requirements and timeline".into());
+                let start = Utc::now() - Duration::days(7);
+                a.details = ActivityDetails::Meeting(MeetingDetails {
+                    start: Some(start),
+                    end: Some(start + Duration::minutes(45)),
+                    location: Some("Zoom".into()),
+                    attendees: vec![contacts[0].id.clone()],
+                });
+                a.completed = true;
+                a
+            },
+            {
+                let mut a = Activity::new(ActivityType::Email, "Sent proposal to Marcus".into());
+                a.contact_id = Some(contacts[1].id.clone());
+                a.deal_id = Some(deals[1].id.clone());
+                a.description = Some("API integration proposal with pricing tiers".into());
+                a.details = ActivityDetails::Email(EmailDetails {
+                    direction: Direction::Outbound,
+                    subject: "API integration proposal".into(),
+                });
+                a.completed = true;
+                a
+            },
+            {
+                let mut a = Activity::new(
+                    ActivityType::Task,
+                    "Follow up with Emily on requirements".into(),
+                );
+                a.contact_id = Some(contacts[2].id.clone());
+                a.deal_id = Some(deals[2].id.clone());
+                a.due_date = Some(Utc::now() - Duration::days(1));
+                a.completed = false;
+                a
+            },
+            {
+                let mut a = Activity::new(ActivityType::Call, "Intro call with David".into());
+                a.contact_id = Some(contacts[3].id.clone());
+                a.deal_id = Some(deals[3].id.clone());
+                a.description = Some("Initial discussion about startup needs".into());
+                a.details = ActivityDetails::Call(CallDetails {
+                    direction: Direction::Outbound,
+                    duration_minutes: Some(25),
+                    outcome: Some(CallOutcome::Connected),
+                });
+                a.completed = true;
+                a
+            },
+            {
+                let mut a = Activity::new(
+                    ActivityType::Note,
+                    "Lisa mentioned budget approval pending".into(),
+                );
+                a.contact_id = Some(contacts[4].id.clone());
+                a.deal_id = Some(deals[4].id.clone());
+                a.completed = false;
+                a
+            },
+            {
+                let mut a = Activity::new(ActivityType::Task, "Prepare demo for TechCorp".into());
+                a.contact_id = Some(contacts[0].id.clone());
+                a.deal_id = Some(deals[0].id.clone());
+                a.due_date = Some(Utc::now() + Duration::days(2));
+                a.reminder = Some(Reminder::OneDay);
+                a.completed = false;
+                a
+            },
+            {
+                let mut a = Activity::new(
+                    ActivityType::Meeting,
+                    "Quarterly business review with TechCorp".into(),
+                );
+                a.contact_id = Some(contacts[0].id.clone());
+                a.deal_id = Some(deals[5].id.clone());
+                a.due_date = Some(Utc::now() + Duration::days(14));
+                a.recurrence = Some(Recurrence::new(Frequency::Monthly, 3, RecurrenceEnd::Never));
+                a.completed = false;
+                a
+            },
+        ];
+
+        data.contacts = contacts.into();
+        data.relationships = relationships.into();
+        data.products = products.into();
+        data.deals = deals.into();
+        data.activities = activities.into();
+        data
+    }
+}
+
+// ============================================================================
+// Referential Integrity
+// ============================================================================
+
+/// What happens to records that point at a contact or deal being deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DeleteMode {
+    /// Clear the reference and keep the record.
+    #[default]
+    Unlink,
+    /// Point the reference at the record with this id instead.
+    Reassign(String),
+    /// Delete the referencing records as well. A contact only takes the deals it is
+    /// the primary contact on; other deals just lose it as a participant.
+    Cascade,
+}
+
+/// Records that reference a contact or deal, as (id, title) pairs.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct References {
+    pub deals: Vec<(String, String)>,
+    pub activities: Vec<(String, String)>,
+    /// Contact relationships, which stay with the contact while it is in the trash.
+    pub relationships: usize,
+}
+
+impl References {
+    pub fn is_empty(&self) -> bool {
+        self.deals.is_empty() && self.activities.is_empty()
+    }
+}
+
+/// Dangling references fixed by [`AppData::repair_orphans`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OrphanReport {
+    pub participants: usize,
+    pub activity_contacts: usize,
+    pub activity_deals: usize,
+    pub relationships: usize,
+}
+
+impl OrphanReport {
+    pub fn total(&self) -> usize {
+        self.participants + self.activity_contacts + self.activity_deals + self.relationships
+    }
+}
+
+impl AppData {
+    pub fn contact_references(&self, contact_id: &str) -> References {
+        References {
+            deals: self
+                .deals_for_contact(contact_id)
+                .into_iter()
+                .map(|d| (d.id.clone(), d.title.clone()))
+                .collect(),
+            activities: self
+                .activities_for_contact(contact_id)
+                .into_iter()
+                .map(|a| (a.id.clone(), a.title.clone()))
+                .collect(),
+            relationships: self.relationships.iter().filter(|r| r.involves(contact_id)).count(),
+        }
+    }
+
+    pub fn deal_references(&self, deal_id: &str) -> References {
+        References {
+            deals: Vec::new(),
+            activities: self
+                .activities_for_deal(deal_id)
+                .into_iter()
+                .map(|a| (a.id.clone(), a.title.clone()))
+                .collect(),
+            relationships: 0,
+        }
+    }
+
+    /// Moves a contact to the trash, handling deals and activities that reference it
+    /// per `mode`. Cascading trashes the contact's deals along with their activities.
+    pub fn remove_contact(&mut self, contact_id: &str, mode: &DeleteMode) {
+        match mode {
+            DeleteMode::Unlink => {
+                for id in self.deals.linked_ids(Link::Contact, contact_id) {
+                    self.deals.update(&id, |deal| deal.participants.retain(|p| p.contact_id != contact_id));
+                }
+                for id in self.activities.linked_ids(Link::Contact, contact_id) {
+                    self.activities.update(&id, |activity| activity.contact_id = None);
+                }
+            }
+            DeleteMode::Reassign(target) => {
+                for id in self.deals.linked_ids(Link::Contact, contact_id) {
+                    self.deals.update(&id, |deal| {
+                        if deal.has_participant(target) {
+                            deal.participants.retain(|p| p.contact_id != contact_id);
+                        } else if let Some(p) = deal.participants.iter_mut().find(|p| p.contact_id == contact_id) {
+                            p.contact_id = target.clone();
+                        }
+                    });
+                }
+                for id in self.activities.linked_ids(Link::Contact, contact_id) {
+                    self.activities.update(&id, |activity| activity.contact_id = Some(target.clone()));
+                }
+            }
+            DeleteMode::Cascade => {
+                let (led, joined): (Vec<&Deal>, Vec<&Deal>) = self
+                    .deals_for_contact(contact_id)
+                    .into_iter()
+                    .partition(|d| d.primary_contact_id() == Some(contact_id));
+                let deal_ids: Vec<String> = led.iter().map(|d| d.id.clone()).collect();
+                let joined: Vec<String> = joined.iter().map(|d| d.id.clone()).collect();
+                for id in &joined {
+                    self.deals.update(id, |deal| deal.participants.retain(|p| p.contact_id != contact_id));
+                }
+
+                let mut activity_ids = self.activities.linked_ids(Link::Contact, contact_id);
+                for deal_id in &deal_ids {
+                    for id in self.activities.linked_ids(Link::Deal, deal_id) {
+                        if !activity_ids.contains(&id) {
+                            activity_ids.push(id);
+                        }
+                    }
+                }
+                for id in &deal_ids {
+                    self.trash_deal(id);
+                }
+                for id in &activity_ids {
+                    self.remove_activity(id);
+                }
+            }
+        }
+
+        if let Some(mut contact) = self.contacts.remove(contact_id) {
+            contact.deleted_at = Some(Utc::now());
+            self.trash.contacts.push(contact);
+        }
+    }
+
+    /// Moves a deal to the trash, handling activities that reference it per `mode`.
+    pub fn remove_deal(&mut self, deal_id: &str, mode: &DeleteMode) {
+        let target = match mode {
+            DeleteMode::Cascade => {
+                let ids: Vec<String> = self
+                    .activities_for_deal(deal_id)
+                    .into_iter()
+                    .map(|a| a.id.clone())
+                    .collect();
+                for id in &ids {
+                    self.remove_activity(id);
+                }
+                None
+            }
+            DeleteMode::Reassign(target) => Some(target.clone()),
+            DeleteMode::Unlink => None,
+        };
+        for id in self.activities.linked_ids(Link::Deal, deal_id) {
+            self.activities.update(&id, |activity| activity.deal_id = target.clone());
+        }
+
+        self.trash_deal(deal_id);
+    }
+
+    fn trash_deal(&mut self, deal_id: &str) {
+        if let Some(mut deal) = self.deals.remove(deal_id) {
+            deal.deleted_at = Some(Utc::now());
+            self.trash.deals.push(deal);
+        }
+    }
+
+    /// Moves an activity to the trash.
+    pub fn remove_activity(&mut self, activity_id: &str) {
+        if let Some(mut activity) = self.activities.remove(activity_id) {
+            activity.deleted_at = Some(Utc::now());
+            self.trash.activities.push(activity);
+        }
+    }
+
+    /// Clears references to contacts and deals that no longer exist. Records in the
+    /// trash still count as existing, so restoring them brings their links back.
+    pub fn repair_orphans(&mut self) -> OrphanReport {
+        let contact_ids: HashSet<String> = self
+            .contacts
+            .iter()
+            .chain(&self.trash.contacts)
+            .map(|c| c.id.clone())
+            .collect();
+        let deal_ids: HashSet<String> = self
+            .deals
+            .iter()
+            .chain(&self.trash.deals)
+            .map(|d| d.id.clone())
+            .collect();
+        let mut report = OrphanReport::default();
+
+        self.deals.update_where(
+            |deal| deal.participants.iter().any(|p| !contact_ids.contains(&p.contact_id)),
+            |deal| {
+                let before = deal.participants.len();
+                deal.participants.retain(|p| contact_ids.contains(&p.contact_id));
+                report.participants += before - deal.participants.len();
+            },
+        );
+
+        self.activities.update_where(
+            |activity| {
+                activity.contact_id.as_ref().is_some_and(|id| !contact_ids.contains(id))
+                    || activity.deal_id.as_ref().is_some_and(|id| !deal_ids.contains(id))
+            },
+            |activity| {
+                if activity.contact_id.as_ref().is_some_and(|id| !contact_ids.contains(id)) {
+                    activity.contact_id = None;
+                    report.activity_contacts += 1;
+                }
+                if activity.deal_id.as_ref().is_some_and(|id| !deal_ids.contains(id)) {
+                    activity.deal_id = None;
+                    report.activity_deals += 1;
+                }
+            },
+        );
+
+        report.relationships = self
+            .relationships
+            .retain(|r| contact_ids.contains(&r.from_id) && contact_ids.contains(&r.to_id))
+            .len();
+
+        report
+    }
+}
+
+// ============================================================================
+// Trash
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordKind {
+    Contact,
+    Deal,
+    Activity,
+}
+
+impl RecordKind {
+    pub fn display_name(&self) -> &str {
+        match self {
+            RecordKind::Contact => "Contact",
+            RecordKind::Deal => "Deal",
+            RecordKind::Activity => "Activity",
+        }
+    }
+
+    pub fn icon(&self) -> &str {
+        match self {
+            RecordKind::Contact => "👤",
+            RecordKind::Deal => "💼",
+            RecordKind::Activity => "📋",
+        }
+    }
+}
+
+impl std::fmt::Display for RecordKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// Soft-deleted records, kept out of the live lists until restored or purged.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Trash {
+    pub contacts: Records<Contact>,
+    pub deals: Records<Deal>,
+    pub activities: Records<Activity>,
+}
+
+/// A row in the Trash view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashEntry {
+    pub kind: RecordKind,
+    pub id: String,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl Trash {
+    pub fn len(&self) -> usize {
+        self.contacts.len() + self.deals.len() + self.activities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Everything in the trash, most recently deleted first.
+    pub fn entries(&self) -> Vec<TrashEntry> {
+        let entry = |kind, id: &String, title: String, deleted_at: Option<DateTime<Utc>>| TrashEntry {
+            kind,
+            id: id.clone(),
+            title,
+            deleted_at: deleted_at.unwrap_or_default(),
+        };
+
+        let mut entries: Vec<TrashEntry> = self
+            .contacts
+            .iter()
+            .map(|c| entry(RecordKind::Contact, &c.id, c.full_name(), c.deleted_at))
+            .chain(
+                self.deals
+                    .iter()
+                    .map(|d| entry(RecordKind::Deal, &d.id, d.title.clone(), d.deleted_at)),
+            )
+            .chain(
+                self.activities
+                    .iter()
+                    .map(|a| entry(RecordKind::Activity, &a.id, a.title.clone(), a.deleted_at)),
+            )
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+        entries
+    }
+}
+
+impl AppData {
+    /// Puts a trashed record back into its live list.
+    pub fn restore(&mut self, kind: RecordKind, id: &str) {
+        match kind {
+            RecordKind::Contact => {
+                if let Some(mut contact) = self.trash.contacts.remove(id) {
+                    contact.deleted_at = None;
+                    self.contacts.push(contact);
+                }
+            }
+            RecordKind::Deal => {
+                if let Some(mut deal) = self.trash.deals.remove(id) {
+                    deal.deleted_at = None;
+                    deal.rank = self.next_rank(deal.stage);
+                    self.deals.push(deal);
+                }
+            }
+            RecordKind::Activity => {
+                if let Some(mut activity) = self.trash.activities.remove(id) {
+                    activity.deleted_at = None;
+                    self.activities.push(activity);
+                }
+            }
+        }
+    }
+
+    /// Permanently deletes a trashed record and unlinks anything still pointing at it.
+    pub fn purge(&mut self, kind: RecordKind, id: &str) {
+        match kind {
+            RecordKind::Contact => self.trash.contacts.remove(id).is_some(),
+            RecordKind::Deal => self.trash.deals.remove(id).is_some(),
+            RecordKind::Activity => self.trash.activities.remove(id).is_some(),
+        };
+        self.repair_orphans();
+    }
+
+    pub fn empty_trash(&mut self) {
+        self.trash.contacts.clear();
+        self.trash.deals.clear();
+        self.trash.activities.clear();
+        self.repair_orphans();
+    }
+
+    /// Purges records that have been in the trash longer than the retention
+    /// setting allows. Returns how many were removed.
+    pub fn purge_expired(&mut self, now: DateTime<Utc>) -> usize {
+        let days = self.settings.trash_retention_days;
+        if days == 0 || self.trash.is_empty() {
+            return 0;
+        }
+
+        let cutoff = now - Duration::days(days as i64);
+        let keep = |deleted_at: Option<DateTime<Utc>>| deleted_at.is_none_or(|at| at > cutoff);
+        let before = self.trash.len();
+        self.trash.contacts.retain(|c| keep(c.deleted_at));
+        self.trash.deals.retain(|d| keep(d.deleted_at));
+        self.trash.activities.retain(|a| keep(a.deleted_at));
+
+        let purged = before - self.trash.len();
+        if purged > 0 {
+            self.repair_orphans();
+        }
+        purged
+    }
+}
+
+// ============================================================================
+// Data Persistence
+// ============================================================================
+
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Size and modification time of a data file, to notice when another program
+/// writes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Returned by [`save_to`] when the file was written by someone else since the
+/// data was read from it. Reload the file and make the change again.
+#[derive(Debug)]
+pub struct FileChanged;
+
+impl std::fmt::Display for FileChanged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the data file was changed by another program since it was read")
+    }
+}
+
+impl std::error::Error for FileChanged {}
+
+/// Takes the lock on `path`, held until the returned file is dropped. The lock
+/// lives in a `.lock` file beside the data, so the data file itself can be
+/// rewritten while it is held.
+fn lock(path: &Path) -> std::io::Result<File> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_file_name(name))?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// Where the app keeps its data: `dcrm/data.json` in the local data directory.
+pub fn data_path() -> PathBuf {
+    let mut path = dirs::data_local_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("dcrm");
+    fs::create_dir_all(&path).ok();
+    path.push("data.json");
+    path
+}
+
+pub fn save_data(data: &mut AppData) -> Result<(), Box<dyn std::error::Error>> {
+    save_to(data, &data_path())
+}
+
+pub fn overwrite_data(data: &mut AppData) -> Result<(), Box<dyn std::error::Error>> {
+    overwrite_to(data, &data_path())
+}
+
+pub fn load_data() -> AppData {
+    load_from(&data_path())
+}
+
+/// Writes `data` to `path` while holding the file's lock. Fails with
+/// [`FileChanged`] instead of overwriting the file if it changed since `data` was
+/// read from or last saved to it.
+pub fn save_to(data: &mut AppData, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    write_file(data, path, true)
+}
+
+/// Like [`save_to`], but replaces whatever the file holds, including changes
+/// another program made since `data` was read.
+pub fn overwrite_to(data: &mut AppData, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    write_file(data, path, false)
+}
+
+fn write_file(data: &mut AppData, path: &Path, check: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let _lock = lock(path)?;
+    if check && data.changed_on_disk(path) {
+        return Err(FileChanged.into());
+    }
+    let json = serde_json::to_string_pretty(data)?;
+    fs::write(path, json)?;
+    data.file_stamp = FileStamp::of(path);
+    Ok(())
+}
+
+impl AppData {
+    /// Whether the file at `path` was written by someone else since this data was
+    /// read from or saved to it.
+    pub fn changed_on_disk(&self, path: &Path) -> bool {
+        FileStamp::of(path) != self.file_stamp
+    }
+}
+
+/// Loads the data file at `path`, falling back to sample data when it is missing
+/// or unreadable. Expired trash is purged and dangling references repaired on the way.
+pub fn load_from(path: &Path) -> AppData {
+    // Without a directory there is nothing to lock, or to read
+    let lock = lock(path).ok();
+    let mut data = if path.exists() {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|_| AppData::with_sample_data()),
+            Err(_) => AppData::with_sample_data(),
+        }
+    } else {
+        AppData::with_sample_data()
+    };
+    data.file_stamp = FileStamp::of(path);
+    drop(lock);
+    prepare(data, path)
+}
+
+/// Like [`load_from`], but returns read and parse errors instead of replacing the
+/// file's contents with sample data, and starts empty when there is no file yet.
+pub fn open_data(path: &Path) -> Result<AppData, Box<dyn std::error::Error>> {
+    let data = if path.exists() {
+        let _lock = lock(path)?;
+        let mut data: AppData = serde_json::from_str(&fs::read_to_string(path)?)?;
+        data.file_stamp = FileStamp::of(path);
+        data
+    } else {
+        AppData::new()
+    };
+    Ok(prepare(data, path))
+}
+
+fn prepare(mut data: AppData, path: &Path) -> AppData {
+    data.normalize();
+
+    let purged = data.purge_expired(Utc::now());
+    // Older versions deleted records without touching what pointed at them
+    let repaired = data.repair_orphans().total();
+    if purged + repaired > 0 {
+        let _ = save_to(&mut data, path);
+    }
+    data
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per line item; deals without line items get a single row with the item columns empty.
+pub fn export_deals_csv(data: &AppData) -> String {
+    let mut csv = String::from(
+        "deal_id,title,company,stage,currency,value,mrr,arr,item,quantity,unit_price,discount_pct,billing,line_total\n",
+    );
+
+    for deal in &data.deals {
+        let deal_cols = format!(
+            "{},{},{},{},{},{},{},{}",
+            csv_field(&deal.id),
+            csv_field(&deal.title),
+            csv_field(&deal.company),
+            deal.stage,
+            deal.value.currency,
+            deal.value.rounded(),
+            deal.mrr().rounded(),
+            deal.arr().rounded(),
+        );
+
+        if deal.line_items.is_empty() {
+            csv.push_str(&format!("{},,,,,,\n", deal_cols));
+        }
+
+        for item in &deal.line_items {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{}\n",
+                deal_cols,
+                csv_field(&item.name),
+                item.quantity,
+                item.unit_price.round_dp(2),
+                item.discount,
+                item.billing,
+                item.total().round_dp(2),
+            ));
+        }
+    }
+
+    csv
+}
+
+/// Writes an export into the user's download directory, falling back to the data directory.
+pub fn write_export(file_name: &str, contents: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = dirs::download_dir().unwrap_or_else(|| {
+        let mut path = data_path();
+        path.pop();
+        path
+    });
+    path.push(file_name);
+    fs::write(&path, contents)?;
+    Ok(path)
+}
@@ -0,0 +1,157 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Currency
+// ============================================================================
+
+/// ISO 4217 codes, kept upper-case so they serialize as the code itself.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+pub enum Currency {
+    #[default]
+    USD,
+    EUR,
+    GBP,
+    INR,
+}
+
+impl Currency {
+    pub fn all() -> Vec<Currency> {
+        vec![Currency::USD, Currency::EUR, Currency::GBP, Currency::INR]
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Currency::USD => "USD",
+            Currency::EUR => "EUR",
+            Currency::GBP => "GBP",
+            Currency::INR => "INR",
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        match self {
+            Currency::USD => "$",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+            Currency::INR => "₹",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "EUR" => Currency::EUR,
+            "GBP" => Currency::GBP,
+            "INR" => Currency::INR,
+            _ => Currency::USD,
+        }
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+// ============================================================================
+// Money
+// ============================================================================
+
+/// An exact decimal amount in a given currency.
+///
+/// Serialized as `{ "amount": "1500.00", "currency": "USD" }`. Plain numbers from
+/// older save files are still accepted and read as USD.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(from = "MoneyRepr")]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MoneyRepr {
+    Full { amount: Decimal, currency: Currency },
+    Legacy(Decimal),
+}
+
+impl From<MoneyRepr> for Money {
+    fn from(repr: MoneyRepr) -> Self {
+        match repr {
+            MoneyRepr::Full { amount, currency } => Money::new(amount, currency),
+            MoneyRepr::Legacy(amount) => Money::new(amount, Currency::default()),
+        }
+    }
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(Decimal::ZERO, currency)
+    }
+
+    /// Parses user input such as `"1500"` or `"1,500.50"`.
+    pub fn parse(input: &str, currency: Currency) -> Option<Self> {
+        input
+            .trim()
+            .replace(',', "")
+            .parse::<Decimal>()
+            .ok()
+            .map(|amount| Self::new(amount, currency))
+    }
+
+    /// The sum of two amounts, or `None` if they are in different currencies.
+    /// Convert first with [`Settings::convert`](super::Settings::convert).
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        (self.currency == other.currency).then(|| Money::new(self.amount + other.amount, self.currency))
+    }
+
+    /// Lossy conversion, only for ratios and chart widths.
+    pub fn to_f64(self) -> f64 {
+        self.amount.to_string().parse().unwrap_or(0.0)
+    }
+
+    pub fn scale(&self, factor: Decimal) -> Self {
+        Self::new(self.amount * factor, self.currency)
+    }
+
+    /// Converts using `rate`, the units of `to` per unit of this currency.
+    pub fn convert(&self, to: Currency, rate: Decimal) -> Self {
+        Self::new(self.amount * rate, to)
+    }
+
+    pub fn rounded(&self) -> Decimal {
+        self.amount.round_dp(2)
+    }
+
+    /// Compact display used across the app, e.g. `€1.2M`, `£45K`.
+    pub fn format_compact(&self) -> String {
+        let symbol = self.currency.symbol();
+        let thousand = Decimal::from(1_000);
+        let million = Decimal::from(1_000_000);
+
+        if self.amount >= million {
+            format!("{}{}M", symbol, (self.amount / million).round_dp(1))
+        } else if self.amount >= thousand {
+            format!("{}{}K", symbol, (self.amount / thousand).round_dp(0))
+        } else {
+            format!("{}{}", symbol, self.amount.round_dp(0))
+        }
+    }
+
+    /// Full display with two decimal places, e.g. `$1500.00`.
+    pub fn format_precise(&self) -> String {
+        format!("{}{:.2}", self.currency.symbol(), self.rounded())
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format_precise())
+    }
+}
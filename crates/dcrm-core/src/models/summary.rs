@@ -0,0 +1,66 @@
+//! Headline numbers for reports and scripts.
+
+use super::{AppData, DealStage, Money};
+use serde::Serialize;
+
+/// The dashboard's figures in one value, with money in the reporting currency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub contacts: usize,
+    pub active_deals: usize,
+    pub pipeline_value: Money,
+    pub weighted_pipeline_value: Money,
+    pub won_value: Money,
+    pub won_deals: usize,
+    pub lost_deals: usize,
+    /// Share of closed deals that were won, as a percentage.
+    pub win_rate: Option<f64>,
+    pub pending_tasks: usize,
+    pub overdue_activities: usize,
+    pub calls: usize,
+    /// Share of calls that connected, as a percentage.
+    pub connect_rate: Option<f64>,
+    pub meetings: usize,
+    pub emails_sent: usize,
+    pub emails_received: usize,
+    /// Open deals and their value at each active stage, in pipeline order.
+    pub stages: Vec<StageTotal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageTotal {
+    pub stage: DealStage,
+    pub deals: usize,
+    pub value: Money,
+}
+
+impl AppData {
+    pub fn summary(&self) -> Summary {
+        let activity = self.activity_stats();
+        Summary {
+            contacts: self.contacts.len(),
+            active_deals: self.active_deals_count(),
+            pipeline_value: self.total_pipeline_value(),
+            weighted_pipeline_value: self.weighted_pipeline_value(),
+            won_value: self.won_deals_value(),
+            won_deals: self.deals_by_stage(DealStage::Won).len(),
+            lost_deals: self.deals_by_stage(DealStage::Lost).len(),
+            win_rate: self.win_rate(),
+            pending_tasks: self.pending_tasks_count(),
+            overdue_activities: self.overdue_count(),
+            calls: activity.calls,
+            connect_rate: activity.connect_rate(),
+            meetings: activity.meetings,
+            emails_sent: activity.emails_sent,
+            emails_received: activity.emails_received,
+            stages: DealStage::active()
+                .into_iter()
+                .map(|stage| StageTotal {
+                    stage,
+                    deals: self.deals_by_stage(stage).len(),
+                    value: self.stage_value(stage),
+                })
+                .collect(),
+        }
+    }
+}
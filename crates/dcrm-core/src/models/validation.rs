@@ -0,0 +1,229 @@
+//! Field rules for contacts, deals, deal closes, activities and webhooks.
+//!
+//! The [actions](crate::actions) run these before storing anything, so every path
+//! that adds or updates a record is held to the same rules as the forms.
+
+use super::{Activity, ActivityDetails, CloseInfo, Contact, Deal, RecurrenceEnd, Webhook};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// Name of the offending field, e.g. `"email"`.
+    pub field: &'static str,
+    pub message: String,
+}
+
+/// Every rule a record failed. Empty means the record is valid.
+///
+/// Serializes as a list of `{ "field", "message" }` objects.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(transparent)]
+pub struct ValidationErrors {
+    errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field,
+            message: message.into(),
+        });
+    }
+
+    pub fn merge(&mut self, other: ValidationErrors) {
+        self.errors.extend(other.errors);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// First message for `field`, for showing next to its input.
+    pub fn message(&self, field: &str) -> Option<String> {
+        self.errors
+            .iter()
+            .find(|e| e.field == field)
+            .map(|e| e.message.clone())
+    }
+
+    fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let messages: Vec<String> = self
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+// ============================================================================
+// Rules
+// ============================================================================
+
+/// Loose shape check: one `@`, no whitespace and a dotted domain.
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.contains(char::is_whitespace)
+        && domain.split('.').count() >= 2
+        && domain.split('.').all(|part| !part.is_empty())
+}
+
+/// Digits with common separators, and enough digits to be dialable.
+fn is_valid_phone(phone: &str) -> bool {
+    let allowed = |c: char| c.is_ascii_digit() || " +-().".contains(c);
+    let digits = phone.chars().filter(char::is_ascii_digit).count();
+    phone.chars().all(allowed) && digits >= 7
+}
+
+impl Contact {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if self.first_name.trim().is_empty() {
+            errors.add("first_name", "First name is required");
+        }
+        if self.last_name.trim().is_empty() {
+            errors.add("last_name", "Last name is required");
+        }
+        if self.email.trim().is_empty() {
+            errors.add("email", "Email is required");
+        } else if !is_valid_email(self.email.trim()) {
+            errors.add("email", "Enter a valid email address");
+        }
+        if let Some(phone) = &self.phone
+            && !is_valid_phone(phone)
+        {
+            errors.add("phone", "Enter a valid phone number");
+        }
+
+        errors.into_result()
+    }
+}
+
+impl Deal {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if self.title.trim().is_empty() {
+            errors.add("title", "Title is required");
+        }
+        if self.company.trim().is_empty() {
+            errors.add("company", "Company is required");
+        }
+        if self.value.amount < Decimal::ZERO {
+            errors.add("value", "Value can't be negative");
+        }
+        if self.probability > 100 {
+            errors.add("probability", "Probability must be between 0 and 100");
+        }
+
+        let mut seen: Vec<&str> = Vec::new();
+        for participant in &self.participants {
+            if seen.contains(&participant.contact_id.as_str()) {
+                errors.add("participants", "A contact can only be added once");
+                break;
+            }
+            seen.push(&participant.contact_id);
+        }
+
+        for item in &self.line_items {
+            if item.name.trim().is_empty() {
+                errors.add("line_items", "Every line item needs a name");
+            } else if item.quantity == 0 {
+                errors.add("line_items", format!("{}: quantity must be at least 1", item.name));
+            } else if item.unit_price < Decimal::ZERO {
+                errors.add("line_items", format!("{}: price can't be negative", item.name));
+            } else if item.discount < Decimal::ZERO || item.discount > Decimal::ONE_HUNDRED {
+                errors.add("line_items", format!("{}: discount must be between 0 and 100%", item.name));
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+impl CloseInfo {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if self.reason.trim().is_empty() {
+            errors.add("reason", "Pick a reason");
+        }
+        if self.final_value.amount < Decimal::ZERO {
+            errors.add("final_value", "Final value can't be negative");
+        }
+
+        errors.into_result()
+    }
+}
+
+impl Activity {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if self.title.trim().is_empty() {
+            errors.add("title", "Title is required");
+        }
+        if self.reminder.is_some() && self.due_date.is_none() {
+            errors.add("reminder", "A reminder needs a due date");
+        }
+        if let ActivityDetails::Meeting(meeting) = &self.details
+            && let (Some(start), Some(end)) = (meeting.start, meeting.end)
+            && end < start
+        {
+            errors.add("meeting", "The meeting can't end before it starts");
+        }
+        if let Some(rule) = &self.recurrence {
+            if rule.interval == 0 {
+                errors.add("recurrence", "Repeat interval must be at least 1");
+            }
+            if rule.end == RecurrenceEnd::AfterCount(0) {
+                errors.add("recurrence", "A series needs at least one occurrence");
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+impl Webhook {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        let url = self.url.trim();
+        let host = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .unwrap_or("");
+        if url.is_empty() {
+            errors.add("url", "URL is required");
+        } else if host.is_empty() || url.contains(char::is_whitespace) {
+            errors.add("url", "Enter an http:// or https:// URL");
+        }
+        if self.secret.trim().is_empty() {
+            errors.add("secret", "A signing secret is required");
+        }
+        if self.events.is_empty() {
+            errors.add("events", "Pick at least one event");
+        }
+
+        errors.into_result()
+    }
+}
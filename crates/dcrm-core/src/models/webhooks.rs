@@ -0,0 +1,281 @@
+//! Outgoing webhooks.
+//!
+//! Actions queue an [`Event`] for each change a webhook can subscribe to. Hosts
+//! turn the queue into [`Delivery`] entries with [`AppData::take_deliveries`],
+//! send them (see the `dcrm-webhooks` crate) and hand the results back through
+//! [`AppData::record_delivery`]. Undoing a change doesn't send anything.
+
+use super::AppData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+/// Deliveries kept in the log before the oldest are dropped.
+const MAX_DELIVERIES: usize = 200;
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum WebhookEvent {
+    #[serde(rename = "contact.created")]
+    ContactCreated,
+    #[serde(rename = "contact.updated")]
+    ContactUpdated,
+    #[serde(rename = "contact.deleted")]
+    ContactDeleted,
+    #[serde(rename = "deal.stage_changed")]
+    DealStageChanged,
+    #[serde(rename = "deal.won")]
+    DealWon,
+    #[serde(rename = "deal.lost")]
+    DealLost,
+    #[serde(rename = "task.completed")]
+    TaskCompleted,
+}
+
+impl WebhookEvent {
+    pub fn all() -> Vec<WebhookEvent> {
+        vec![
+            WebhookEvent::ContactCreated,
+            WebhookEvent::ContactUpdated,
+            WebhookEvent::ContactDeleted,
+            WebhookEvent::DealStageChanged,
+            WebhookEvent::DealWon,
+            WebhookEvent::DealLost,
+            WebhookEvent::TaskCompleted,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            WebhookEvent::ContactCreated => "Contact created",
+            WebhookEvent::ContactUpdated => "Contact updated",
+            WebhookEvent::ContactDeleted => "Contact deleted",
+            WebhookEvent::DealStageChanged => "Deal stage changed",
+            WebhookEvent::DealWon => "Deal won",
+            WebhookEvent::DealLost => "Deal lost",
+            WebhookEvent::TaskCompleted => "Task completed",
+        }
+    }
+
+    /// Name sent to receivers, e.g. `deal.stage_changed`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            WebhookEvent::ContactCreated => "contact.created",
+            WebhookEvent::ContactUpdated => "contact.updated",
+            WebhookEvent::ContactDeleted => "contact.deleted",
+            WebhookEvent::DealStageChanged => "deal.stage_changed",
+            WebhookEvent::DealWon => "deal.won",
+            WebhookEvent::DealLost => "deal.lost",
+            WebhookEvent::TaskCompleted => "task.completed",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<WebhookEvent> {
+        Self::all().into_iter().find(|e| e.key() == key)
+    }
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// Something that happened, waiting to be sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub kind: WebhookEvent,
+    pub occurred_at: DateTime<Utc>,
+    /// The record involved, as it is after the change.
+    pub data: Value,
+}
+
+// ============================================================================
+// Webhooks
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// Key for the HMAC signature receivers use to check a request came from us.
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+impl Webhook {
+    pub fn new(url: String, secret: String, events: Vec<WebhookEvent>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            url,
+            secret,
+            events,
+            enabled: true,
+        }
+    }
+
+    pub fn subscribes(&self, kind: WebhookEvent) -> bool {
+        self.enabled && self.events.contains(&kind)
+    }
+}
+
+// ============================================================================
+// Delivery Log
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn display_name(&self) -> &str {
+        match self {
+            DeliveryStatus::Pending => "Pending",
+            DeliveryStatus::Delivered => "Delivered",
+            DeliveryStatus::Failed => "Failed",
+        }
+    }
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+/// One try at sending a delivery.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Attempt {
+    pub at: DateTime<Utc>,
+    /// HTTP status of the response, if one came back.
+    pub status_code: Option<u16>,
+    /// Why the request failed before a response arrived.
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    pub fn succeeded(&self) -> bool {
+        self.status_code.is_some_and(|code| (200..300).contains(&code))
+    }
+
+    /// Short outcome for the log, e.g. `"HTTP 503"` or the connection error.
+    pub fn summary(&self) -> String {
+        match (self.status_code, &self.error) {
+            (Some(code), _) => format!("HTTP {}", code),
+            (None, Some(error)) => error.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// An event on its way to one webhook.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Delivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub url: String,
+    pub event: WebhookEvent,
+    /// The JSON body, kept as sent so retries carry the same signature.
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub attempts: Vec<Attempt>,
+    pub status: DeliveryStatus,
+}
+
+impl Delivery {
+    pub fn new(webhook: &Webhook, event: &Event) -> Self {
+        let id = Uuid::new_v4().to_string();
+        let body = json!({
+            "id": id,
+            "event": event.kind.key(),
+            "occurred_at": event.occurred_at,
+            "data": event.data,
+        });
+
+        Self {
+            id,
+            webhook_id: webhook.id.clone(),
+            url: webhook.url.clone(),
+            event: event.kind,
+            body: body.to_string(),
+            created_at: event.occurred_at,
+            attempts: Vec::new(),
+            status: DeliveryStatus::Pending,
+        }
+    }
+
+    pub fn last_attempt(&self) -> Option<&Attempt> {
+        self.attempts.last()
+    }
+}
+
+impl AppData {
+    /// Queues `kind` for sending, if any enabled webhook subscribes to it.
+    pub fn emit(&mut self, kind: WebhookEvent, data: impl Serialize) {
+        if !self.settings.webhooks.iter().any(|w| w.subscribes(kind)) {
+            return;
+        }
+        self.outbox.push(Event {
+            kind,
+            occurred_at: Utc::now(),
+            data: serde_json::to_value(data).unwrap_or(Value::Null),
+        });
+    }
+
+    /// Events queued since the last call that haven't been taken yet.
+    pub fn pending_events(&self) -> &[Event] {
+        &self.outbox
+    }
+
+    /// Empties the queue into the delivery log, one pending entry per event and
+    /// subscribed webhook, and returns those entries with their webhook for sending.
+    pub fn take_deliveries(&mut self) -> Vec<(Webhook, Delivery)> {
+        let mut jobs = Vec::new();
+        for event in std::mem::take(&mut self.outbox) {
+            for webhook in self.settings.webhooks.iter().filter(|w| w.subscribes(event.kind)) {
+                jobs.push((webhook.clone(), Delivery::new(webhook, &event)));
+            }
+        }
+
+        self.deliveries.extend(jobs.iter().map(|(_, delivery)| delivery.clone()));
+        let excess = self.deliveries.len().saturating_sub(MAX_DELIVERIES);
+        self.deliveries.drain(..excess);
+        jobs
+    }
+
+    /// Deliveries still pending from an earlier run, with their webhook, for
+    /// sending again. Those whose webhook has since been removed or turned off are
+    /// marked failed instead.
+    pub fn resume_deliveries(&mut self) -> Vec<(Webhook, Delivery)> {
+        let mut jobs = Vec::new();
+        for delivery in self.deliveries.iter_mut().filter(|d| d.status == DeliveryStatus::Pending) {
+            match self.settings.webhooks.iter().find(|w| w.id == delivery.webhook_id && w.enabled) {
+                Some(webhook) => jobs.push((webhook.clone(), delivery.clone())),
+                None => delivery.status = DeliveryStatus::Failed,
+            }
+        }
+        jobs
+    }
+
+    /// Stores the outcome of a delivery over its log entry.
+    ///
+    /// This is bookkeeping rather than a user change, so it isn't recorded for undo.
+    pub fn record_delivery(&mut self, delivery: Delivery) {
+        if let Some(entry) = self.deliveries.iter_mut().find(|d| d.id == delivery.id) {
+            *entry = delivery;
+        }
+    }
+
+    /// Deliveries for the log view, newest first.
+    pub fn recent_deliveries(&self) -> impl Iterator<Item = &Delivery> {
+        self.deliveries.iter().rev()
+    }
+}
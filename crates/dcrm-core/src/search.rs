@@ -0,0 +1,111 @@
+//! Fuzzy search across contacts, deals and activities.
+
+use crate::models::{Activity, AppData, Contact, Deal};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// Results returned by [`search`].
+const MAX_RESULTS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchResult {
+    Contact(Contact),
+    Deal(Deal),
+    Activity(Activity),
+}
+
+impl SearchResult {
+    pub fn title(&self) -> String {
+        match self {
+            SearchResult::Contact(c) => c.full_name(),
+            SearchResult::Deal(d) => d.title.clone(),
+            SearchResult::Activity(a) => a.title.clone(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            SearchResult::Contact(c) => &c.id,
+            SearchResult::Deal(d) => &d.id,
+            SearchResult::Activity(a) => &a.id,
+        }
+    }
+
+    pub fn subtitle(&self) -> String {
+        match self {
+            SearchResult::Contact(c) => c.company.clone().unwrap_or_else(|| c.email.clone()),
+            SearchResult::Deal(d) => format!("{} • {}", d.company, d.format_value()),
+            SearchResult::Activity(a) => a.activity_type.display_name().to_string(),
+        }
+    }
+
+    pub fn result_type(&self) -> &'static str {
+        match self {
+            SearchResult::Contact(_) => "Contact",
+            SearchResult::Deal(_) => "Deal",
+            SearchResult::Activity(_) => "Activity",
+        }
+    }
+}
+
+/// Serializes as `{ "type", "id", "title", "subtitle" }` rather than the whole record.
+impl Serialize for SearchResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut result = serializer.serialize_struct("SearchResult", 4)?;
+        result.serialize_field("type", self.result_type())?;
+        result.serialize_field("id", self.id())?;
+        result.serialize_field("title", &self.title())?;
+        result.serialize_field("subtitle", &self.subtitle())?;
+        result.end()
+    }
+}
+
+/// The best matches for `query`, best first.
+pub fn search(data: &AppData, query: &str) -> Vec<SearchResult> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let matcher = SkimMatcherV2::default();
+    let mut results: Vec<(i64, SearchResult)> = Vec::new();
+
+    // Search contacts
+    for contact in &data.contacts {
+        let search_str = format!(
+            "{} {} {} {}",
+            contact.first_name,
+            contact.last_name,
+            contact.email,
+            contact.company.as_deref().unwrap_or("")
+        );
+        if let Some(score) = matcher.fuzzy_match(&search_str, query) {
+            results.push((score, SearchResult::Contact(contact.clone())));
+        }
+    }
+
+    // Search deals
+    for deal in &data.deals {
+        let search_str = format!("{} {}", deal.title, deal.company);
+        if let Some(score) = matcher.fuzzy_match(&search_str, query) {
+            results.push((score, SearchResult::Deal(deal.clone())));
+        }
+    }
+
+    // Search activities
+    for activity in &data.activities {
+        let search_str = format!(
+            "{} {}",
+            activity.title,
+            activity.description.as_deref().unwrap_or("")
+        );
+        if let Some(score) = matcher.fuzzy_match(&search_str, query) {
+            results.push((score, SearchResult::Activity(activity.clone())));
+        }
+    }
+
+    // Sort by score descending
+    results.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    results.into_iter().take(MAX_RESULTS).map(|(_, r)| r).collect()
+}
@@ -0,0 +1,350 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{contact, deal, deal_with, task, usd, with_contact};
+use dcrm_core::actions;
+use dcrm_core::models::{
+    AppData, BillingCycle, CloseInfo, ContactRelationship, DealParticipant, DealRole, DealStage, DeleteMode,
+    Frequency, LineItem, Recurrence, RecurrenceEnd, RecordKind, RelationshipKind, Reminder,
+};
+use rust_decimal::Decimal;
+
+// ============================================================================
+// Contacts
+// ============================================================================
+
+#[test]
+fn add_contact_rejects_invalid_records() {
+    let mut data = AppData::new();
+    let mut invalid = contact("Ada", "Lovelace");
+    invalid.email = "not-an-email".to_string();
+
+    let errors = actions::add_contact(&mut data, invalid).unwrap_err();
+    assert!(errors.message("email").is_some());
+    assert!(data.contacts.is_empty());
+    assert!(data.history.undo_label().is_none());
+}
+
+#[test]
+fn update_contact_replaces_the_record_and_logs_the_change() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    let mut edited = data.contact_by_id(&id).unwrap().clone();
+    edited.company = Some("Analytical Engines".to_string());
+
+    actions::update_contact(&mut data, edited).unwrap();
+
+    assert_eq!(data.contact_by_id(&id).unwrap().company.as_deref(), Some("Analytical Engines"));
+    let history = data.audit_for(RecordKind::Contact, &id);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].changes[0].field, "Company");
+    assert_eq!(history[0].changes[0].new, "Analytical Engines");
+}
+
+#[test]
+fn delete_contact_unlinks_references_and_trashes_the_contact() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    actions::add_deal(&mut data, deal_with("Engine", 1_000, &id)).unwrap();
+    let mut call = task("Call Ada");
+    call.contact_id = Some(id.clone());
+    actions::add_activity(&mut data, call).unwrap();
+
+    actions::delete_contact(&mut data, &id, DeleteMode::Unlink);
+
+    assert!(data.contact_by_id(&id).is_none());
+    assert_eq!(data.trash.contacts.len(), 1);
+    assert!(data.deals[0].participants.is_empty());
+    assert!(data.activities[0].contact_id.is_none());
+}
+
+#[test]
+fn delete_contact_can_cascade_to_deals() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    actions::add_deal(&mut data, deal_with("Engine", 1_000, &id)).unwrap();
+
+    actions::delete_contact(&mut data, &id, DeleteMode::Cascade);
+
+    assert!(data.deals.is_empty());
+    assert_eq!(data.trash.deals.len(), 1);
+}
+
+#[test]
+fn cascading_a_participant_keeps_deals_led_by_others() {
+    let (mut data, lead) = with_contact("Ada", "Lovelace");
+    let helper = contact("Bea", "Smith");
+    let helper_id = helper.id.clone();
+    actions::add_contact(&mut data, helper).unwrap();
+    let mut engine = deal_with("Engine", 1_000, &lead);
+    engine.participants.push(DealParticipant {
+        contact_id: helper_id.clone(),
+        role: DealRole::Influencer,
+    });
+    let engine_id = engine.id.clone();
+    actions::add_deal(&mut data, engine).unwrap();
+    let mut call = task("Call");
+    call.deal_id = Some(engine_id.clone());
+    actions::add_activity(&mut data, call).unwrap();
+
+    actions::delete_contact(&mut data, &helper_id, DeleteMode::Cascade);
+
+    let engine = data.deal_by_id(&engine_id).unwrap();
+    assert!(!engine.has_participant(&helper_id));
+    assert!(engine.has_participant(&lead));
+    assert_eq!(data.activities_for_deal(&engine_id).len(), 1);
+    assert!(data.trash.deals.is_empty());
+}
+
+#[test]
+fn merge_contacts_moves_references_to_the_survivor() {
+    let (mut data, survivor) = with_contact("Ada", "Lovelace");
+    let duplicate = contact("Ada", "Lovelace");
+    let duplicate_id = duplicate.id.clone();
+    actions::add_contact(&mut data, duplicate).unwrap();
+    actions::add_deal(&mut data, deal_with("Engine", 1_000, &duplicate_id)).unwrap();
+
+    let merged = data.contact_by_id(&survivor).unwrap().clone();
+    actions::merge_contacts(&mut data, merged, &duplicate_id).unwrap();
+
+    assert_eq!(data.contacts.len(), 1);
+    assert!(data.deals[0].has_participant(&survivor));
+    assert_eq!(data.deals_for_contact(&survivor).len(), 1);
+}
+
+#[test]
+fn add_relationship_skips_self_links_and_repeats() {
+    let (mut data, ada) = with_contact("Ada", "Lovelace");
+    let charles = contact("Charles", "Babbage");
+    let charles_id = charles.id.clone();
+    actions::add_contact(&mut data, charles).unwrap();
+
+    actions::add_relationship(&mut data, ContactRelationship::new(ada.clone(), ada.clone(), RelationshipKind::Colleague));
+    actions::add_relationship(&mut data, ContactRelationship::new(ada.clone(), charles_id.clone(), RelationshipKind::Colleague));
+    actions::add_relationship(&mut data, ContactRelationship::new(charles_id, ada, RelationshipKind::Colleague));
+
+    assert_eq!(data.relationships.len(), 1);
+}
+
+// ============================================================================
+// Deals
+// ============================================================================
+
+#[test]
+fn new_deals_go_to_the_bottom_of_their_column() {
+    let mut data = AppData::new();
+    actions::add_deal(&mut data, deal("First", 100)).unwrap();
+    actions::add_deal(&mut data, deal("Second", 200)).unwrap();
+
+    let titles: Vec<&str> = data.deals_by_stage(DealStage::Lead).iter().map(|d| d.title.as_str()).collect();
+    assert_eq!(titles, ["First", "Second"]);
+}
+
+#[test]
+fn changing_stage_sets_probability_and_records_history() {
+    let mut data = AppData::new();
+    let deal = deal("Engine", 1_000);
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+
+    actions::update_deal_stage(&mut data, &id, DealStage::Proposal);
+
+    let deal = data.deal_by_id(&id).unwrap();
+    assert_eq!(deal.stage, DealStage::Proposal);
+    assert_eq!(deal.probability, 50);
+    assert_eq!(deal.stage_history.len(), 2);
+    assert_eq!(data.audit_for(RecordKind::Deal, &id).len(), 1);
+}
+
+#[test]
+fn closing_a_deal_keeps_the_final_value() {
+    let mut data = AppData::new();
+    let deal = deal("Engine", 1_000);
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+
+    let close = CloseInfo {
+        reason: "Price".to_string(),
+        note: None,
+        closed_at: Utc::now(),
+        final_value: usd(900),
+    };
+    actions::close_deal(&mut data, &id, DealStage::Won, close).unwrap();
+
+    let deal = data.deal_by_id(&id).unwrap();
+    assert_eq!(deal.stage, DealStage::Won);
+    assert_eq!(deal.probability, 100);
+    assert_eq!(deal.value, usd(900));
+
+    // Reopening drops the close details
+    actions::update_deal_stage(&mut data, &id, DealStage::Negotiation);
+    assert!(data.deal_by_id(&id).unwrap().close.is_none());
+}
+
+#[test]
+fn editing_a_closed_deal_keeps_the_final_value() {
+    let mut data = AppData::new();
+    let mut deal = deal("Engine", 0);
+    deal.line_items.push(LineItem::new("Seats".to_string(), Decimal::from(1_000), BillingCycle::OneTime));
+    deal.recalculate_value();
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+    let close = CloseInfo {
+        reason: "Price".to_string(),
+        note: None,
+        closed_at: Utc::now(),
+        final_value: usd(900),
+    };
+    actions::close_deal(&mut data, &id, DealStage::Won, close).unwrap();
+
+    // The edit form and the API recalculate the value on every save
+    let mut edited = data.deal_by_id(&id).unwrap().clone();
+    edited.title = "Difference Engine".to_string();
+    edited.recalculate_value();
+    actions::update_deal(&mut data, edited).unwrap();
+    assert_eq!(data.deal_by_id(&id).unwrap().value, usd(900));
+
+    // Reopened, it is worth its line items again
+    actions::update_deal_stage(&mut data, &id, DealStage::Negotiation);
+    let mut edited = data.deal_by_id(&id).unwrap().clone();
+    edited.recalculate_value();
+    assert_eq!(edited.value, usd(1_000));
+}
+
+#[test]
+fn reopening_from_the_edit_form_drops_the_close() {
+    let mut data = AppData::new();
+    let deal = deal("Engine", 1_000);
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+    let close = CloseInfo {
+        reason: "Price".to_string(),
+        note: None,
+        closed_at: Utc::now(),
+        final_value: usd(900),
+    };
+    actions::close_deal(&mut data, &id, DealStage::Lost, close).unwrap();
+
+    let mut edited = data.deal_by_id(&id).unwrap().clone();
+    edited.set_stage(DealStage::Proposal);
+    actions::update_deal(&mut data, edited).unwrap();
+    assert!(data.deal_by_id(&id).unwrap().close.is_none());
+}
+
+#[test]
+fn closing_needs_a_reason_and_a_valid_value() {
+    let mut data = AppData::new();
+    let deal = deal("Engine", 1_000);
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+
+    let close = CloseInfo {
+        reason: " ".to_string(),
+        note: None,
+        closed_at: Utc::now(),
+        final_value: usd(-5),
+    };
+    let errors = actions::close_deal(&mut data, &id, DealStage::Won, close).unwrap_err();
+    assert!(errors.message("reason").is_some());
+    assert!(errors.message("final_value").is_some());
+    assert_eq!(data.deal_by_id(&id).unwrap().stage, DealStage::Lead);
+}
+
+#[test]
+fn move_deal_reorders_within_a_column() {
+    let mut data = AppData::new();
+    for title in ["A", "B", "C"] {
+        actions::add_deal(&mut data, deal(title, 100)).unwrap();
+    }
+    let c = data.deals[2].id.clone();
+
+    actions::move_deal(&mut data, &c, DealStage::Lead, Some(0));
+
+    let titles: Vec<&str> = data.deals_by_stage(DealStage::Lead).iter().map(|d| d.title.as_str()).collect();
+    assert_eq!(titles, ["C", "A", "B"]);
+}
+
+#[test]
+fn restoring_a_deal_puts_it_back_on_the_board() {
+    let mut data = AppData::new();
+    let deal = deal("Engine", 1_000);
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+
+    actions::delete_deal(&mut data, &id, DeleteMode::Unlink);
+    assert!(data.deal_by_id(&id).is_none());
+
+    actions::restore_record(&mut data, RecordKind::Deal, &id);
+    assert!(data.deal_by_id(&id).is_some());
+    assert!(data.trash.is_empty());
+}
+
+// ============================================================================
+// Activities
+// ============================================================================
+
+#[test]
+fn completing_a_recurring_activity_schedules_the_next_one() {
+    let mut data = AppData::new();
+    let mut standup = task("Standup");
+    standup.due_date = Some(Utc::now());
+    standup.recurrence = Some(Recurrence::new(Frequency::Daily, 1, RecurrenceEnd::AfterCount(2)));
+    let id = standup.id.clone();
+    actions::add_activity(&mut data, standup).unwrap();
+
+    actions::toggle_activity_completed(&mut data, &id);
+    assert_eq!(data.activities.len(), 2);
+    assert_eq!(data.activities[1].recurrence.as_ref().unwrap().occurrence, 2);
+
+    // Reopening and completing again doesn't schedule a duplicate
+    actions::toggle_activity_completed(&mut data, &id);
+    actions::toggle_activity_completed(&mut data, &id);
+    assert_eq!(data.activities.len(), 2);
+
+    // The series ends after its second occurrence
+    let second = data.activities[1].id.clone();
+    actions::toggle_activity_completed(&mut data, &second);
+    assert_eq!(data.activities.len(), 2);
+}
+
+#[test]
+fn cancelling_a_series_keeps_completed_occurrences() {
+    let mut data = AppData::new();
+    let mut standup = task("Standup");
+    standup.recurrence = Some(Recurrence::new(Frequency::Weekly, 1, RecurrenceEnd::Never));
+    let series = standup.series_id().unwrap().to_string();
+    let id = standup.id.clone();
+    actions::add_activity(&mut data, standup).unwrap();
+    actions::toggle_activity_completed(&mut data, &id);
+
+    actions::cancel_series(&mut data, &series);
+
+    assert_eq!(data.activities.len(), 1);
+    assert!(data.activities[0].completed);
+    assert!(data.activities[0].recurrence.is_none());
+}
+
+#[test]
+fn due_reminders_are_taken_once() {
+    let mut data = AppData::new();
+    let mut call = task("Call back");
+    call.due_date = Some(Utc::now() + Duration::minutes(10));
+    call.reminder = Some(Reminder::FifteenMinutes);
+    actions::add_activity(&mut data, call).unwrap();
+
+    let now = Utc::now();
+    assert_eq!(actions::take_due_reminders(&mut data, now).len(), 1);
+    assert!(actions::take_due_reminders(&mut data, now).is_empty());
+}
+
+#[test]
+fn deleting_an_activity_moves_it_to_the_trash() {
+    let mut data = AppData::new();
+    let call = task("Call back");
+    let id = call.id.clone();
+    actions::add_activity(&mut data, call).unwrap();
+
+    actions::delete_activity(&mut data, &id);
+    assert!(data.activities.is_empty());
+    assert_eq!(data.trash.activities.len(), 1);
+
+    actions::empty_trash(&mut data);
+    assert!(data.trash.is_empty());
+}
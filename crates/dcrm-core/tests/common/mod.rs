@@ -0,0 +1,41 @@
+//! Record builders shared by the integration tests.
+
+#![allow(dead_code)]
+
+use dcrm_core::models::{Activity, ActivityType, AppData, Contact, Currency, Deal, DealParticipant, DealRole, Money};
+use rust_decimal::Decimal;
+
+pub fn contact(first: &str, last: &str) -> Contact {
+    let email = format!("{}.{}@example.com", first, last).to_lowercase();
+    Contact::new(first.to_string(), last.to_string(), email)
+}
+
+pub fn deal(title: &str, amount: i64) -> Deal {
+    Deal::new(title.to_string(), "Acme".to_string(), usd(amount))
+}
+
+pub fn deal_with(title: &str, amount: i64, contact_id: &str) -> Deal {
+    let mut deal = deal(title, amount);
+    deal.participants.push(DealParticipant {
+        contact_id: contact_id.to_string(),
+        role: DealRole::default(),
+    });
+    deal
+}
+
+pub fn task(title: &str) -> Activity {
+    Activity::new(ActivityType::Task, title.to_string())
+}
+
+pub fn usd(amount: i64) -> Money {
+    Money::new(Decimal::from(amount), Currency::USD)
+}
+
+/// An empty store with one contact, returning the contact's id.
+pub fn with_contact(first: &str, last: &str) -> (AppData, String) {
+    let mut data = AppData::new();
+    let contact = contact(first, last);
+    let id = contact.id.clone();
+    data.contacts.push(contact);
+    (data, id)
+}
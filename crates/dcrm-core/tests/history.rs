@@ -0,0 +1,127 @@
+mod common;
+
+use common::{contact, deal, with_contact};
+use dcrm_core::actions;
+use dcrm_core::models::{AppData, DealStage, DeleteMode, RecordKind, Settings};
+
+#[test]
+fn undo_and_redo_an_edit() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    let mut edited = data.contact_by_id(&id).unwrap().clone();
+    edited.first_name = "Augusta".to_string();
+    actions::update_contact(&mut data, edited).unwrap();
+
+    assert_eq!(data.undo().as_deref(), Some("Edit Augusta Lovelace"));
+    assert_eq!(data.contact_by_id(&id).unwrap().first_name, "Ada");
+
+    assert_eq!(data.redo().as_deref(), Some("Edit Augusta Lovelace"));
+    assert_eq!(data.contact_by_id(&id).unwrap().first_name, "Augusta");
+}
+
+#[test]
+fn undoing_a_delete_restores_records_in_place() {
+    let mut data = AppData::new();
+    for name in ["Ada", "Charles", "Grace"] {
+        actions::add_contact(&mut data, contact(name, "Tester")).unwrap();
+    }
+    let charles = data.contacts[1].id.clone();
+
+    actions::delete_contact(&mut data, &charles, DeleteMode::Unlink);
+    assert_eq!(data.contacts.len(), 2);
+    assert!(data.history.last().is_some_and(|c| c.destructive));
+
+    data.undo();
+    assert_eq!(data.contacts[1].id, charles);
+    assert!(data.trash.is_empty());
+    assert_eq!(data.contact_by_id(&charles).unwrap().first_name, "Charles");
+}
+
+#[test]
+fn a_new_change_clears_redo() {
+    let mut data = AppData::new();
+    actions::add_contact(&mut data, contact("Ada", "Lovelace")).unwrap();
+    data.undo();
+    assert!(data.history.redo_label().is_some());
+
+    actions::add_contact(&mut data, contact("Grace", "Hopper")).unwrap();
+    assert!(data.history.redo_label().is_none());
+    assert!(data.redo().is_none());
+}
+
+#[test]
+fn changes_that_do_nothing_are_not_recorded() {
+    let mut data = AppData::new();
+    actions::update_deal_stage(&mut data, "missing", DealStage::Won);
+    assert!(data.history.undo_label().is_none());
+    assert_eq!(data.history.revision(), 0);
+}
+
+#[test]
+fn undo_is_logged_in_the_change_history() {
+    let mut data = AppData::new();
+    let deal = deal("Engine", 1_000);
+    let id = deal.id.clone();
+    actions::add_deal(&mut data, deal).unwrap();
+    actions::update_deal_stage(&mut data, &id, DealStage::Qualified);
+
+    data.undo();
+
+    let history = data.audit_for(RecordKind::Deal, &id);
+    assert_eq!(history.len(), 2);
+    let stage = history[0].changes.iter().find(|c| c.field == "Stage").unwrap();
+    assert_eq!((stage.old.as_str(), stage.new.as_str()), ("Qualified", "Lead"));
+}
+
+#[test]
+fn undoing_a_step_that_removes_several_records_restores_their_order() {
+    let mut data = AppData::new();
+    for name in ["Ada", "Charles", "Grace", "Alan", "Edsger"] {
+        actions::add_contact(&mut data, contact(name, "Tester")).unwrap();
+    }
+    let order: Vec<String> = data.contacts.iter().map(|c| c.id.clone()).collect();
+
+    data.record_destructive("Remove some", |d| {
+        d.contacts.retain(|c| c.first_name.starts_with('A'));
+    });
+    assert_eq!(data.contacts.len(), 2);
+
+    data.undo();
+    let restored: Vec<String> = data.contacts.iter().map(|c| c.id.clone()).collect();
+    assert_eq!(restored, order);
+    assert_eq!(data.contacts.position(&order[4]), Some(4));
+
+    data.redo();
+    assert_eq!(data.contacts.len(), 2);
+    assert_eq!(data.contacts[1].first_name, "Alan");
+}
+
+#[test]
+fn steps_recorded_inside_a_step_join_it() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+
+    data.record("Outer", |d| {
+        d.record("Inner", |d| d.contacts.update(&id, |c| c.first_name = "Augusta".to_string()));
+        d.settings.trash_retention_days = 7;
+    });
+    assert_eq!(data.history.undo_label(), Some("Outer"));
+
+    data.undo();
+    assert_eq!(data.contact_by_id(&id).unwrap().first_name, "Ada");
+    assert_eq!(data.settings.trash_retention_days, Settings::default().trash_retention_days);
+    assert!(data.history.undo_label().is_none());
+}
+
+#[test]
+fn the_change_history_keeps_the_latest_entries() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    for i in 0..10_005 {
+        let mut edited = data.contact_by_id(&id).unwrap().clone();
+        edited.first_name = format!("Ada {}", i);
+        actions::update_contact(&mut data, edited).unwrap();
+    }
+
+    let history = data.audit_for(RecordKind::Contact, &id);
+    assert_eq!(history.len(), 10_000);
+    assert_eq!(history[0].changes[0].new, "Ada 10004");
+    assert_eq!(history[9_999].changes[0].old, "Ada 4");
+}
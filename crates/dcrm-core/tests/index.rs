@@ -0,0 +1,101 @@
+mod common;
+
+use common::{contact, deal_with, task};
+use dcrm_core::actions;
+use dcrm_core::models::{AppData, DeleteMode};
+
+/// Ada with two deals and three activities, two of them on the first deal.
+fn populated() -> (AppData, String, String) {
+    let mut data = AppData::new();
+    let mut ada = contact("Ada", "Lovelace");
+    ada.tags = vec!["VIP".to_string(), "engineering".to_string()];
+    let ada_id = ada.id.clone();
+    actions::add_contact(&mut data, ada).unwrap();
+    actions::add_contact(&mut data, contact("Grace", "Hopper")).unwrap();
+
+    let engine = deal_with("Engine", 1_000, &ada_id);
+    let engine_id = engine.id.clone();
+    actions::add_deal(&mut data, engine).unwrap();
+    actions::add_deal(&mut data, deal_with("Loom", 500, &ada_id)).unwrap();
+
+    for (title, on_deal) in [("Call", true), ("Email", true), ("Lunch", false)] {
+        let mut activity = task(title);
+        activity.contact_id = Some(ada_id.clone());
+        activity.deal_id = on_deal.then(|| engine_id.clone());
+        actions::add_activity(&mut data, activity).unwrap();
+    }
+    (data, ada_id, engine_id)
+}
+
+#[test]
+fn lookups_follow_links() {
+    let (data, ada, engine) = populated();
+
+    assert_eq!(data.contact_by_id(&ada).unwrap().first_name, "Ada");
+    assert_eq!(data.deal_by_id(&engine).unwrap().title, "Engine");
+    assert_eq!(data.deals_for_contact(&ada).len(), 2);
+    assert_eq!(data.activities_for_contact(&ada).len(), 3);
+    assert_eq!(data.activities_for_deal(&engine).len(), 2);
+    assert!(data.contact_by_id("missing").is_none());
+}
+
+#[test]
+fn tag_lookups_ignore_case() {
+    let (data, ada, _) = populated();
+
+    let vips = data.contacts_with_tag("vip");
+    assert_eq!(vips.len(), 1);
+    assert_eq!(vips[0].id, ada);
+    assert_eq!(data.all_tags(), ["VIP", "engineering"]);
+}
+
+#[test]
+fn lookups_stay_correct_after_removals_and_undo() {
+    let (mut data, ada, engine) = populated();
+    let grace = data.contacts[1].id.clone();
+
+    actions::delete_contact(&mut data, &ada, DeleteMode::Unlink);
+    assert!(data.contact_by_id(&ada).is_none());
+    assert_eq!(data.contact_by_id(&grace).unwrap().first_name, "Grace");
+    assert!(data.deals_for_contact(&ada).is_empty());
+    assert!(data.activities_for_contact(&ada).is_empty());
+
+    data.undo();
+    assert_eq!(data.deals_for_contact(&ada).len(), 2);
+    assert_eq!(data.activities_for_deal(&engine).len(), 2);
+
+    data.redo();
+    assert!(data.contacts_with_tag("VIP").is_empty());
+}
+
+#[test]
+fn lookups_follow_edits_to_links() {
+    let (mut data, ada, engine) = populated();
+    let grace = data.contacts[1].id.clone();
+
+    data.deals.update(&engine, |deal| deal.participants[0].contact_id = grace.clone());
+    assert_eq!(data.deals_for_contact(&ada).len(), 1);
+    assert_eq!(data.deals_for_contact(&grace)[0].id, engine);
+
+    let call = data.activities[0].id.clone();
+    data.activities.update(&call, |activity| activity.deal_id = None);
+    assert_eq!(data.activities_for_deal(&engine).len(), 1);
+
+    data.contacts.update(&ada, |contact| contact.tags.clear());
+    assert!(data.contacts_with_tag("vip").is_empty());
+}
+
+#[test]
+fn removals_keep_positions_in_step() {
+    let (mut data, ada, _) = populated();
+    let grace = data.contacts[1].id.clone();
+
+    assert_eq!(data.contacts.remove(&ada).unwrap().first_name, "Ada");
+    assert_eq!(data.contacts.position(&grace), Some(0));
+    assert!(data.contacts.update(&ada, |c| c.notes = None).is_none());
+
+    let removed = data.activities.retain(|a| a.title != "Email");
+    assert_eq!(removed.len(), 1);
+    assert_eq!(data.activities_for_contact(&ada).len(), 2);
+    assert_eq!(data.activity_by_id(&data.activities[1].id.clone()).unwrap().title, "Lunch");
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{contact, deal_with, with_contact};
+use dcrm_core::actions;
+use dcrm_core::models::{AppData, DealRole, FileChanged, load_from, open_data, overwrite_to, save_to};
+use std::path::PathBuf;
+
+/// A fresh path under the system temp directory, removed when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("dcrm-test-{}", uuid::Uuid::new_v4()));
+        Self(dir.join("data.json"))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if let Some(dir) = self.0.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[test]
+fn saved_data_loads_back() {
+    let file = TempFile::new();
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    actions::add_deal(&mut data, deal_with("Engine", 1_000, &id)).unwrap();
+
+    save_to(&mut data, &file.0).unwrap();
+    let loaded = load_from(&file.0);
+
+    assert_eq!(loaded.contacts, data.contacts);
+    assert_eq!(loaded.deals, data.deals);
+    assert_eq!(loaded.deals_for_contact(&id).len(), 1);
+    // Undo history belongs to the session and isn't saved
+    assert!(loaded.history.undo_label().is_none());
+}
+
+#[test]
+fn a_missing_file_loads_sample_data() {
+    let file = TempFile::new();
+    let data = load_from(&file.0);
+    assert!(!data.contacts.is_empty());
+}
+
+#[test]
+fn loading_purges_expired_trash_and_repairs_links() {
+    let file = TempFile::new();
+    let mut data = AppData::new();
+    let ada = contact("Ada", "Lovelace");
+    let ada_id = ada.id.clone();
+    actions::add_contact(&mut data, ada).unwrap();
+    actions::add_deal(&mut data, deal_with("Engine", 1_000, &ada_id)).unwrap();
+    actions::delete_contact(&mut data, &ada_id, dcrm_core::models::DeleteMode::Unlink);
+    data.trash.contacts.update(&ada_id, |c| c.deleted_at = Some(Utc::now() - Duration::days(60)));
+    // Put the dangling link back, as older versions left it
+    let engine_id = data.deals[0].id.clone();
+    data.deals.update(&engine_id, |d| d.add_participant(ada_id.clone(), DealRole::default()));
+    save_to(&mut data, &file.0).unwrap();
+
+    let loaded = load_from(&file.0);
+    assert!(loaded.trash.is_empty());
+    assert!(loaded.deals[0].participants.is_empty());
+
+    // The cleanup is written back
+    let reloaded: AppData = serde_json::from_str(&std::fs::read_to_string(&file.0).unwrap()).unwrap();
+    assert!(reloaded.trash.is_empty());
+}
+
+#[test]
+fn older_files_without_newer_fields_still_load() {
+    let file = TempFile::new();
+    std::fs::create_dir_all(file.0.parent().unwrap()).unwrap();
+    let json = r#"{
+        "contacts": [],
+        "deals": [{
+            "id": "d1", "title": "Legacy", "contact_id": null, "company": "Acme",
+            "value": 1200.0, "currency": "EUR", "stage": "Won", "probability": 100,
+            "expected_close": null, "notes": null,
+            "close": { "reason": "Price", "note": null, "closed_at": "2024-02-01T00:00:00Z", "final_value": 1100.0 },
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"
+        }, {
+            "id": "d2", "title": "Tagged", "company": "Acme",
+            "value": { "amount": "500", "currency": "USD" }, "stage": "Won", "probability": 100,
+            "expected_close": null, "notes": null,
+            "close": {
+                "reason": "Price", "note": null, "closed_at": "2024-02-01T00:00:00Z",
+                "final_value": { "amount": "450", "currency": "GBP" }
+            },
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"
+        }],
+        "activities": []
+    }"#;
+    std::fs::write(&file.0, json).unwrap();
+
+    let data = load_from(&file.0);
+    let deal = data.deal_by_id("d1").unwrap();
+    assert_eq!(deal.value.currency.code(), "EUR");
+    assert_eq!(deal.close.as_ref().unwrap().final_value.currency.code(), "EUR");
+    assert_eq!(deal.stage_history.len(), 1);
+
+    // Amounts that already carry a currency are left as they are
+    let tagged = data.deal_by_id("d2").unwrap();
+    assert_eq!(tagged.close.as_ref().unwrap().final_value.currency.code(), "GBP");
+}
+
+#[test]
+fn open_data_starts_empty_and_reports_bad_files() {
+    let file = TempFile::new();
+    assert!(open_data(&file.0).unwrap().contacts.is_empty());
+
+    std::fs::create_dir_all(file.0.parent().unwrap()).unwrap();
+    std::fs::write(&file.0, "not json").unwrap();
+    assert!(open_data(&file.0).is_err());
+    assert_eq!(std::fs::read_to_string(&file.0).unwrap(), "not json");
+}
+
+#[test]
+fn saving_refuses_to_overwrite_changes_from_another_program() {
+    let file = TempFile::new();
+    save_to(&mut AppData::new(), &file.0).unwrap();
+
+    let mut app = open_data(&file.0).unwrap();
+    let mut cli = open_data(&file.0).unwrap();
+    actions::add_contact(&mut cli, contact("Ada", "Lovelace")).unwrap();
+    save_to(&mut cli, &file.0).unwrap();
+
+    actions::add_contact(&mut app, contact("Grace", "Hopper")).unwrap();
+    let error = save_to(&mut app, &file.0).unwrap_err();
+    assert!(error.is::<FileChanged>());
+    assert_eq!(open_data(&file.0).unwrap().contacts[0].first_name, "Ada");
+
+    // Saving again after its own save is fine
+    actions::add_contact(&mut cli, contact("Grace", "Hopper")).unwrap();
+    save_to(&mut cli, &file.0).unwrap();
+    assert_eq!(open_data(&file.0).unwrap().contacts.len(), 2);
+
+    // Overwriting replaces the other program's changes on purpose
+    assert!(app.changed_on_disk(&file.0));
+    overwrite_to(&mut app, &file.0).unwrap();
+    assert!(!app.changed_on_disk(&file.0));
+    assert_eq!(open_data(&file.0).unwrap().contacts[0].first_name, "Grace");
+    assert!(save_to(&mut cli, &file.0).unwrap_err().is::<FileChanged>());
+}
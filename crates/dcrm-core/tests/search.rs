@@ -0,0 +1,46 @@
+mod common;
+
+use common::{contact, deal, task};
+use dcrm_core::models::AppData;
+use dcrm_core::search::{SearchResult, search};
+
+fn data() -> AppData {
+    let mut data = AppData::new();
+    let mut ada = contact("Ada", "Lovelace");
+    ada.company = Some("Analytical Engines".to_string());
+    data.contacts.push(ada);
+    data.contacts.push(contact("Grace", "Hopper"));
+    data.deals.push(deal("Difference Engine", 5_000));
+    data.activities.push(task("Send engine schematics"));
+    data
+}
+
+#[test]
+fn empty_query_finds_nothing() {
+    assert!(search(&data(), "").is_empty());
+}
+
+#[test]
+fn matches_every_record_type() {
+    let results = search(&data(), "engine");
+    assert!(results.iter().any(|r| matches!(r, SearchResult::Contact(_))));
+    assert!(results.iter().any(|r| matches!(r, SearchResult::Deal(_))));
+    assert!(results.iter().any(|r| matches!(r, SearchResult::Activity(_))));
+    assert!(!results.iter().any(|r| r.title() == "Grace Hopper"));
+}
+
+#[test]
+fn best_match_comes_first() {
+    let results = search(&data(), "hopper");
+    assert_eq!(results[0].title(), "Grace Hopper");
+    assert_eq!(results[0].result_type(), "Contact");
+}
+
+#[test]
+fn results_are_capped() {
+    let mut data = AppData::new();
+    for i in 0..25 {
+        data.contacts.push(contact("Ada", &format!("Tester{}", i)));
+    }
+    assert_eq!(search(&data, "ada").len(), 10);
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{deal, task, usd};
+use dcrm_core::models::{
+    ActivityDetails, AppData, CallDetails, CallOutcome, CloseInfo, Currency, DealStage, Money, StageChange,
+};
+use rust_decimal::Decimal;
+
+fn pipeline() -> AppData {
+    let mut data = AppData::new();
+    let mut lead = deal("Lead", 1_000);
+    lead.probability = 10;
+    let mut proposal = deal("Proposal", 2_000);
+    proposal.set_stage(DealStage::Proposal);
+    proposal.probability = 50;
+    let mut won = deal("Won", 4_000);
+    won.set_stage(DealStage::Won);
+    won.close = Some(CloseInfo {
+        reason: "Price".to_string(),
+        note: None,
+        closed_at: Utc::now(),
+        final_value: usd(4_000),
+    });
+    let mut lost = deal("Lost", 500);
+    lost.set_stage(DealStage::Lost);
+    data.deals = vec![lead, proposal, won, lost].into();
+    data
+}
+
+#[test]
+fn pipeline_totals_cover_open_deals() {
+    let data = pipeline();
+    assert_eq!(data.total_pipeline_value(), usd(3_000));
+    assert_eq!(data.weighted_pipeline_value(), usd(1_100));
+    assert_eq!(data.won_deals_value(), usd(4_000));
+    assert_eq!(data.active_deals_count(), 2);
+}
+
+#[test]
+fn win_rate_counts_closed_deals() {
+    assert_eq!(pipeline().win_rate(), Some(50.0));
+    assert_eq!(AppData::new().win_rate(), None);
+}
+
+#[test]
+fn stage_averages_skip_instant_and_open_visits() {
+    let mut data = AppData::new();
+    let start = Utc::now() - Duration::days(10);
+    let change = |from, to, days| StageChange { from, to, at: start + Duration::days(days) };
+
+    let mut moved = deal("Moved", 1_000);
+    moved.stage_history = vec![
+        change(None, DealStage::Lead, 0),
+        // Lead -> Qualified -> Proposal in one jump leaves a zero-length Qualified visit
+        change(Some(DealStage::Lead), DealStage::Qualified, 2),
+        change(Some(DealStage::Qualified), DealStage::Proposal, 2),
+        change(Some(DealStage::Proposal), DealStage::Qualified, 6),
+    ];
+    let mut waiting = deal("Waiting", 1_000);
+    waiting.stage_history = vec![change(None, DealStage::Lead, 0)];
+    data.deals = vec![moved, waiting].into();
+
+    assert_eq!(data.average_time_in_stage(DealStage::Lead), Some(Duration::days(2)));
+    assert_eq!(data.average_time_in_stage(DealStage::Proposal), Some(Duration::days(4)));
+    assert_eq!(data.average_time_in_stage(DealStage::Qualified), None);
+}
+
+#[test]
+fn close_reasons_default_to_unspecified() {
+    let data = pipeline();
+    let won = data.close_reason_breakdown(DealStage::Won);
+    assert_eq!(won, [("Price".to_string(), 1, usd(4_000))]);
+    let lost = data.close_reason_breakdown(DealStage::Lost);
+    assert_eq!(lost[0].0, "Unspecified");
+}
+
+#[test]
+fn values_are_reported_in_the_reporting_currency() {
+    let mut data = AppData::new();
+    data.deals.push(deal("Dollars", 100));
+    let mut euros = deal("Euros", 100);
+    euros.value = Money::new(Decimal::from(100), Currency::EUR);
+    data.deals.push(euros);
+
+    // Defaults convert one euro to 1.08 dollars
+    assert_eq!(data.total_pipeline_value(), Money::new(Decimal::new(20800, 2), Currency::USD));
+
+    data.settings.set_reporting_currency(Currency::EUR);
+    assert_eq!(data.total_pipeline_value().currency, Currency::EUR);
+    assert_eq!(data.reporting_value(&data.deals[1]).amount, Decimal::from(100));
+}
+
+#[test]
+fn amounts_in_different_currencies_are_only_added_after_converting() {
+    let settings = AppData::new().settings;
+    let dollars = usd(108);
+    let euros = Money::new(Decimal::from(100), Currency::EUR);
+
+    assert_eq!(dollars.checked_add(euros), None);
+    assert_eq!(dollars.checked_add(usd(2)), Some(usd(110)));
+    assert_eq!(settings.convert(euros, Currency::USD), dollars);
+    assert_eq!(settings.total([dollars, euros], Currency::USD), usd(216));
+}
+
+#[test]
+fn activity_stats_count_calls_by_outcome() {
+    let mut data = AppData::new();
+    for outcome in [CallOutcome::Connected, CallOutcome::Connected, CallOutcome::Voicemail] {
+        let mut call = task("Call");
+        call.details = ActivityDetails::Call(CallDetails {
+            outcome: Some(outcome),
+            duration_minutes: Some(10),
+            ..Default::default()
+        });
+        data.activities.push(call);
+    }
+    data.activities.push(task("Follow up"));
+
+    let stats = data.activity_stats();
+    assert_eq!(stats.calls, 3);
+    assert_eq!(stats.talk_minutes, 30);
+    assert_eq!(data.pending_tasks_count(), 4);
+}
+
+#[test]
+fn summary_matches_the_individual_figures() {
+    let data = pipeline();
+    let summary = data.summary();
+    assert_eq!(summary.active_deals, 2);
+    assert_eq!(summary.pipeline_value, usd(3_000));
+    assert_eq!(summary.won_deals, 1);
+    assert_eq!(summary.lost_deals, 1);
+    assert_eq!(summary.win_rate, Some(50.0));
+    assert_eq!(summary.connect_rate, None);
+    assert_eq!(summary.stages.len(), 4);
+    assert_eq!(summary.stages[2].stage, DealStage::Proposal);
+    assert_eq!(summary.stages[2].value, usd(2_000));
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{contact, deal, task};
+use dcrm_core::models::{Frequency, LineItem, BillingCycle, Money, Currency, Recurrence, RecurrenceEnd, Reminder};
+use rust_decimal::Decimal;
+
+#[test]
+fn contacts_need_a_name_and_a_valid_email() {
+    assert!(contact("Ada", "Lovelace").validate().is_ok());
+
+    let mut invalid = contact("", "Lovelace");
+    invalid.email = "ada@example".to_string();
+    invalid.phone = Some("123".to_string());
+    let errors = invalid.validate().unwrap_err();
+    assert!(errors.message("first_name").is_some());
+    assert!(errors.message("email").is_some());
+    assert!(errors.message("phone").is_some());
+    assert!(errors.message("last_name").is_none());
+}
+
+#[test]
+fn deals_reject_bad_values_and_line_items() {
+    assert!(deal("Engine", 1_000).validate().is_ok());
+
+    let mut invalid = deal("Engine", 1_000);
+    invalid.value = Money::new(Decimal::from(-1), Currency::USD);
+    invalid.probability = 120;
+    let mut item = LineItem::new("Seats".to_string(), Decimal::from(10), BillingCycle::Monthly);
+    item.quantity = 0;
+    invalid.line_items.push(item);
+
+    let errors = invalid.validate().unwrap_err();
+    assert!(errors.message("value").is_some());
+    assert!(errors.message("probability").is_some());
+    assert_eq!(errors.message("line_items").as_deref(), Some("Seats: quantity must be at least 1"));
+}
+
+#[test]
+fn activities_need_a_due_date_for_reminders() {
+    let mut call = task("Call back");
+    call.reminder = Some(Reminder::OneHour);
+    assert!(call.validate().unwrap_err().message("reminder").is_some());
+
+    call.due_date = Some(chrono::Utc::now());
+    assert!(call.validate().is_ok());
+
+    call.recurrence = Some(Recurrence::new(Frequency::Daily, 1, RecurrenceEnd::AfterCount(0)));
+    assert!(call.validate().unwrap_err().message("recurrence").is_some());
+}
+
+#[test]
+fn money_parses_user_input() {
+    let parsed = Money::parse(" 1,500.50 ", Currency::GBP).unwrap();
+    assert_eq!(parsed.amount, Decimal::new(150050, 2));
+    assert_eq!(parsed.format_compact(), "£2K");
+    assert_eq!(parsed.format_precise(), "£1500.50");
+    assert!(Money::parse("lots", Currency::USD).is_none());
+}
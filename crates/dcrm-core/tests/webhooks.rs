@@ -0,0 +1,153 @@
+mod common;
+
+use chrono::Utc;
+use common::{contact, deal, task, usd, with_contact};
+use dcrm_core::actions;
+use dcrm_core::models::{
+    AppData, Attempt, CloseInfo, DealStage, DeleteMode, DeliveryStatus, Webhook, WebhookEvent,
+};
+use serde_json::Value;
+
+fn subscribed(data: &mut AppData, events: Vec<WebhookEvent>) -> String {
+    let webhook = Webhook::new("https://hooks.example.com/crm".into(), "s3cret".into(), events);
+    let id = webhook.id.clone();
+    actions::add_webhook(data, webhook).unwrap();
+    id
+}
+
+fn kinds(data: &AppData) -> Vec<WebhookEvent> {
+    data.pending_events().iter().map(|e| e.kind).collect()
+}
+
+#[test]
+fn nothing_is_queued_without_a_subscriber() {
+    let mut data = AppData::new();
+    actions::add_contact(&mut data, contact("Ada", "Lovelace")).unwrap();
+    assert!(data.pending_events().is_empty());
+
+    subscribed(&mut data, vec![WebhookEvent::DealWon]);
+    actions::add_contact(&mut data, contact("Bea", "Smith")).unwrap();
+    assert!(data.pending_events().is_empty());
+}
+
+#[test]
+fn contact_changes_are_queued() {
+    let (mut data, id) = with_contact("Ada", "Lovelace");
+    subscribed(&mut data, WebhookEvent::all());
+
+    actions::add_contact(&mut data, contact("Bea", "Smith")).unwrap();
+    let mut edited = data.contact_by_id(&id).unwrap().clone();
+    edited.company = Some("Analytical Engines".into());
+    actions::update_contact(&mut data, edited).unwrap();
+    actions::delete_contact(&mut data, &id, DeleteMode::Unlink);
+
+    assert_eq!(
+        kinds(&data),
+        [WebhookEvent::ContactCreated, WebhookEvent::ContactUpdated, WebhookEvent::ContactDeleted]
+    );
+    assert_eq!(data.pending_events()[1].data["company"], "Analytical Engines");
+}
+
+#[test]
+fn stage_changes_and_closes_are_queued() {
+    let mut data = AppData::new();
+    subscribed(&mut data, WebhookEvent::all());
+    let engine = deal("Engine", 1_000);
+    let id = engine.id.clone();
+    actions::add_deal(&mut data, engine).unwrap();
+
+    actions::update_deal_stage(&mut data, &id, DealStage::Qualified);
+    actions::update_deal_stage(&mut data, &id, DealStage::Qualified);
+    let close = CloseInfo {
+        reason: "Price".into(),
+        note: None,
+        closed_at: Utc::now(),
+        final_value: usd(900),
+    };
+    actions::close_deal(&mut data, &id, DealStage::Won, close).unwrap();
+
+    assert_eq!(
+        kinds(&data),
+        [WebhookEvent::DealStageChanged, WebhookEvent::DealStageChanged, WebhookEvent::DealWon]
+    );
+    let won = &data.pending_events()[2].data;
+    assert_eq!(won["from"], "Qualified");
+    assert_eq!(won["to"], "Won");
+    assert_eq!(won["deal"]["id"], id.as_str());
+}
+
+#[test]
+fn only_completed_tasks_are_queued() {
+    let mut data = AppData::new();
+    subscribed(&mut data, vec![WebhookEvent::TaskCompleted]);
+    let deck = task("Send deck");
+    let id = deck.id.clone();
+    actions::add_activity(&mut data, deck).unwrap();
+
+    actions::toggle_activity_completed(&mut data, &id);
+    actions::toggle_activity_completed(&mut data, &id);
+
+    assert_eq!(kinds(&data), [WebhookEvent::TaskCompleted]);
+}
+
+#[test]
+fn deliveries_are_logged_per_webhook_and_updated() {
+    let mut data = AppData::new();
+    let first = subscribed(&mut data, vec![WebhookEvent::ContactCreated]);
+    subscribed(&mut data, vec![WebhookEvent::ContactCreated]);
+    actions::toggle_webhook(&mut data, &first);
+    actions::add_contact(&mut data, contact("Ada", "Lovelace")).unwrap();
+
+    let jobs = data.take_deliveries();
+    assert_eq!(jobs.len(), 1);
+    assert!(data.pending_events().is_empty());
+    assert!(data.take_deliveries().is_empty());
+
+    let (webhook, mut delivery) = jobs.into_iter().next().unwrap();
+    assert_ne!(webhook.id, first);
+    let body: Value = serde_json::from_str(&delivery.body).unwrap();
+    assert_eq!(body["event"], "contact.created");
+    assert_eq!(body["id"], delivery.id.as_str());
+    assert_eq!(body["data"]["first_name"], "Ada");
+
+    delivery.attempts.push(Attempt { at: Utc::now(), status_code: Some(204), error: None });
+    delivery.status = DeliveryStatus::Delivered;
+    data.record_delivery(delivery);
+    assert_eq!(data.deliveries.len(), 1);
+    assert_eq!(data.deliveries[0].status, DeliveryStatus::Delivered);
+    assert!(data.deliveries[0].last_attempt().unwrap().succeeded());
+}
+
+#[test]
+fn pending_deliveries_are_resumed_after_a_restart() {
+    let mut data = AppData::new();
+    let kept = subscribed(&mut data, vec![WebhookEvent::ContactCreated]);
+    let dropped = subscribed(&mut data, vec![WebhookEvent::ContactCreated]);
+    actions::add_contact(&mut data, contact("Ada", "Lovelace")).unwrap();
+    data.take_deliveries();
+    actions::delete_webhook(&mut data, &dropped);
+
+    // Only what was saved survives a restart
+    let mut data: AppData = serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+    let jobs = data.resume_deliveries();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].0.id, kept);
+    assert_eq!(jobs[0].1.status, DeliveryStatus::Pending);
+
+    let statuses: Vec<DeliveryStatus> = data.deliveries.iter().map(|d| d.status).collect();
+    assert!(statuses.contains(&DeliveryStatus::Failed));
+    assert_eq!(data.resume_deliveries().len(), 1);
+}
+
+#[test]
+fn webhooks_need_a_url_secret_and_events() {
+    let mut data = AppData::new();
+    let webhook = Webhook::new("ftp://example.com".into(), " ".into(), Vec::new());
+
+    let errors = actions::add_webhook(&mut data, webhook).unwrap_err();
+    assert!(errors.message("url").is_some());
+    assert!(errors.message("secret").is_some());
+    assert!(errors.message("events").is_some());
+    assert!(data.settings.webhooks.is_empty());
+    assert_eq!(WebhookEvent::from_key("deal.stage_changed"), Some(WebhookEvent::DealStageChanged));
+}
@@ -0,0 +1,63 @@
+//! Error responses.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use dcrm_core::models::ValidationErrors;
+use serde_json::json;
+
+/// Everything a request can fail with, sent as `{ "error": ..., "fields": [...] }`.
+#[derive(Debug)]
+pub enum ApiError {
+    Unauthorized,
+    NotFound(&'static str),
+    BadRequest(String),
+    /// The record broke a validation rule; `fields` lists each one.
+    Invalid(ValidationErrors),
+    /// Another program wrote the data file; the server has reloaded it.
+    Conflict,
+    Storage(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Conflict => StatusCode::CONFLICT,
+            ApiError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Unauthorized => write!(f, "missing or invalid API token"),
+            ApiError::NotFound(kind) => write!(f, "{} not found", kind),
+            ApiError::BadRequest(message) => write!(f, "{}", message),
+            ApiError::Invalid(errors) => write!(f, "{}", errors),
+            ApiError::Conflict => write!(f, "the data file was changed by another program; it has been reloaded, so retry the request"),
+            ApiError::Storage(message) => write!(f, "couldn't read or save the data file: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        ApiError::Invalid(errors)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = match &self {
+            ApiError::Invalid(errors) => json!({ "error": "validation failed", "fields": errors }),
+            _ => json!({ "error": self.to_string() }),
+        };
+        (self.status(), axum::Json(body)).into_response()
+    }
+}
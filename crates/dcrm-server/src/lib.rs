@@ -0,0 +1,19 @@
+//! Headless HTTP API over the DCRM data file.
+//!
+//! Serves contacts, deals and activities as JSON, along with search and the
+//! pipeline statistics. Changes go through the same [actions](dcrm_core::actions)
+//! and validation as the desktop app and are saved to the data file straight away.
+//! Webhooks configured in the app fire for changes made here too.
+//!
+//! Every route under `/api` except `/api/health` needs an
+//! `Authorization: Bearer <token>` header.
+
+mod error;
+mod json;
+mod page;
+mod routes;
+mod state;
+
+pub use error::ApiError;
+pub use routes::router;
+pub use state::ApiState;
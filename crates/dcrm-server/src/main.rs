@@ -0,0 +1,51 @@
+//! `dcrm-server`: runs the HTTP API without the desktop window.
+
+use clap::Parser;
+use dcrm_core::models;
+use dcrm_server::{ApiState, router};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "dcrm-server", version, about = "Serve the DCRM data file over a local HTTP API")]
+struct Args {
+    /// Data file to use instead of the desktop app's
+    #[arg(long, env = "DCRM_DATA", value_name = "FILE")]
+    data: Option<PathBuf>,
+
+    /// Address to listen on
+    #[arg(long, env = "DCRM_ADDR", default_value = "127.0.0.1:7878")]
+    addr: SocketAddr,
+
+    /// Bearer token clients must send
+    #[arg(long, env = "DCRM_API_TOKEN", hide_env_values = true)]
+    token: String,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    let path = args.data.unwrap_or_else(models::data_path);
+
+    match serve(&path, args.addr, args.token).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn serve(path: &std::path::Path, addr: SocketAddr, token: String) -> Result<(), Box<dyn std::error::Error>> {
+    let state = ApiState::open(path, token)?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Serving {} on http://{}", path.display(), listener.local_addr()?);
+
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Offset pagination for list endpoints.
+
+use serde::Serialize;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// One page of a filtered list, with the size of the whole list.
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl<T: Clone> Page<T> {
+    /// Cuts `offset..offset + limit` out of `items`, cloning only that slice.
+    /// `limit` defaults to 50 and is capped at 500.
+    pub fn of(items: &[&T], offset: Option<usize>, limit: Option<usize>) -> Self {
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        Self {
+            items: items.iter().skip(offset).take(limit).map(|item| (*item).clone()).collect(),
+            total: items.len(),
+            offset,
+            limit,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use super::{check_references, optional};
+use crate::error::ApiError;
+use crate::json::{Json, Query};
+use crate::page::Page;
+use crate::state::ApiState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use dcrm_core::actions;
+use dcrm_core::models::{Activity, ActivityDetails, ActivityType, AppData, Reminder, ValidationErrors};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct ActivityFilter {
+    #[serde(rename = "type")]
+    activity_type: Option<ActivityType>,
+    contact_id: Option<String>,
+    deal_id: Option<String>,
+    completed: Option<bool>,
+    /// Only open activities past their due date.
+    #[serde(default)]
+    overdue: bool,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// The fields a client sets. `details` must match the type, e.g. `{ "Call": {...} }` for a call.
+#[derive(Deserialize)]
+pub struct ActivityInput {
+    activity_type: ActivityType,
+    title: String,
+    description: Option<String>,
+    contact_id: Option<String>,
+    deal_id: Option<String>,
+    due_date: Option<DateTime<Utc>>,
+    reminder: Option<Reminder>,
+    #[serde(default)]
+    completed: bool,
+    #[serde(default)]
+    details: ActivityDetails,
+}
+
+impl ActivityInput {
+    fn apply(self, activity: &mut Activity) {
+        let reminder_at = activity.reminder_at();
+        activity.activity_type = self.activity_type;
+        activity.title = self.title.trim().to_string();
+        activity.description = optional(self.description);
+        activity.contact_id = optional(self.contact_id);
+        activity.deal_id = optional(self.deal_id);
+        activity.due_date = self.due_date;
+        activity.reminder = self.reminder;
+        activity.completed = self.completed;
+        activity.details = self.details;
+        // Keep the sent flag only while the reminder still points at the same moment
+        activity.reminder_sent = activity.reminder_sent && activity.reminder_at() == reminder_at;
+    }
+}
+
+pub async fn list(
+    State(state): State<ApiState>,
+    Query(filter): Query<ActivityFilter>,
+) -> Result<Json<Page<Activity>>, ApiError> {
+    state.read(|data| {
+        let activities: Vec<&Activity> = match (&filter.contact_id, &filter.deal_id) {
+            (Some(id), _) => data.activities_for_contact(id),
+            (None, Some(id)) => data.activities_for_deal(id),
+            (None, None) => data.activities.iter().collect(),
+        };
+        let activities: Vec<&Activity> = activities
+            .into_iter()
+            .filter(|a| filter.deal_id.is_none() || a.deal_id == filter.deal_id)
+            .filter(|a| filter.activity_type.is_none_or(|t| a.activity_type == t))
+            .filter(|a| filter.completed.is_none_or(|c| a.completed == c))
+            .filter(|a| !filter.overdue || a.is_overdue())
+            .collect();
+        Page::of(&activities, filter.offset, filter.limit)
+    })
+    .await
+    .map(Json)
+}
+
+pub async fn show(State(state): State<ApiState>, Path(id): Path<String>) -> Result<Json<Activity>, ApiError> {
+    state.read(|data| data.activity_by_id(&id).cloned().map(Json).ok_or(ApiError::NotFound("activity"))).await?
+}
+
+pub async fn create(
+    State(state): State<ApiState>,
+    Json(input): Json<ActivityInput>,
+) -> Result<(StatusCode, Json<Activity>), ApiError> {
+    let mut activity = Activity::default();
+    input.apply(&mut activity);
+
+    state.write(|data| {
+        check(data, &activity)?;
+        actions::add_activity(data, activity.clone())?;
+        Ok((StatusCode::CREATED, Json(activity)))
+    }).await
+}
+
+pub async fn update(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(input): Json<ActivityInput>,
+) -> Result<Json<Activity>, ApiError> {
+    state.write(|data| {
+        let mut activity = data.activity_by_id(&id).cloned().ok_or(ApiError::NotFound("activity"))?;
+        input.apply(&mut activity);
+        activity.updated_at = Utc::now();
+
+        check(data, &activity)?;
+        actions::update_activity(data, activity.clone())?;
+        Ok(Json(activity))
+    }).await
+}
+
+/// Marks an activity as completed, scheduling the next one if it repeats.
+/// Completing an already completed activity changes nothing.
+pub async fn complete(State(state): State<ApiState>, Path(id): Path<String>) -> Result<Json<Activity>, ApiError> {
+    state.write(|data| {
+        let activity = data.activity_by_id(&id).ok_or(ApiError::NotFound("activity"))?;
+        if !activity.completed {
+            actions::toggle_activity_completed(data, &id);
+        }
+        data.activity_by_id(&id).cloned().map(Json).ok_or(ApiError::NotFound("activity"))
+    }).await
+}
+
+pub async fn delete(State(state): State<ApiState>, Path(id): Path<String>) -> Result<StatusCode, ApiError> {
+    state.write(|data| {
+        if data.activity_by_id(&id).is_none() {
+            return Err(ApiError::NotFound("activity"));
+        }
+        actions::delete_activity(data, &id);
+        Ok(StatusCode::NO_CONTENT)
+    }).await
+}
+
+/// Checks what the core rules can't: that links point at existing records and the
+/// details belong to the activity's type.
+fn check(data: &AppData, activity: &Activity) -> Result<(), ApiError> {
+    let contacts: Vec<&str> = activity.contact_id.as_deref().into_iter().collect();
+    check_references(data, &contacts, activity.deal_id.as_deref(), "contact_id")?;
+
+    let matches = matches!(
+        (activity.activity_type, &activity.details),
+        (ActivityType::Call, ActivityDetails::Call(_) | ActivityDetails::None)
+            | (ActivityType::Meeting, ActivityDetails::Meeting(_) | ActivityDetails::None)
+            | (ActivityType::Email, ActivityDetails::Email(_) | ActivityDetails::None)
+            | (ActivityType::Task | ActivityType::Note, ActivityDetails::None)
+    );
+    if !matches {
+        let mut errors = ValidationErrors::default();
+        errors.add("details", format!("Details don't match the {} type", activity.activity_type));
+        return Err(errors.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use super::{DeleteQuery, optional};
+use crate::error::ApiError;
+use crate::json::{Json, Query};
+use crate::page::Page;
+use crate::state::ApiState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use chrono::Utc;
+use dcrm_core::actions;
+use dcrm_core::models::{Activity, Contact, Deal, DeleteMode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct ContactFilter {
+    tag: Option<String>,
+    company: Option<String>,
+    email: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// The fields a client sets; ids and timestamps are managed by the server.
+#[derive(Deserialize)]
+pub struct ContactInput {
+    first_name: String,
+    last_name: String,
+    email: String,
+    phone: Option<String>,
+    company: Option<String>,
+    position: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    notes: Option<String>,
+}
+
+impl ContactInput {
+    fn apply(self, contact: &mut Contact) {
+        contact.first_name = self.first_name.trim().to_string();
+        contact.last_name = self.last_name.trim().to_string();
+        contact.email = self.email.trim().to_string();
+        contact.phone = optional(self.phone);
+        contact.company = optional(self.company);
+        contact.position = optional(self.position);
+        contact.tags = self
+            .tags
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        contact.notes = optional(self.notes);
+    }
+}
+
+/// A contact with the deals and activities linked to it.
+#[derive(Serialize)]
+pub struct ContactDetail {
+    contact: Contact,
+    deals: Vec<Deal>,
+    activities: Vec<Activity>,
+}
+
+pub async fn list(
+    State(state): State<ApiState>,
+    Query(filter): Query<ContactFilter>,
+) -> Result<Json<Page<Contact>>, ApiError> {
+    state.read(|data| {
+        let contacts: Vec<&Contact> = match &filter.tag {
+            Some(tag) => data.contacts_with_tag(tag),
+            None => data.contacts.iter().collect(),
+        };
+        let contacts: Vec<&Contact> = contacts
+            .into_iter()
+            .filter(|c| {
+                filter
+                    .company
+                    .as_ref()
+                    .is_none_or(|company| c.company.as_ref().is_some_and(|x| x.eq_ignore_ascii_case(company)))
+            })
+            .filter(|c| filter.email.as_ref().is_none_or(|email| c.email.eq_ignore_ascii_case(email)))
+            .collect();
+        Page::of(&contacts, filter.offset, filter.limit)
+    })
+    .await
+    .map(Json)
+}
+
+pub async fn show(State(state): State<ApiState>, Path(id): Path<String>) -> Result<Json<ContactDetail>, ApiError> {
+    state.read(|data| {
+        let contact = data.contact_by_id(&id).ok_or(ApiError::NotFound("contact"))?;
+        Ok(Json(ContactDetail {
+            contact: contact.clone(),
+            deals: data.deals_for_contact(&id).into_iter().cloned().collect(),
+            activities: data.activities_for_contact(&id).into_iter().cloned().collect(),
+        }))
+    })
+    .await?
+}
+
+pub async fn create(
+    State(state): State<ApiState>,
+    Json(input): Json<ContactInput>,
+) -> Result<(StatusCode, Json<Contact>), ApiError> {
+    let mut contact = Contact::default();
+    input.apply(&mut contact);
+
+    state.write(|data| {
+        actions::add_contact(data, contact.clone())?;
+        Ok((StatusCode::CREATED, Json(contact)))
+    }).await
+}
+
+pub async fn update(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(input): Json<ContactInput>,
+) -> Result<Json<Contact>, ApiError> {
+    state.write(|data| {
+        let mut contact = data.contact_by_id(&id).cloned().ok_or(ApiError::NotFound("contact"))?;
+        input.apply(&mut contact);
+        contact.updated_at = Utc::now();
+
+        actions::update_contact(data, contact.clone())?;
+        Ok(Json(contact))
+    }).await
+}
+
+pub async fn delete(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> Result<StatusCode, ApiError> {
+    let mode = query.mode(&id)?;
+    state.write(|data| {
+        if data.contact_by_id(&id).is_none() {
+            return Err(ApiError::NotFound("contact"));
+        }
+        if let DeleteMode::Reassign(other) = &mode
+            && data.contact_by_id(other).is_none()
+        {
+            return Err(ApiError::BadRequest(format!("no contact with id {} to reassign to", other)));
+        }
+
+        actions::delete_contact(data, &id, mode);
+        Ok(StatusCode::NO_CONTENT)
+    }).await
+}
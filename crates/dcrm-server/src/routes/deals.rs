@@ -0,0 +1,179 @@
+use super::{DeleteQuery, check_references, optional};
+use crate::error::ApiError;
+use crate::json::{Json, Query};
+use crate::page::Page;
+use crate::state::ApiState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use dcrm_core::actions;
+use dcrm_core::models::{AppData, CloseInfo, Deal, DealParticipant, DealStage, DeleteMode, Money, ValidationErrors};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct DealFilter {
+    stage: Option<DealStage>,
+    contact_id: Option<String>,
+    company: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// The fields a client sets. Stage changes after creation go through
+/// `POST /api/deals/{id}/stage`, so they're recorded and closed deals get a reason.
+#[derive(Deserialize)]
+pub struct DealInput {
+    title: String,
+    company: String,
+    value: Money,
+    #[serde(default)]
+    participants: Vec<DealParticipant>,
+    stage: Option<DealStage>,
+    probability: Option<u8>,
+    expected_close: Option<DateTime<Utc>>,
+    notes: Option<String>,
+}
+
+impl DealInput {
+    fn apply(self, deal: &mut Deal) {
+        deal.title = self.title.trim().to_string();
+        deal.company = self.company.trim().to_string();
+        deal.value = self.value;
+        deal.participants = self.participants;
+        if let Some(probability) = self.probability {
+            deal.probability = probability;
+        }
+        deal.expected_close = self.expected_close;
+        deal.notes = optional(self.notes);
+        deal.recalculate_value();
+    }
+}
+
+#[derive(Deserialize)]
+pub struct StageChange {
+    stage: DealStage,
+    /// Required when closing as Won or Lost.
+    reason: Option<String>,
+    note: Option<String>,
+    /// What the deal closed at; its current value when omitted.
+    final_value: Option<Money>,
+}
+
+pub async fn list(
+    State(state): State<ApiState>,
+    Query(filter): Query<DealFilter>,
+) -> Result<Json<Page<Deal>>, ApiError> {
+    state.read(|data| {
+        let deals: Vec<&Deal> = match &filter.contact_id {
+            Some(id) => data.deals_for_contact(id),
+            None => data.deals.iter().collect(),
+        };
+        let deals: Vec<&Deal> = deals
+            .into_iter()
+            .filter(|d| filter.stage.is_none_or(|stage| d.stage == stage))
+            .filter(|d| filter.company.as_ref().is_none_or(|c| d.company.eq_ignore_ascii_case(c)))
+            .collect();
+        Page::of(&deals, filter.offset, filter.limit)
+    })
+    .await
+    .map(Json)
+}
+
+pub async fn show(State(state): State<ApiState>, Path(id): Path<String>) -> Result<Json<Deal>, ApiError> {
+    state.read(|data| data.deal_by_id(&id).cloned().map(Json).ok_or(ApiError::NotFound("deal"))).await?
+}
+
+pub async fn create(
+    State(state): State<ApiState>,
+    Json(input): Json<DealInput>,
+) -> Result<(StatusCode, Json<Deal>), ApiError> {
+    let stage = input.stage.unwrap_or(DealStage::Lead);
+    if !DealStage::active().contains(&stage) {
+        return Err(ApiError::BadRequest(
+            "new deals start in an open stage; close them through /stage".into(),
+        ));
+    }
+    let mut deal = Deal::default();
+    deal.set_stage(stage);
+    input.apply(&mut deal);
+    let id = deal.id.clone();
+
+    state.write(|data| {
+        check_participants(data, &deal)?;
+        actions::add_deal(data, deal)?;
+        Ok((StatusCode::CREATED, Json(data.deal_by_id(&id).cloned().ok_or(ApiError::NotFound("deal"))?)))
+    }).await
+}
+
+pub async fn update(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(input): Json<DealInput>,
+) -> Result<Json<Deal>, ApiError> {
+    state.write(|data| {
+        let mut deal = data.deal_by_id(&id).cloned().ok_or(ApiError::NotFound("deal"))?;
+        if input.stage.is_some_and(|stage| stage != deal.stage) {
+            return Err(ApiError::BadRequest("change the stage through /stage".into()));
+        }
+        input.apply(&mut deal);
+        deal.updated_at = Utc::now();
+
+        check_participants(data, &deal)?;
+        actions::update_deal(data, deal)?;
+        data.deal_by_id(&id).cloned().map(Json).ok_or(ApiError::NotFound("deal"))
+    }).await
+}
+
+pub async fn move_stage(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(change): Json<StageChange>,
+) -> Result<Json<Deal>, ApiError> {
+    state.write(|data| {
+        let value = data.deal_by_id(&id).ok_or(ApiError::NotFound("deal"))?.value;
+
+        if DealStage::active().contains(&change.stage) {
+            actions::update_deal_stage(data, &id, change.stage);
+        } else {
+            let Some(reason) = optional(change.reason) else {
+                let mut errors = ValidationErrors::default();
+                errors.add("reason", format!("A deal marked as {} needs a reason", change.stage));
+                return Err(errors.into());
+            };
+            let close = CloseInfo {
+                reason,
+                note: optional(change.note),
+                closed_at: Utc::now(),
+                final_value: change.final_value.unwrap_or(value),
+            };
+            actions::close_deal(data, &id, change.stage, close)?;
+        }
+        data.deal_by_id(&id).cloned().map(Json).ok_or(ApiError::NotFound("deal"))
+    }).await
+}
+
+pub async fn delete(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> Result<StatusCode, ApiError> {
+    let mode = query.mode(&id)?;
+    state.write(|data| {
+        if data.deal_by_id(&id).is_none() {
+            return Err(ApiError::NotFound("deal"));
+        }
+        if let DeleteMode::Reassign(other) = &mode
+            && data.deal_by_id(other).is_none()
+        {
+            return Err(ApiError::BadRequest(format!("no deal with id {} to reassign to", other)));
+        }
+
+        actions::delete_deal(data, &id, mode);
+        Ok(StatusCode::NO_CONTENT)
+    }).await
+}
+
+fn check_participants(data: &AppData, deal: &Deal) -> Result<(), ApiError> {
+    let ids: Vec<&str> = deal.participants.iter().map(|p| p.contact_id.as_str()).collect();
+    check_references(data, &ids, None, "participants")
+}
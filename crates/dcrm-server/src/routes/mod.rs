@@ -0,0 +1,146 @@
+//! Routes, authentication and the endpoints that span record types.
+
+mod activities;
+mod contacts;
+mod deals;
+
+use crate::error::ApiError;
+use crate::json::{Json, Query};
+use crate::state::ApiState;
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{get, post};
+use dcrm_core::models::{AppData, DeleteMode, Summary, ValidationErrors};
+use dcrm_core::search::{SearchResult, search};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+pub fn router(state: ApiState) -> Router {
+    let api = Router::new()
+        .route("/contacts", get(contacts::list).post(contacts::create))
+        .route("/contacts/{id}", get(contacts::show).put(contacts::update).delete(contacts::delete))
+        .route("/deals", get(deals::list).post(deals::create))
+        .route("/deals/{id}", get(deals::show).put(deals::update).delete(deals::delete))
+        .route("/deals/{id}/stage", post(deals::move_stage))
+        .route("/activities", get(activities::list).post(activities::create))
+        .route(
+            "/activities/{id}",
+            get(activities::show).put(activities::update).delete(activities::delete),
+        )
+        .route("/activities/{id}/complete", post(activities::complete))
+        .route("/search", get(run_search))
+        .route("/stats", get(stats))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        // Added after the auth layer so monitors can reach it without a token
+        .route("/health", get(health));
+
+    Router::new()
+        .nest("/api", api)
+        .fallback(not_found)
+        .with_state(state)
+}
+
+// ============================================================================
+// Auth
+// ============================================================================
+
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Result<Response, ApiError> {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match token {
+        Some(token) if same_token(token, state.token()) => Ok(next.run(request).await),
+        _ => Err(ApiError::Unauthorized),
+    }
+}
+
+/// Compares in time that depends only on the lengths, so the token can't be guessed byte by byte.
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// ============================================================================
+// Shared endpoints
+// ============================================================================
+
+async fn health() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+async fn not_found() -> ApiError {
+    ApiError::NotFound("route")
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+}
+
+async fn run_search(
+    State(state): State<ApiState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchResult>>, ApiError> {
+    state.read(|data| search(data, &query.q)).await.map(Json)
+}
+
+async fn stats(State(state): State<ApiState>) -> Result<Json<Summary>, ApiError> {
+    state.read(AppData::summary).await.map(Json)
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// `?cascade=true` deletes the records pointing at the deleted one, `?reassign=<id>`
+/// moves them to another record, and neither just unlinks them.
+#[derive(Deserialize)]
+struct DeleteQuery {
+    #[serde(default)]
+    cascade: bool,
+    reassign: Option<String>,
+}
+
+impl DeleteQuery {
+    fn mode(self, id: &str) -> Result<DeleteMode, ApiError> {
+        match self.reassign {
+            Some(_) if self.cascade => Err(ApiError::BadRequest("use either cascade or reassign".into())),
+            Some(other) if other == id => Err(ApiError::BadRequest("can't reassign a record to itself".into())),
+            Some(other) => Ok(DeleteMode::Reassign(other)),
+            None if self.cascade => Ok(DeleteMode::Cascade),
+            None => Ok(DeleteMode::Unlink),
+        }
+    }
+}
+
+/// `None` for a missing or blank value.
+fn optional(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Reports references to records that don't exist.
+fn check_references(
+    data: &AppData,
+    contact_ids: &[&str],
+    deal_id: Option<&str>,
+    field: &'static str,
+) -> Result<(), ApiError> {
+    let mut errors = ValidationErrors::default();
+    for id in contact_ids.iter().filter(|id| data.contact_by_id(id).is_none()) {
+        errors.add(field, format!("No contact with id {}", id));
+    }
+    if let Some(id) = deal_id.filter(|id| data.deal_by_id(id).is_none()) {
+        errors.add("deal_id", format!("No deal with id {}", id));
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors.into()) }
+}
@@ -0,0 +1,152 @@
+//! The open data file, shared between requests.
+
+use crate::error::ApiError;
+use dcrm_core::models::{self, AppData, Delivery, FileChanged, Webhook};
+use dcrm_webhooks::RetryPolicy;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Tries at logging a delivery while other programs keep writing the file.
+const LOG_ATTEMPTS: u32 = 3;
+
+#[derive(Clone)]
+pub struct ApiState {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    data: Mutex<AppData>,
+    /// Held from the start of a change until it is saved and swapped in, so
+    /// changes don't overwrite each other.
+    writer: tokio::sync::Mutex<()>,
+    path: PathBuf,
+    token: String,
+}
+
+impl ApiState {
+    /// Opens the data file at `path`, starting empty when it doesn't exist yet.
+    /// Requests must carry `token` as a bearer token.
+    pub fn open(path: &Path, token: impl Into<String>) -> Result<Self, Box<dyn Error>> {
+        let token = token.into();
+        if token.is_empty() {
+            return Err("the API token must not be empty".into());
+        }
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                data: Mutex::new(models::open_data(path)?),
+                writer: tokio::sync::Mutex::new(()),
+                path: path.to_path_buf(),
+                token,
+            }),
+        })
+    }
+
+    pub(crate) fn token(&self) -> &str {
+        &self.shared.token
+    }
+
+    /// Runs `f` on the data, first reloading the file if another program wrote it.
+    pub(crate) async fn read<T>(&self, f: impl FnOnce(&AppData) -> T) -> Result<T, ApiError> {
+        self.refresh().await?;
+        Ok(f(&self.data()))
+    }
+
+    /// Applies a change to a copy of the data and saves it on a blocking thread.
+    /// Requests see the change once it is saved; if it fails or the save does,
+    /// they keep seeing the data as it was. Webhooks the change triggered are then
+    /// sent in the background.
+    ///
+    /// The file is reloaded first if another program wrote it. Only if it is written
+    /// again while the change is being saved is the change dropped, the file
+    /// reloaded and the request failed with a conflict.
+    pub(crate) async fn write<T>(&self, f: impl FnOnce(&mut AppData) -> Result<T, ApiError>) -> Result<T, ApiError> {
+        let _writer = self.shared.writer.lock().await;
+        self.refresh().await?;
+        let mut data = self.data().clone();
+        let result = f(&mut data)?;
+        let jobs = data.take_deliveries();
+
+        let path = self.shared.path.clone();
+        let saved = tokio::task::spawn_blocking(move || save(data, &path))
+            .await
+            .map_err(|e| ApiError::Storage(e.to_string()))?;
+        let (data, outcome) = match saved {
+            Saved::Written(data) => (data, Ok(result)),
+            Saved::Reloaded(data) => (data, Err(ApiError::Conflict)),
+            Saved::Failed(message) => return Err(ApiError::Storage(message)),
+        };
+        *self.data() = data;
+        let result = outcome?;
+
+        for job in jobs {
+            self.send(job);
+        }
+        Ok(result)
+    }
+
+    fn data(&self) -> MutexGuard<'_, AppData> {
+        self.shared.data.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reloads the file on a blocking thread if another program wrote it since the
+    /// server last read or saved it.
+    async fn refresh(&self) -> Result<(), ApiError> {
+        let path = self.shared.path.clone();
+        if !self.data().changed_on_disk(&path) {
+            return Ok(());
+        }
+        let data = tokio::task::spawn_blocking(move || models::open_data(&path).map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| ApiError::Storage(e.to_string()))?
+            .map_err(ApiError::Storage)?;
+        *self.data() = data;
+        Ok(())
+    }
+
+    /// Delivers on a blocking thread, retrying as needed, and logs the outcome.
+    fn send(&self, (webhook, mut delivery): (Webhook, Delivery)) {
+        let state = self.clone();
+        tokio::spawn(async move {
+            let delivered = tokio::task::spawn_blocking(move || {
+                dcrm_webhooks::deliver(&webhook, &mut delivery, &RetryPolicy::default());
+                delivery
+            })
+            .await;
+            let Ok(delivery) = delivered else { return };
+            // A conflict has already reloaded the file, so logging again lands on
+            // top of the other program's changes
+            for _ in 0..LOG_ATTEMPTS {
+                let logged = state
+                    .write(|data| {
+                        data.record_delivery(delivery.clone());
+                        Ok(())
+                    })
+                    .await;
+                if !matches!(logged, Err(ApiError::Conflict)) {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// How saving a change went.
+enum Saved {
+    Written(AppData),
+    /// Another program had written the file; this is what it holds now.
+    Reloaded(AppData),
+    Failed(String),
+}
+
+fn save(mut data: AppData, path: &Path) -> Saved {
+    match models::save_to(&mut data, path) {
+        Ok(()) => Saved::Written(data),
+        Err(e) if e.is::<FileChanged>() => match models::open_data(path) {
+            Ok(data) => Saved::Reloaded(data),
+            Err(e) => Saved::Failed(e.to_string()),
+        },
+        Err(e) => Saved::Failed(e.to_string()),
+    }
+}
@@ -0,0 +1,351 @@
+//! Exercises the API against a throwaway data file.
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use dcrm_core::models::{self, AppData, Webhook, WebhookEvent};
+use dcrm_server::{ApiState, router};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tower::ServiceExt;
+
+const TOKEN: &str = "test-token";
+
+/// A server over a data file in a fresh temp directory, removed when dropped.
+struct Api {
+    router: Router,
+    path: PathBuf,
+}
+
+impl Api {
+    fn new() -> Self {
+        Self::with(None)
+    }
+
+    /// A server over a file that already holds `data`.
+    fn with(data: Option<&AppData>) -> Self {
+        let dir = std::env::temp_dir().join(format!("dcrm-server-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("data.json");
+        if let Some(data) = data {
+            models::save_to(&mut data.clone(), &path).unwrap();
+        }
+        let state = ApiState::open(&path, TOKEN).unwrap();
+        Self { router: router(state), path }
+    }
+
+    async fn send(&self, method: &str, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        let body = match body {
+            Some(body) => {
+                request = request.header("Content-Type", "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+
+        let response = self.router.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let json = if bytes.is_empty() { Value::Null } else { serde_json::from_slice(&bytes).unwrap() };
+        (status, json)
+    }
+
+    async fn get(&self, uri: &str) -> (StatusCode, Value) {
+        self.send("GET", uri, Some(TOKEN), None).await
+    }
+
+    async fn post(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.send("POST", uri, Some(TOKEN), Some(body)).await
+    }
+
+    async fn put(&self, uri: &str, body: Value) -> (StatusCode, Value) {
+        self.send("PUT", uri, Some(TOKEN), Some(body)).await
+    }
+
+    async fn delete(&self, uri: &str) -> (StatusCode, Value) {
+        self.send("DELETE", uri, Some(TOKEN), None).await
+    }
+
+    async fn add_contact(&self, first: &str, company: &str) -> String {
+        let email = format!("{}@example.com", first.to_lowercase());
+        let (status, contact) = self
+            .post(
+                "/api/contacts",
+                json!({ "first_name": first, "last_name": "Test", "email": email, "company": company }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", contact);
+        contact["id"].as_str().unwrap().to_string()
+    }
+}
+
+impl Drop for Api {
+    fn drop(&mut self) {
+        if let Some(dir) = self.path.parent() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[tokio::test]
+async fn requests_need_the_token() {
+    let api = Api::new();
+
+    let (status, body) = api.send("GET", "/api/contacts", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(body["error"].is_string());
+    let (status, _) = api.send("GET", "/api/contacts", Some("wrong-token"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = api.send("GET", "/api/health", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn contacts_round_trip_and_are_saved() {
+    let api = Api::new();
+    let id = api.add_contact("Ada", "Acme").await;
+
+    let (status, contact) = api
+        .put(
+            &format!("/api/contacts/{}", id),
+            json!({ "first_name": "Ada", "last_name": "Lovelace", "email": "ada@example.com", "tags": ["vip"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(contact["last_name"], "Lovelace");
+    assert!(contact["company"].is_null());
+
+    // A second server over the same file sees the change
+    let reopened = router(ApiState::open(&api.path, TOKEN).unwrap());
+    let request = Request::get(format!("/api/contacts/{}", id))
+        .header("Authorization", format!("Bearer {}", TOKEN))
+        .body(Body::empty())
+        .unwrap();
+    assert_eq!(reopened.oneshot(request).await.unwrap().status(), StatusCode::OK);
+
+    let (status, _) = api.delete(&format!("/api/contacts/{}", id)).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = api.get(&format!("/api/contacts/{}", id)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "contact not found");
+}
+
+#[tokio::test]
+async fn invalid_records_are_rejected_with_field_errors() {
+    let api = Api::new();
+
+    let (status, body) = api
+        .post("/api/contacts", json!({ "first_name": "", "last_name": "X", "email": "nope" }))
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let fields: Vec<&str> = body["fields"].as_array().unwrap().iter().map(|f| f["field"].as_str().unwrap()).collect();
+    assert!(fields.contains(&"email"));
+
+    let (status, body) = api
+        .post("/api/deals", json!({ "title": "Engine", "company": "Acme", "value": 100, "participants": [{ "contact_id": "missing" }] }))
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["fields"][0]["field"], "participants");
+
+    let (status, body) = api.post("/api/contacts", json!({ "first_name": 1 })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string());
+    assert!(!api.path.exists());
+}
+
+#[tokio::test]
+async fn changes_that_cannot_be_saved_are_not_served() {
+    let api = Api::new();
+    // A file where the data directory should be makes every save fail
+    let dir = api.path.parent().unwrap();
+    std::fs::write(dir, "").unwrap();
+
+    let (status, body) = api
+        .post("/api/contacts", json!({ "first_name": "Ada", "last_name": "Test", "email": "ada@example.com" }))
+        .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(body["error"].as_str().unwrap().starts_with("couldn't read or save"));
+
+    let (_, page) = api.get("/api/contacts").await;
+    assert_eq!(page["total"], 0);
+    std::fs::remove_file(dir).unwrap();
+}
+
+#[tokio::test]
+async fn changes_made_by_another_program_are_reloaded_not_overwritten() {
+    let api = Api::new();
+    api.add_contact("Ada", "Acme").await;
+
+    // The desktop app or the CLI adds a contact behind the server's back
+    let mut other = models::open_data(&api.path).unwrap();
+    other.contacts.push(dcrm_core::models::Contact::new("Grace".into(), "Hopper".into(), "grace@example.com".into()));
+    models::save_to(&mut other, &api.path).unwrap();
+
+    // Reads see it straight away
+    let (_, page) = api.get("/api/contacts").await;
+    assert_eq!(page["total"], 2);
+
+    // Another outside change, then a write: both land
+    let mut other = models::open_data(&api.path).unwrap();
+    other.contacts.push(dcrm_core::models::Contact::new("Alan".into(), "Turing".into(), "alan@example.com".into()));
+    models::save_to(&mut other, &api.path).unwrap();
+    api.add_contact("Cy", "Acme").await;
+    assert_eq!(models::open_data(&api.path).unwrap().contacts.len(), 4);
+}
+
+#[tokio::test]
+async fn lists_are_filtered_and_paged() {
+    let api = Api::new();
+    for name in ["Ada", "Bea", "Cy", "Di", "Ed"] {
+        api.add_contact(name, if name == "Cy" { "Other" } else { "Acme" }).await;
+    }
+
+    let (_, page) = api.get("/api/contacts?company=acme&offset=1&limit=2").await;
+    assert_eq!(page["total"], 4);
+    assert_eq!(page["offset"], 1);
+    let names: Vec<&str> = page["items"].as_array().unwrap().iter().map(|c| c["first_name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["Bea", "Di"]);
+
+    let (_, page) = api.get("/api/contacts?email=CY@example.com").await;
+    assert_eq!(page["items"][0]["company"], "Other");
+
+    let (status, _) = api.get("/api/contacts?limit=lots").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn deals_move_through_the_pipeline() {
+    let api = Api::new();
+    let ada = api.add_contact("Ada", "Acme").await;
+    let (status, deal) = api
+        .post(
+            "/api/deals",
+            json!({
+                "title": "Engine",
+                "company": "Acme",
+                "value": { "amount": "1500", "currency": "EUR" },
+                "participants": [{ "contact_id": ada }],
+                "stage": "Qualified",
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", deal);
+    let id = deal["id"].as_str().unwrap();
+    let stage = format!("/api/deals/{}/stage", id);
+
+    let (status, _) = api.post(&stage, json!({ "stage": "Won" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (_, won) = api.post(&stage, json!({ "stage": "Won", "reason": "Price" })).await;
+    assert_eq!(won["stage"], "Won");
+    assert_eq!(won["probability"], 100);
+
+    let (status, _) = api
+        .put(&format!("/api/deals/{}", id), json!({ "title": "Engine", "company": "Acme", "value": 1, "stage": "Lead" }))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, page) = api.get(&format!("/api/deals?stage=Won&contact_id={}", ada)).await;
+    assert_eq!(page["total"], 1);
+    let (_, stats) = api.get("/api/stats").await;
+    assert_eq!(stats["won_deals"], 1);
+    assert_eq!(stats["stages"].as_array().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn activities_are_logged_completed_and_searched() {
+    let api = Api::new();
+    let ada = api.add_contact("Ada", "Acme").await;
+
+    let (status, body) = api
+        .post("/api/activities", json!({ "activity_type": "Task", "title": "Wrong", "details": { "Email": { "direction": "Inbound", "subject": "Hi" } } }))
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+
+    let (_, task) = api
+        .post(
+            "/api/activities",
+            json!({ "activity_type": "Task", "title": "Send deck", "contact_id": ada, "due_date": "2000-01-01T09:00:00Z" }),
+        )
+        .await;
+    let id = task["id"].as_str().unwrap();
+
+    let (_, overdue) = api.get("/api/activities?overdue=true&type=Task").await;
+    assert_eq!(overdue["total"], 1);
+
+    let complete = format!("/api/activities/{}/complete", id);
+    api.post(&complete, json!({})).await;
+    let (_, again) = api.post(&complete, json!({})).await;
+    assert_eq!(again["completed"], true);
+    let (_, open) = api.get(&format!("/api/activities?completed=false&contact_id={}", ada)).await;
+    assert_eq!(open["total"], 0);
+
+    let (_, results) = api.get("/api/search?q=deck").await;
+    assert_eq!(results[0]["type"], "Activity");
+    assert_eq!(results[0]["id"], id);
+}
+
+#[tokio::test]
+async fn serves_over_a_local_socket() {
+    let api = Api::new();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(axum::serve(listener, api.router.clone()).into_future());
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let request = format!(
+        "GET /api/stats HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nConnection: close\r\n\r\n",
+        addr, TOKEN
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.contains("\"pipeline_value\""));
+}
+
+#[tokio::test]
+async fn closing_a_deal_fires_a_signed_webhook() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    let mut data = AppData::new();
+    data.settings.webhooks.push(Webhook::new(url, "s3cret".into(), vec![WebhookEvent::DealWon]));
+    let api = Api::with(Some(&data));
+
+    // Answer the one request with 200 and hand back its headers and body
+    let receiver = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut headers = HashMap::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            if let Some((name, value)) = line.trim_end().split_once(':') {
+                headers.insert(name.to_lowercase(), value.trim().to_string());
+            }
+            line.clear();
+        }
+        let mut body = vec![0; headers["content-length"].parse().unwrap()];
+        reader.read_exact(&mut body).unwrap();
+        stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").unwrap();
+        (headers, String::from_utf8(body).unwrap())
+    });
+
+    let (_, deal) = api.post("/api/deals", json!({ "title": "Engine", "company": "Acme", "value": 100 })).await;
+    let stage = format!("/api/deals/{}/stage", deal["id"].as_str().unwrap());
+    api.post(&stage, json!({ "stage": "Won", "reason": "Price" })).await;
+
+    let (headers, body) = tokio::task::spawn_blocking(move || receiver.join().unwrap()).await.unwrap();
+    assert_eq!(headers["x-dcrm-event"], "deal.won");
+    assert!(dcrm_webhooks::verify("s3cret", &body, &headers["x-dcrm-signature-256"]));
+    let payload: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(payload["data"]["from"], "Lead");
+    assert_eq!(payload["data"]["deal"]["title"], "Engine");
+}
@@ -0,0 +1,186 @@
+//! Sends DCRM webhook deliveries.
+//!
+//! Each delivery is POSTed as JSON with its event name, its id and an HMAC-SHA256
+//! signature of the body under the webhook's secret:
+//!
+//! ```text
+//! X-Dcrm-Event: deal.won
+//! X-Dcrm-Delivery: 5b0c...
+//! X-Dcrm-Signature-256: sha256=<hex digest>
+//! ```
+//!
+//! Receivers recompute the digest over the raw body to check it came from us
+//! (see [`verify`]). Failed attempts are retried with exponential backoff; sending
+//! blocks, so async hosts run it on a blocking thread.
+
+use chrono::Utc;
+use dcrm_core::models::{Attempt, Delivery, DeliveryStatus, Webhook};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+pub const EVENT_HEADER: &str = "X-Dcrm-Event";
+pub const DELIVERY_HEADER: &str = "X-Dcrm-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Dcrm-Signature-256";
+
+type HmacSha256 = Hmac<Sha256>;
+
+// ============================================================================
+// Signing
+// ============================================================================
+
+/// The signature header value for `body`: `sha256=` and the hex HMAC digest.
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Checks a signature header against `body` in constant time.
+pub fn verify(secret: &str, body: &str, signature: &str) -> bool {
+    let Some(digest) = signature.strip_prefix("sha256=").and_then(|h| hex::decode(h).ok()) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body.as_bytes());
+    mac.verify_slice(&digest).is_ok()
+}
+
+// ============================================================================
+// Retries
+// ============================================================================
+
+/// How hard to try before marking a delivery as failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// Wait before the first retry; it doubles after each failure.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Limit on each request, from connecting to reading the response.
+    pub timeout: Duration,
+    /// Leave a delivery that could still succeed pending after the last attempt,
+    /// instead of failing it, so a later run can send it again.
+    pub leave_pending: bool,
+}
+
+impl RetryPolicy {
+    /// A single short try that leaves retryable failures pending, for callers that
+    /// can't wait out the backoff.
+    pub fn once() -> Self {
+        Self {
+            max_attempts: 1,
+            timeout: Duration::from_secs(3),
+            leave_pending: true,
+            ..Self::default()
+        }
+    }
+
+    /// How long to wait after the `attempt`th try (counting from 1) failed.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            timeout: Duration::from_secs(10),
+            leave_pending: false,
+        }
+    }
+}
+
+/// Network errors, timeouts, rate limits and server errors may pass; other
+/// responses won't change on a retry.
+fn is_retryable(attempt: &Attempt) -> bool {
+    match attempt.status_code {
+        None => true,
+        Some(code) => code == 408 || code == 429 || code >= 500,
+    }
+}
+
+// ============================================================================
+// Delivery
+// ============================================================================
+
+fn agent(policy: &RetryPolicy) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .timeout_global(Some(policy.timeout))
+        .user_agent(concat!("dcrm-webhooks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .into()
+}
+
+fn send(agent: &ureq::Agent, webhook: &Webhook, delivery: &Delivery) -> Attempt {
+    let result = agent
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header(EVENT_HEADER, delivery.event.key())
+        .header(DELIVERY_HEADER, &delivery.id)
+        .header(SIGNATURE_HEADER, sign(&webhook.secret, &delivery.body))
+        .send(&delivery.body);
+
+    let (status_code, error) = match result {
+        Ok(response) => (Some(response.status().as_u16()), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    Attempt {
+        at: Utc::now(),
+        status_code,
+        error,
+    }
+}
+
+/// Sends `delivery` until it gets a 2xx response, a response not worth
+/// retrying, or runs out of attempts, recording each try and the outcome.
+pub fn deliver(webhook: &Webhook, delivery: &mut Delivery, policy: &RetryPolicy) {
+    let agent = agent(policy);
+
+    loop {
+        let attempt = send(&agent, webhook, delivery);
+        let succeeded = attempt.succeeded();
+        let retryable = is_retryable(&attempt);
+        delivery.attempts.push(attempt);
+
+        let tries = delivery.attempts.len() as u32;
+        if succeeded {
+            delivery.status = DeliveryStatus::Delivered;
+            return;
+        }
+        if !retryable {
+            delivery.status = DeliveryStatus::Failed;
+            return;
+        }
+        if tries >= policy.max_attempts {
+            delivery.status = if policy.leave_pending {
+                DeliveryStatus::Pending
+            } else {
+                DeliveryStatus::Failed
+            };
+            return;
+        }
+        std::thread::sleep(policy.delay(tries));
+    }
+}
+
+/// Sends every job side by side and returns the finished deliveries, in order.
+pub fn deliver_all(jobs: Vec<(Webhook, Delivery)>, policy: &RetryPolicy) -> Vec<Delivery> {
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .into_iter()
+            .map(|(webhook, mut delivery)| {
+                scope.spawn(move || {
+                    deliver(&webhook, &mut delivery, policy);
+                    delivery
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    })
+}
@@ -0,0 +1,172 @@
+//! Sends deliveries to a throwaway HTTP listener on localhost.
+
+use dcrm_core::models::{Delivery, DeliveryStatus, Event, Webhook, WebhookEvent};
+use dcrm_webhooks::{DELIVERY_HEADER, EVENT_HEADER, RetryPolicy, SIGNATURE_HEADER, deliver, deliver_all, verify};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+use std::time::Duration;
+
+const SECRET: &str = "s3cret";
+
+/// A request as the listener saw it, with lowercased header names.
+struct Received {
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// Answers one request per status in `statuses`, in order, and passes each
+/// request on. Returns the listener's URL.
+fn listen(statuses: Vec<u16>) -> (String, Receiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+    let (tx, rx) = channel();
+
+    thread::spawn(move || {
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let request = respond(stream, status);
+            if tx.send(request).is_err() {
+                return;
+            }
+        }
+    });
+    (url, rx)
+}
+
+fn respond(mut stream: TcpStream, status: u16) -> Received {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut headers = HashMap::new();
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        headers.insert(name.to_lowercase(), value.trim().to_string());
+    }
+
+    let length = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    let response = format!("HTTP/1.1 {} Test\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+    stream.write_all(response.as_bytes()).unwrap();
+    Received {
+        headers,
+        body: String::from_utf8(body).unwrap(),
+    }
+}
+
+fn job(url: &str) -> (Webhook, Delivery) {
+    let webhook = Webhook::new(url.to_string(), SECRET.to_string(), vec![WebhookEvent::DealWon]);
+    let event = Event {
+        kind: WebhookEvent::DealWon,
+        occurred_at: chrono::Utc::now(),
+        data: json!({ "deal": { "title": "Engine" } }),
+    };
+    let delivery = Delivery::new(&webhook, &event);
+    (webhook, delivery)
+}
+
+fn quick(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        base_delay: Duration::from_millis(10),
+        timeout: Duration::from_secs(5),
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn deliveries_are_signed() {
+    let (url, requests) = listen(vec![204]);
+    let (webhook, mut delivery) = job(&url);
+
+    deliver(&webhook, &mut delivery, &quick(3));
+
+    assert_eq!(delivery.status, DeliveryStatus::Delivered);
+    assert_eq!(delivery.attempts.len(), 1);
+    let request = requests.recv().unwrap();
+    assert_eq!(request.body, delivery.body);
+    assert_eq!(request.headers["content-type"], "application/json");
+    assert_eq!(request.headers[&EVENT_HEADER.to_lowercase()], "deal.won");
+    assert_eq!(request.headers[&DELIVERY_HEADER.to_lowercase()], delivery.id);
+
+    let signature = &request.headers[&SIGNATURE_HEADER.to_lowercase()];
+    assert!(verify(SECRET, &request.body, signature));
+    assert!(!verify("other", &request.body, signature));
+    assert!(!verify(SECRET, "{}", signature));
+}
+
+#[test]
+fn server_errors_are_retried_with_the_same_body() {
+    let (url, requests) = listen(vec![503, 429, 200]);
+    let (webhook, mut delivery) = job(&url);
+
+    deliver(&webhook, &mut delivery, &quick(5));
+
+    assert_eq!(delivery.status, DeliveryStatus::Delivered);
+    let codes: Vec<Option<u16>> = delivery.attempts.iter().map(|a| a.status_code).collect();
+    assert_eq!(codes, [Some(503), Some(429), Some(200)]);
+    let bodies: Vec<String> = requests.iter().take(3).map(|r| r.body).collect();
+    assert!(bodies.iter().all(|b| *b == delivery.body));
+}
+
+#[test]
+fn client_errors_fail_without_retrying() {
+    let (url, _requests) = listen(vec![400, 200]);
+    let (webhook, mut delivery) = job(&url);
+
+    deliver(&webhook, &mut delivery, &quick(5));
+
+    assert_eq!(delivery.status, DeliveryStatus::Failed);
+    assert_eq!(delivery.attempts.len(), 1);
+    assert_eq!(delivery.last_attempt().unwrap().summary(), "HTTP 400");
+}
+
+#[test]
+fn gives_up_after_the_last_attempt() {
+    // Nothing listens on a port once its listener is dropped
+    let url = {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/hooks", listener.local_addr().unwrap())
+    };
+    let (webhook, delivery) = job(&url);
+
+    let done = deliver_all(vec![(webhook, delivery)], &quick(2));
+
+    assert_eq!(done[0].status, DeliveryStatus::Failed);
+    assert_eq!(done[0].attempts.len(), 2);
+    assert!(done[0].attempts.iter().all(|a| a.status_code.is_none() && a.error.is_some()));
+}
+
+#[test]
+fn a_single_try_leaves_retryable_failures_pending() {
+    let (url, _requests) = listen(vec![503, 400]);
+    let (webhook, delivery) = job(&url);
+    let (_, rejected) = job(&url);
+
+    let done = deliver_all(vec![(webhook.clone(), delivery)], &RetryPolicy::once());
+    assert_eq!(done[0].status, DeliveryStatus::Pending);
+    assert_eq!(done[0].attempts.len(), 1);
+
+    let done = deliver_all(vec![(webhook, rejected)], &RetryPolicy::once());
+    assert_eq!(done[0].status, DeliveryStatus::Failed);
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(5),
+        ..RetryPolicy::default()
+    };
+    let delays: Vec<u64> = (1..=5).map(|n| policy.delay(n).as_secs()).collect();
+    assert_eq!(delays, [1, 2, 4, 5, 5]);
+}
@@ -0,0 +1,96 @@
+use crate::models::{RecordKind, export_audit_csv, write_export};
+use crate::state::use_app_data;
+use dioxus::prelude::*;
+
+/// "Details" / "History" switch for record detail views.
+#[component]
+pub fn DetailTabs(showing_history: Signal<bool>, count: usize) -> Element {
+    let tab_class = |active: bool| {
+        if active {
+            "px-3 py-2 text-sm text-zinc-100 border-b-2 border-accent -mb-px"
+        } else {
+            "px-3 py-2 text-sm text-zinc-500 border-b-2 border-transparent -mb-px hover:text-zinc-300 transition-colors"
+        }
+    };
+
+    rsx! {
+        div { class: "flex gap-2 px-5 border-b border-zinc-800",
+            button {
+                class: tab_class(!showing_history()),
+                onclick: move |_| showing_history.set(false),
+                "Details"
+            }
+            button {
+                class: tab_class(showing_history()),
+                onclick: move |_| showing_history.set(true),
+                "History ({count})"
+            }
+        }
+    }
+}
+
+/// Field-level edits to one record, newest first.
+#[component]
+pub fn ChangeHistory(kind: RecordKind, record_id: String) -> Element {
+    let data = use_app_data();
+    let mut export_status = use_signal(|| None::<String>);
+    let entries = data.read().audit_for(kind, &record_id);
+
+    let handle_export = {
+        let entries = entries.clone();
+        move |_| {
+            let csv = export_audit_csv(&entries);
+            let file_name = format!("dcrm-{}-history.csv", kind.display_name().to_lowercase());
+            let status = match write_export(&file_name, &csv) {
+                Ok(path) => format!("Exported to {}", path.display()),
+                Err(e) => format!("Export failed: {}", e),
+            };
+            export_status.set(Some(status));
+        }
+    };
+
+    rsx! {
+        if entries.is_empty() {
+            p { class: "text-sm text-zinc-500", "No changes recorded yet" }
+        } else {
+            div { class: "flex items-center justify-end gap-3 mb-4",
+                if let Some(status) = export_status.read().as_ref() {
+                    span { class: "text-xs text-zinc-500 truncate", "{status}" }
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                    onclick: handle_export,
+                    "Export CSV"
+                }
+            }
+            div { class: "relative pl-5 border-l border-zinc-700 space-y-5",
+                for entry in entries {
+                    div { key: "{entry.id}", class: "relative",
+                        span { class: "absolute -left-[25px] top-1.5 w-2.5 h-2.5 rounded-full bg-zinc-500 ring-4 ring-dark-800" }
+                        div { class: "text-xs text-zinc-500 mb-1.5",
+                            span { class: "text-zinc-300", "{entry.actor}" }
+                            " • "
+                            span { class: "font-mono", {entry.at.format("%b %d, %Y %H:%M").to_string()} }
+                        }
+                        for change in &entry.changes {
+                            div { class: "text-sm py-0.5",
+                                span { class: "text-zinc-500", "{change.field}: " }
+                                if change.old.is_empty() {
+                                    span { class: "text-zinc-600 italic", "empty" }
+                                } else {
+                                    span { class: "text-zinc-400 line-through", "{change.old}" }
+                                }
+                                span { class: "text-zinc-500", " → " }
+                                if change.new.is_empty() {
+                                    span { class: "text-zinc-600 italic", "empty" }
+                                } else {
+                                    span { class: "text-zinc-100", "{change.new}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
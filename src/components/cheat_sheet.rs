@@ -0,0 +1,52 @@
+use crate::shortcuts::SHORTCUTS;
+use crate::state::{Modal, use_modal};
+use dioxus::prelude::*;
+
+/// Overlay listing every keyboard shortcut, opened with `?`.
+#[component]
+pub fn CheatSheet() -> Element {
+    let mut modal = use_modal();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+
+            div {
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-2xl max-h-[90vh] overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    h3 { class: "text-base font-semibold text-zinc-100", "Keyboard Shortcuts" }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+
+                div { class: "p-5 overflow-y-auto grid grid-cols-2 gap-x-8 gap-y-6",
+                    for (section, shortcuts) in SHORTCUTS.iter() {
+                        div {
+                            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3", "{section}" }
+                            div { class: "space-y-2",
+                                for (keys, action) in shortcuts.iter() {
+                                    div { class: "flex items-center justify-between gap-4",
+                                        span { class: "text-sm text-zinc-400", "{action}" }
+                                        span { class: "flex gap-1 shrink-0",
+                                            for key in keys.split(' ') {
+                                                kbd { class: "font-mono text-[10px] text-zinc-300 bg-dark-600 border border-zinc-700 px-1.5 py-0.5 rounded",
+                                                    "{key}"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
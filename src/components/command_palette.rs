@@ -0,0 +1,247 @@
+use crate::models::{ActivityType, AppData, DealStage};
+use crate::shortcuts::{arrow_step, scroll_into_view};
+use crate::state::{
+    Modal, View, redo, toggle_activity_completed, undo, update_deal_stage, use_app_data, use_modal, use_navigator,
+};
+use dcrm_core::search::{SearchResult, search};
+use dioxus::prelude::*;
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    Go(View),
+    Show(Modal),
+    Open(Box<SearchResult>),
+    MoveDeal(String, DealStage),
+    ToggleTask(String),
+    Undo,
+    Redo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Command {
+    group: &'static str,
+    label: String,
+    /// Shortcut or extra detail shown on the right.
+    hint: String,
+    action: Action,
+}
+
+impl Command {
+    fn new(group: &'static str, label: impl Into<String>, hint: impl Into<String>, action: Action) -> Self {
+        Self {
+            group,
+            label: label.into(),
+            hint: hint.into(),
+            action,
+        }
+    }
+}
+
+/// Commands that don't depend on a record.
+fn fixed_commands() -> Vec<Command> {
+    let mut commands: Vec<Command> = View::all()
+        .into_iter()
+        .enumerate()
+        .map(|(i, view)| {
+            let label = format!("Go to {}", view.display_name());
+            Command::new("Navigate", label, format!("Ctrl {}", i + 1), Action::Go(view))
+        })
+        .collect();
+
+    commands.extend([
+        Command::new("Create", "New contact", "", Action::Show(Modal::NewContact)),
+        Command::new("Create", "New deal", "", Action::Show(Modal::NewDeal)),
+        Command::new("Create", "New activity", "", Action::Show(Modal::NewActivity)),
+        Command::new("Contacts", "Find duplicate contacts", "", Action::Show(Modal::Duplicates)),
+        Command::new("Edit", "Undo", "Ctrl Z", Action::Undo),
+        Command::new("Edit", "Redo", "Ctrl Shift Z", Action::Redo),
+        Command::new("Help", "Keyboard shortcuts", "?", Action::Show(Modal::Shortcuts)),
+    ]);
+    commands
+}
+
+/// Opening, moving and completing the records that match `query`. A trailing stage
+/// name, as in "acme won", narrows deal moves to that stage.
+fn record_commands(data: &AppData, query: &str) -> Vec<Command> {
+    let (terms, stage) = match query.rsplit_once(' ') {
+        Some((rest, last)) => {
+            let stage = DealStage::all().into_iter().find(|s| s.display_name().eq_ignore_ascii_case(last));
+            match stage {
+                Some(stage) => (rest.trim(), Some(stage)),
+                None => (query, None),
+            }
+        }
+        None => (query, None),
+    };
+
+    let mut commands = Vec::new();
+    for result in search(data, terms) {
+        if stage.is_none() {
+            let label = format!("Open {}", result.title());
+            commands.push(Command::new(result.result_type(), label, result.subtitle(), Action::Open(Box::new(result.clone()))));
+        }
+
+        match &result {
+            SearchResult::Deal(deal) => {
+                let targets = DealStage::all()
+                    .into_iter()
+                    .filter(|s| *s != deal.stage && stage.is_none_or(|wanted| wanted == *s));
+                for target in targets {
+                    let label = format!("Move {} to {}", deal.title, target);
+                    let hint = format!("from {}", deal.stage);
+                    commands.push(Command::new("Deal", label, hint, Action::MoveDeal(deal.id.clone(), target)));
+                }
+            }
+            SearchResult::Activity(task) if task.activity_type == ActivityType::Task && stage.is_none() => {
+                let label = if task.completed {
+                    format!("Reopen {}", task.title)
+                } else {
+                    format!("Complete {}", task.title)
+                };
+                commands.push(Command::new("Task", label, "", Action::ToggleTask(task.id.clone())));
+            }
+            _ => {}
+        }
+    }
+    commands
+}
+
+/// Fixed commands whose words all appear in the query, then record commands.
+fn commands(data: &AppData, query: &str) -> Vec<Command> {
+    let query = query.trim();
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+    let mut commands: Vec<Command> = fixed_commands()
+        .into_iter()
+        .filter(|c| {
+            let text = format!("{} {}", c.group, c.label).to_lowercase();
+            words.iter().all(|w| text.contains(w.as_str()))
+        })
+        .collect();
+    if !query.is_empty() {
+        commands.extend(record_commands(data, query));
+    }
+    commands
+}
+
+// ============================================================================
+// Palette
+// ============================================================================
+
+/// Ctrl/Cmd+K: type to filter commands and records, arrows to pick, Enter to run.
+#[component]
+pub fn CommandPalette() -> Element {
+    let mut data = use_app_data();
+    let mut modal = use_modal();
+    let mut navigator = use_navigator();
+    let mut query = use_signal(String::new);
+    let mut highlighted = use_signal(|| 0usize);
+
+    let commands = commands(&data.read(), &query.read());
+    let selected = (*highlighted.read()).min(commands.len().saturating_sub(1));
+
+    let mut run = move |command: Command| match command.action {
+        Action::Go(view) => navigator.go(view),
+        Action::Show(next) => modal.set(next),
+        Action::Open(result) => navigator.open(&result),
+        Action::MoveDeal(id, stage) if DealStage::active().contains(&stage) => {
+            update_deal_stage(&mut data, &id, stage);
+            modal.set(Modal::None);
+        }
+        // Closing a deal asks for the reason
+        Action::MoveDeal(id, stage) => modal.set(Modal::CloseDeal(id, stage)),
+        Action::ToggleTask(id) => {
+            toggle_activity_completed(&mut data, &id);
+            modal.set(Modal::None);
+        }
+        Action::Undo => {
+            undo(&mut data);
+            modal.set(Modal::None);
+        }
+        Action::Redo => {
+            redo(&mut data);
+            modal.set(Modal::None);
+        }
+    };
+
+    let handle_key = {
+        let commands = commands.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == Key::Enter {
+                e.prevent_default();
+                if let Some(command) = commands.get(selected) {
+                    run(command.clone());
+                }
+            } else if let Some(next) = arrow_step(&e.key(), selected, commands.len()) {
+                e.prevent_default();
+                highlighted.set(next);
+                scroll_into_view(&format!("command-{}", next));
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-start justify-center pt-24 z-50",
+            onclick: move |_| modal.set(Modal::None),
+
+            div {
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-xl overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+
+                div { class: "p-4 border-b border-zinc-700",
+                    div {
+                        class: "flex items-center gap-2 bg-dark-700 border border-zinc-700 rounded-lg px-4 py-2 w-full
+                                focus-within:border-accent focus-within:ring-1 focus-within:ring-accent/20",
+                        span { class: "text-zinc-500", "›" }
+                        input {
+                            class: "flex-1 bg-transparent border-none outline-none text-zinc-100 text-sm placeholder-zinc-500",
+                            r#type: "text",
+                            placeholder: "Type a command, or a record to open, move or complete...",
+                            value: "{query}",
+                            oninput: move |e| {
+                                query.set(e.value());
+                                highlighted.set(0);
+                            },
+                            onkeydown: handle_key,
+                            onmounted: move |e: MountedEvent| async move {
+                                let _ = e.set_focus(true).await;
+                            },
+                        }
+                        span { class: "font-mono text-[10px] text-zinc-500 bg-dark-600 px-1.5 py-0.5 rounded", "ESC" }
+                    }
+                }
+
+                div { class: "max-h-96 overflow-y-auto py-1",
+                    if commands.is_empty() {
+                        div { class: "p-4 text-center text-zinc-500 text-sm", "No matching commands" }
+                    }
+                    for (i, command) in commands.into_iter().enumerate() {
+                        div {
+                            id: "command-{i}",
+                            class: if i == selected {
+                                "flex items-center gap-3 px-4 py-2 cursor-pointer bg-dark-700 border-l-2 border-l-accent"
+                            } else {
+                                "flex items-center gap-3 px-4 py-2 cursor-pointer border-l-2 border-l-transparent hover:bg-dark-700/50"
+                            },
+                            onmouseenter: move |_| highlighted.set(i),
+                            onclick: {
+                                let command = command.clone();
+                                move |_| run(command.clone())
+                            },
+                            span { class: "w-20 text-[10px] font-semibold text-zinc-500 uppercase tracking-wider", "{command.group}" }
+                            span { class: "flex-1 min-w-0 text-sm text-zinc-100 truncate", "{command.label}" }
+                            if !command.hint.is_empty() {
+                                span { class: "text-xs text-zinc-500 truncate max-w-48", "{command.hint}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,15 @@
+mod change_history;
+mod cheat_sheet;
+mod command_palette;
 mod sidebar;
 mod topbar;
 mod modals;
+mod toast;
 
+pub use change_history::{ChangeHistory, DetailTabs};
+pub use cheat_sheet::CheatSheet;
+pub use command_palette::CommandPalette;
 pub use sidebar::Sidebar;
 pub use topbar::TopBar;
 pub use modals::ModalContainer;
+pub use toast::{SaveToast, UndoToast};
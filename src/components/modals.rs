@@ -1,11 +1,22 @@
 use dioxus::prelude::*;
-use crate::models::{Contact, Deal, DealStage, Activity, ActivityType};
+use crate::components::{ChangeHistory, CheatSheet, CommandPalette, DetailTabs};
+use crate::models::{
+    Contact, Deal, DealStage, DealParticipant, DealRole, Activity, ActivityType, ActivityDetails, CallDetails,
+    CallOutcome, Direction, EmailDetails, MeetingDetails, Reminder, Frequency,
+    Recurrence, RecurrenceEnd, CloseInfo,
+    LineItem, BillingCycle, Currency, Money, OrgNode, DeleteMode, References, ValidationErrors,
+    DuplicatePair, RecordKind, format_duration, merge_notes, merge_tags,
+};
+use crate::shortcuts::{arrow_step, scroll_into_view};
 use crate::state::{
-    Modal, use_modal, use_app_data, use_search_query,
-    add_contact, update_contact, add_deal, update_deal, add_activity,
-    search, SearchResult,
+    Modal, use_modal, use_app_data, use_navigator, use_search_query,
+    add_contact, update_contact, add_deal, update_deal, update_deal_stage, close_deal, delete_deal,
+    delete_contact, merge_contacts, add_activity,
+    update_activity, toggle_activity_completed, update_series, cancel_series,
 };
-use chrono::Utc;
+use dcrm_core::search::{SearchResult, search};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
 
 #[component]
 pub fn ModalContainer() -> Element {
@@ -19,15 +30,105 @@ pub fn ModalContainer() -> Element {
             let contact = data.read().contact_by_id(id).cloned();
             rsx! { ContactModal { contact: contact } }
         },
+        Modal::DeleteContact(id) => {
+            let data = use_app_data();
+            let Some(contact) = data.read().contact_by_id(id).cloned() else {
+                return rsx! {};
+            };
+            let candidates = data
+                .read()
+                .contacts
+                .iter()
+                .filter(|c| c.id != contact.id)
+                .map(|c| (c.id.clone(), c.full_name()))
+                .collect::<Vec<_>>();
+            let references = data.read().contact_references(id);
+            let id = id.clone();
+            rsx! {
+                DeleteModal {
+                    kind: "contact",
+                    name: contact.full_name(),
+                    references: references,
+                    candidates: candidates,
+                    on_delete: move |mode| {
+                        let mut data = data;
+                        delete_contact(&mut data, &id, mode);
+                    },
+                }
+            }
+        },
+        Modal::Duplicates => rsx! { DuplicatesModal {} },
+        Modal::MergeContacts(first_id, second_id) => {
+            let data = use_app_data();
+            let first = data.read().contact_by_id(first_id).cloned();
+            let second = data.read().contact_by_id(second_id).cloned();
+            match (first, second) {
+                (Some(first), Some(second)) => rsx! { MergeContactsModal { first: first, second: second } },
+                _ => rsx! {},
+            }
+        },
         Modal::NewDeal => rsx! { DealModal { deal: None } },
         Modal::EditDeal(id) => {
             let data = use_app_data();
             let deal = data.read().deal_by_id(id).cloned();
             rsx! { DealModal { deal: deal } }
         },
-        Modal::NewActivity => rsx! { ActivityModal {} },
+        Modal::NewActivity => rsx! { ActivityModal { activity: None } },
+        Modal::NewDealActivity(deal_id) => rsx! { ActivityModal { activity: None, deal_id: deal_id.clone() } },
+        Modal::EditActivity(id) => {
+            let data = use_app_data();
+            let activity = data.read().activity_by_id(id).cloned();
+            rsx! { ActivityModal { activity: activity } }
+        },
         Modal::Search => rsx! { SearchModal {} },
-        Modal::ContactDetail(_) | Modal::DealDetail(_) => rsx! {},
+        Modal::CommandPalette => rsx! { CommandPalette {} },
+        Modal::Shortcuts => rsx! { CheatSheet {} },
+        Modal::CloseDeal(id, stage) => {
+            let data = use_app_data();
+            let deal = data.read().deal_by_id(id).cloned();
+            match deal {
+                Some(deal) => rsx! { CloseDealModal { deal: deal, stage: *stage } },
+                None => rsx! {},
+            }
+        },
+        Modal::DeleteDeal(id) => {
+            let data = use_app_data();
+            let Some(deal) = data.read().deal_by_id(id).cloned() else {
+                return rsx! {};
+            };
+            let candidates = data
+                .read()
+                .deals
+                .iter()
+                .filter(|d| d.id != deal.id)
+                .map(|d| (d.id.clone(), d.title.clone()))
+                .collect::<Vec<_>>();
+            let references = data.read().deal_references(id);
+            let id = id.clone();
+            rsx! {
+                DeleteModal {
+                    kind: "deal",
+                    name: deal.title.clone(),
+                    references: references,
+                    candidates: candidates,
+                    on_delete: move |mode| {
+                        let mut data = data;
+                        delete_deal(&mut data, &id, mode);
+                    },
+                }
+            }
+        },
+        Modal::DealDetail(id) => {
+            let data = use_app_data();
+            let deal = data.read().deal_by_id(id).cloned();
+            match deal {
+                Some(deal) => rsx! { DealDetailModal { deal: deal } },
+                None => rsx! {},
+            }
+        },
+        Modal::OrgChart(company) => rsx! { OrgChartModal { company: company.clone() } },
+        Modal::EditSeries(series_id) => rsx! { SeriesModal { series_id: series_id.clone() } },
+        Modal::ContactDetail(_) => rsx! {},
     }
 }
 
@@ -53,6 +154,7 @@ fn ContactModal(contact: Option<Contact>) -> Element {
     let mut position = use_signal(|| initial.position.clone().unwrap_or_default());
     let mut tags_str = use_signal(|| initial.tags.join(", "));
     let mut notes = use_signal(|| initial.notes.clone().unwrap_or_default());
+    let mut errors = use_signal(ValidationErrors::default);
     let contact_id = initial.id.clone();
 
     let handle_save = move |_| {
@@ -78,13 +180,16 @@ fn ContactModal(contact: Option<Contact>) -> Element {
             .collect();
         contact.updated_at = Utc::now();
         
-        if is_edit {
-            update_contact(&mut data, contact);
+        let saved = if is_edit {
+            update_contact(&mut data, contact)
         } else {
-            add_contact(&mut data, contact);
-        }
+            add_contact(&mut data, contact)
+        };
         
-        modal.set(Modal::None);
+        match saved {
+            Ok(()) => modal.set(Modal::None),
+            Err(e) => errors.set(e),
+        }
     };
 
     rsx! {
@@ -111,7 +216,7 @@ fn ContactModal(contact: Option<Contact>) -> Element {
                 // Body
                 div { class: "p-5 overflow-y-auto",
                     div { class: "grid grid-cols-2 gap-4",
-                        FormField { label: "First Name *",
+                        FormField { label: "First Name *", error: errors.read().message("first_name"),
                             input {
                                 class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                         focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -120,7 +225,7 @@ fn ContactModal(contact: Option<Contact>) -> Element {
                                 oninput: move |e| first_name.set(e.value()),
                             }
                         }
-                        FormField { label: "Last Name *",
+                        FormField { label: "Last Name *", error: errors.read().message("last_name"),
                             input {
                                 class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                         focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -131,7 +236,7 @@ fn ContactModal(contact: Option<Contact>) -> Element {
                         }
                     }
                     
-                    FormField { label: "Email *",
+                    FormField { label: "Email *", error: errors.read().message("email"),
                         input {
                             class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                     focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -141,7 +246,7 @@ fn ContactModal(contact: Option<Contact>) -> Element {
                         }
                     }
                     
-                    FormField { label: "Phone",
+                    FormField { label: "Phone", error: errors.read().message("phone"),
                         input {
                             class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                     focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -204,6 +309,7 @@ fn ContactModal(contact: Option<Contact>) -> Element {
                     button {
                         class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md 
                                 hover:bg-accent-dim transition-colors",
+                        "data-submit": true,
                         onclick: handle_save,
                         if is_edit { "Save Changes" } else { "Create Contact" }
                     }
@@ -213,6 +319,261 @@ fn ContactModal(contact: Option<Contact>) -> Element {
     }
 }
 
+// ============================================================================
+// Duplicate Contacts
+// ============================================================================
+
+#[component]
+fn DuplicatesModal() -> Element {
+    let mut modal = use_modal();
+    let data = use_app_data();
+    let pairs = data.read().find_duplicates();
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-2xl max-h-[90vh] flex flex-col overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "Possible Duplicates" }
+                        div { class: "text-sm text-zinc-500", "Matched on email, phone, or name and company" }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    if pairs.is_empty() {
+                        p { class: "text-sm text-zinc-500 text-center py-8", "No duplicate contacts found" }
+                    }
+                    for pair in pairs {
+                        DuplicateRow { pair: pair }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DuplicateRow(pair: DuplicatePair) -> Element {
+    let mut modal = use_modal();
+    let reasons: Vec<String> = pair.reasons.iter().map(|r| r.to_string()).collect();
+
+    rsx! {
+        div { class: "flex items-center gap-4 bg-dark-700 border border-zinc-700 rounded-lg px-4 py-3 mb-2",
+            div { class: "flex-1 grid grid-cols-2 gap-4 min-w-0",
+                for contact in [&pair.first, &pair.second] {
+                    div { class: "min-w-0",
+                        div { class: "text-sm text-zinc-100 truncate", "{contact.full_name()}" }
+                        div { class: "text-xs text-zinc-500 truncate", "{contact.email}" }
+                        if let Some(company) = &contact.company {
+                            div { class: "text-xs text-zinc-500 truncate", "{company}" }
+                        }
+                    }
+                }
+            }
+            div { class: "flex flex-col items-end gap-2",
+                div { class: "flex gap-1",
+                    for reason in reasons {
+                        span { class: "text-[10px] bg-amber-500/15 text-amber-400 px-2 py-0.5 rounded-full", "{reason}" }
+                    }
+                }
+                button {
+                    class: "px-3 py-1 text-xs bg-accent text-dark-900 font-medium rounded-md hover:bg-accent-dim transition-colors",
+                    onclick: {
+                        let first = pair.first.id.clone();
+                        let second = pair.second.id.clone();
+                        move |_| modal.set(Modal::MergeContacts(first.clone(), second.clone()))
+                    },
+                    "Merge..."
+                }
+            }
+        }
+    }
+}
+
+const MERGE_FIELDS: [&str; 6] = ["First name", "Last name", "Email", "Phone", "Company", "Position"];
+
+fn merge_field_values(contact: &Contact) -> [String; 6] {
+    [
+        contact.first_name.clone(),
+        contact.last_name.clone(),
+        contact.email.clone(),
+        contact.phone.clone().unwrap_or_default(),
+        contact.company.clone().unwrap_or_default(),
+        contact.position.clone().unwrap_or_default(),
+    ]
+}
+
+/// Side-by-side field picker for two contacts. The older record survives so
+/// its id, and everything already linked to it, stays put.
+#[component]
+fn MergeContactsModal(first: Contact, second: Contact) -> Element {
+    let mut modal = use_modal();
+    let mut data = use_app_data();
+
+    let (survivor, duplicate) = if first.created_at <= second.created_at {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let kept_values = merge_field_values(&survivor);
+    let other_values = merge_field_values(&duplicate);
+
+    // Prefer the surviving record's value unless it's blank
+    let mut take_other = use_signal(|| {
+        let mut picks = [false; 6];
+        for (i, pick) in picks.iter_mut().enumerate() {
+            *pick = kept_values[i].trim().is_empty() && !other_values[i].trim().is_empty();
+        }
+        picks
+    });
+    let tags = merge_tags(&survivor.tags, &duplicate.tags);
+    let mut notes = use_signal(|| {
+        merge_notes(survivor.notes.as_deref(), duplicate.notes.as_deref()).unwrap_or_default()
+    });
+    let mut errors = use_signal(ValidationErrors::default);
+
+    let handle_merge = {
+        let tags = tags.clone();
+        let (kept_values, other_values) = (kept_values.clone(), other_values.clone());
+        let duplicate_id = duplicate.id.clone();
+        move |_| {
+            let picks = *take_other.read();
+            let value = |i: usize| {
+                if picks[i] { other_values[i].clone() } else { kept_values[i].clone() }
+            };
+            let optional = |i: usize| Some(value(i)).filter(|v| !v.trim().is_empty());
+
+            let mut merged = survivor.clone();
+            merged.first_name = value(0);
+            merged.last_name = value(1);
+            merged.email = value(2);
+            merged.phone = optional(3);
+            merged.company = optional(4);
+            merged.position = optional(5);
+            merged.tags = tags.clone();
+            merged.notes = Some(notes.read().clone()).filter(|n| !n.trim().is_empty());
+            merged.updated_at = Utc::now();
+
+            match merge_contacts(&mut data, merged, &duplicate_id) {
+                Ok(()) => modal.set(Modal::Duplicates),
+                Err(e) => errors.set(e),
+            }
+        }
+    };
+
+    let field_keys = ["first_name", "last_name", "email", "phone", "company", "position"];
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-2xl max-h-[90vh] flex flex-col overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "Merge Contacts" }
+                        div { class: "text-sm text-zinc-500", "Pick the value to keep for each field" }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::Duplicates),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    for (i, label) in MERGE_FIELDS.iter().enumerate() {
+                        FormField { label: label, error: errors.read().message(field_keys[i]),
+                            div { class: "grid grid-cols-2 gap-2",
+                                MergeOption {
+                                    value: kept_values[i].clone(),
+                                    selected: !take_other.read()[i],
+                                    onselect: move |_| take_other.write()[i] = false,
+                                }
+                                MergeOption {
+                                    value: other_values[i].clone(),
+                                    selected: take_other.read()[i],
+                                    onselect: move |_| take_other.write()[i] = true,
+                                }
+                            }
+                        }
+                    }
+
+                    FormField { label: "Tags (combined)",
+                        div { class: "flex flex-wrap gap-1",
+                            if tags.is_empty() {
+                                span { class: "text-sm text-zinc-500", "No tags" }
+                            }
+                            for tag in &tags {
+                                span { class: "text-xs bg-dark-700 border border-zinc-700 px-2 py-0.5 rounded-full text-zinc-300", "{tag}" }
+                            }
+                        }
+                    }
+
+                    FormField { label: "Notes (combined)",
+                        textarea {
+                            class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                    min-h-24 resize-y focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                            value: "{notes}",
+                            oninput: move |e| notes.set(e.value()),
+                        }
+                    }
+
+                    p { class: "text-xs text-zinc-500",
+                        "Deals, activities and relationships of {duplicate.full_name()} move to the merged contact."
+                    }
+                }
+                
+                div { class: "flex justify-end gap-3 px-5 py-4 border-t border-zinc-700",
+                    button {
+                        class: "px-4 py-2 bg-dark-700 border border-zinc-700 text-zinc-100 text-sm font-medium
+                                rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: move |_| modal.set(Modal::Duplicates),
+                        "Back"
+                    }
+                    button {
+                        class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md 
+                                hover:bg-accent-dim transition-colors",
+                        onclick: handle_merge,
+                        "Merge"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MergeOption(value: String, selected: bool, onselect: EventHandler<MouseEvent>) -> Element {
+    let class = if selected {
+        "border-accent bg-accent/10 text-zinc-100"
+    } else {
+        "border-zinc-700 bg-dark-700 text-zinc-400 hover:border-zinc-600"
+    };
+
+    rsx! {
+        button {
+            class: "px-3 py-2 text-left text-sm border rounded-md truncate transition-colors {class}",
+            onclick: move |e| onselect.call(e),
+            if value.trim().is_empty() { "—" } else { "{value}" }
+        }
+    }
+}
+
 // ============================================================================
 // Deal Modal
 // ============================================================================
@@ -229,40 +590,116 @@ fn DealModal(deal: Option<Deal>) -> Element {
     
     let mut deal_title = use_signal(|| initial.title.clone());
     let mut company = use_signal(|| initial.company.clone());
-    let mut value = use_signal(|| initial.value.to_string());
+    let mut value = use_signal(|| initial.value.amount.to_string());
+    let mut currency = use_signal(|| initial.value.currency);
     let mut stage = use_signal(|| initial.stage);
+    let mut expected_close = use_signal(|| {
+        initial.expected_close.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()
+    });
     let mut probability = use_signal(|| initial.probability.to_string());
-    let mut contact_id = use_signal(|| initial.contact_id.clone());
+    let participants = use_signal(|| initial.participants.clone());
     let mut notes = use_signal(|| initial.notes.clone().unwrap_or_default());
+    let line_items = use_signal(|| initial.line_items.clone());
+    let mut errors = use_signal(ValidationErrors::default);
     let deal_id = initial.id.clone();
 
-    let contacts = data.read().contacts.clone();
+    let closed_value = initial
+        .close
+        .as_ref()
+        .filter(|_| !DealStage::active().contains(&*stage.read()))
+        .map(|c| c.final_value);
+    let derived_value: Option<Money> = if closed_value.is_some() {
+        closed_value
+    } else if line_items.read().is_empty() {
+        None
+    } else {
+        let total = line_items.read().iter().map(|i| i.contract_value()).sum();
+        Some(Money::new(total, *currency.read()))
+    };
 
     let handle_save = move |_| {
+        let mut problems = ValidationErrors::default();
+        let parsed_value = Money::parse(&value.read(), *currency.read());
+        if parsed_value.is_none() && line_items.read().is_empty() {
+            problems.add("value", "Enter a number");
+        }
+        // Parse wide so out-of-range input is reported rather than silently replaced
+        let parsed_probability = probability.read().trim().parse::<i64>().ok();
+        if parsed_probability.is_none() {
+            problems.add("probability", "Enter a number");
+        }
+        // An unchanged date keeps its time of day, so saving doesn't log a change
+        let parsed_close = match expected_close.read().as_str() {
+            "" => Some(None),
+            date if initial.expected_close.is_some_and(|d| d.format("%Y-%m-%d").to_string() == date) => {
+                Some(initial.expected_close)
+            }
+            date => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| Some(d.and_utc())),
+        };
+        if parsed_close.is_none() {
+            problems.add("expected_close", "Enter a date");
+        }
+
         let mut deal = Deal::new(
             deal_title.read().clone(),
             company.read().clone(),
-            value.read().parse().unwrap_or(0.0),
+            parsed_value.unwrap_or_else(|| Money::zero(*currency.read())),
         );
         
         if is_edit {
             deal.id = deal_id.clone();
             deal.created_at = initial.created_at;
+            deal.stage = initial.stage;
+            deal.rank = initial.rank;
+            deal.stage_history = initial.stage_history.clone();
+            deal.close = initial.close.clone();
         }
         
-        deal.stage = *stage.read();
-        deal.probability = probability.read().parse().unwrap_or(10);
-        deal.contact_id = contact_id.read().clone();
+        // Closing a deal goes through the close dialog so the reason gets captured
+        let new_stage = *stage.read();
+        let closing = !DealStage::active().contains(&new_stage) && new_stage != initial.stage;
+        if !closing {
+            deal.set_stage(new_stage);
+        }
+        match parsed_probability.map(u8::try_from) {
+            Some(Ok(p)) => deal.probability = p,
+            Some(Err(_)) => problems.add("probability", "Probability must be between 0 and 100"),
+            None => {}
+        }
+        deal.expected_close = parsed_close.flatten();
+        deal.participants = participants.read().clone();
         deal.notes = if notes.read().is_empty() { None } else { Some(notes.read().clone()) };
+        deal.line_items = line_items.read().clone();
+        deal.recalculate_value();
         deal.updated_at = Utc::now();
+        let id = deal.id.clone();
         
-        if is_edit {
-            update_deal(&mut data, deal);
+        if let Err(e) = deal.validate() {
+            problems.merge(e);
+        }
+        if !problems.is_empty() {
+            errors.set(problems);
+            return;
+        }
+        
+        let saved = if is_edit {
+            update_deal(&mut data, deal)
         } else {
-            add_deal(&mut data, deal);
+            add_deal(&mut data, deal)
+        };
+        if let Err(e) = saved {
+            errors.set(e);
+            return;
         }
         
-        modal.set(Modal::None);
+        if closing {
+            modal.set(Modal::CloseDeal(id, new_stage));
+        } else {
+            modal.set(Modal::None);
+        }
     };
 
     rsx! {
@@ -271,7 +708,7 @@ fn DealModal(deal: Option<Deal>) -> Element {
             onclick: move |_| modal.set(Modal::None),
             
             div { 
-                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-lg max-h-[90vh] overflow-hidden shadow-2xl",
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-2xl max-h-[90vh] overflow-y-auto shadow-2xl",
                 onclick: |e| e.stop_propagation(),
                 
                 div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
@@ -284,7 +721,7 @@ fn DealModal(deal: Option<Deal>) -> Element {
                 }
                 
                 div { class: "p-5 overflow-y-auto",
-                    FormField { label: "Deal Title *",
+                    FormField { label: "Deal Title *", error: errors.read().message("title"),
                         input {
                             class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                     placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -295,7 +732,7 @@ fn DealModal(deal: Option<Deal>) -> Element {
                         }
                     }
                     
-                    FormField { label: "Company *",
+                    FormField { label: "Company *", error: errors.read().message("company"),
                         input {
                             class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                     focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -305,17 +742,38 @@ fn DealModal(deal: Option<Deal>) -> Element {
                         }
                     }
                     
-                    div { class: "grid grid-cols-2 gap-4",
-                        FormField { label: "Value ($)",
-                            input {
+                    div { class: "grid grid-cols-3 gap-4",
+                        FormField { label: "Value", error: errors.read().message("value"),
+                            if let Some(derived) = derived_value {
+                                div { class: "w-full px-3 py-2 bg-dark-600 border border-zinc-700 rounded-md text-zinc-400 text-sm font-mono",
+                                    title: if closed_value.is_some() { "The value the deal closed at" } else { "Derived from line items" },
+                                    "{derived}"
+                                }
+                            } else {
+                                input {
+                                    class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                            focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                    r#type: "number",
+                                    value: "{value}",
+                                    oninput: move |e| value.set(e.value()),
+                                }
+                            }
+                        }
+                        FormField { label: "Currency",
+                            select {
                                 class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                         focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
-                                r#type: "number",
-                                value: "{value}",
-                                oninput: move |e| value.set(e.value()),
+                                onchange: move |e| currency.set(Currency::from_code(&e.value())),
+                                for c in Currency::all() {
+                                    option { 
+                                        value: "{c.code()}",
+                                        selected: *currency.read() == c,
+                                        "{c.symbol()} {c.code()}"
+                                    }
+                                }
                             }
                         }
-                        FormField { label: "Probability (%)",
+                        FormField { label: "Probability (%)", error: errors.read().message("probability"),
                             input {
                                 class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                         focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -328,52 +786,50 @@ fn DealModal(deal: Option<Deal>) -> Element {
                         }
                     }
                     
-                    FormField { label: "Stage",
-                        select {
-                            class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
-                                    focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
-                            value: "{stage.read().display_name()}",
-                            onchange: move |e| {
-                                let new_stage = match e.value().as_str() {
-                                    "Lead" => DealStage::Lead,
-                                    "Qualified" => DealStage::Qualified,
-                                    "Proposal" => DealStage::Proposal,
-                                    "Negotiation" => DealStage::Negotiation,
-                                    "Won" => DealStage::Won,
-                                    "Lost" => DealStage::Lost,
-                                    _ => DealStage::Lead,
-                                };
-                                stage.set(new_stage);
-                            },
-                            for s in DealStage::all() {
-                                option { 
-                                    value: "{s.display_name()}",
-                                    selected: *stage.read() == s,
-                                    "{s.display_name()}"
+                    div { class: "grid grid-cols-2 gap-4",
+                        FormField { label: "Stage",
+                            select {
+                                class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                        focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                value: "{stage.read().display_name()}",
+                                onchange: move |e| {
+                                    let new_stage = match e.value().as_str() {
+                                        "Lead" => DealStage::Lead,
+                                        "Qualified" => DealStage::Qualified,
+                                        "Proposal" => DealStage::Proposal,
+                                        "Negotiation" => DealStage::Negotiation,
+                                        "Won" => DealStage::Won,
+                                        "Lost" => DealStage::Lost,
+                                        _ => DealStage::Lead,
+                                    };
+                                    stage.set(new_stage);
+                                },
+                                for s in DealStage::all() {
+                                    option { 
+                                        value: "{s.display_name()}",
+                                        selected: *stage.read() == s,
+                                        "{s.display_name()}"
+                                    }
                                 }
                             }
                         }
-                    }
-                    
-                    FormField { label: "Contact",
-                        select {
-                            class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
-                                    focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
-                            onchange: move |e| {
-                                let val = e.value();
-                                contact_id.set(if val.is_empty() { None } else { Some(val) });
-                            },
-                            option { value: "", "Select a contact..." }
-                            for c in &contacts {
-                                option { 
-                                    value: "{c.id}",
-                                    selected: contact_id.read().as_ref() == Some(&c.id),
-                                    "{c.full_name()}"
-                                }
+                        FormField { label: "Expected Close", error: errors.read().message("expected_close"),
+                            input {
+                                class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                        focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                r#type: "date",
+                                value: "{expected_close}",
+                                oninput: move |e| expected_close.set(e.value()),
                             }
                         }
                     }
                     
+                    ParticipantsEditor { participants: participants }
+                    ErrorText { error: errors.read().message("participants") }
+                    
+                    LineItemsEditor { items: line_items, currency: *currency.read() }
+                    ErrorText { error: errors.read().message("line_items") }
+                    
                     FormField { label: "Notes",
                         textarea {
                             class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
@@ -394,6 +850,7 @@ fn DealModal(deal: Option<Deal>) -> Element {
                     button {
                         class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md 
                                 hover:bg-accent-dim transition-colors",
+                        "data-submit": true,
                         onclick: handle_save,
                         if is_edit { "Save Changes" } else { "Create Deal" }
                     }
@@ -403,63 +860,955 @@ fn DealModal(deal: Option<Deal>) -> Element {
     }
 }
 
-// ============================================================================
-// Activity Modal
-// ============================================================================
-
 #[component]
-fn ActivityModal() -> Element {
-    let mut modal = use_modal();
-    let mut data = use_app_data();
-    
-    let mut activity_type = use_signal(|| ActivityType::Task);
-    let mut title = use_signal(String::new);
-    let mut description = use_signal(String::new);
-    let mut contact_id = use_signal(|| None::<String>);
-    let mut deal_id = use_signal(|| None::<String>);
-
-    let contacts = data.read().contacts.clone();
-    let deals = data.read().deals.clone();
+fn ParticipantsEditor(participants: Signal<Vec<DealParticipant>>) -> Element {
+    let data = use_app_data();
+    let rows = participants.read().clone();
+    let available: Vec<Contact> = data
+        .read()
+        .contacts
+        .iter()
+        .filter(|c| !rows.iter().any(|p| p.contact_id == c.id))
+        .cloned()
+        .collect();
 
-    let handle_save = move |_| {
-        let mut activity = Activity::new(
-            *activity_type.read(),
-            title.read().clone(),
-        );
-        
-        activity.description = if description.read().is_empty() { None } else { Some(description.read().clone()) };
-        activity.contact_id = contact_id.read().clone();
-        activity.deal_id = deal_id.read().clone();
-        
-        add_activity(&mut data, activity);
-        modal.set(Modal::None);
-    };
+    let input_class = "w-full px-2 py-1.5 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                       focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
 
     rsx! {
-        div { 
-            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
-            onclick: move |_| modal.set(Modal::None),
-            
-            div { 
-                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-lg max-h-[90vh] overflow-hidden shadow-2xl",
-                onclick: |e| e.stop_propagation(),
-                
-                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
-                    h3 { class: "text-base font-semibold text-zinc-100", "New Activity" }
+        div { class: "mb-4",
+            label { class: "block text-xs font-medium text-zinc-400 mb-2", "Stakeholders" }
+
+            for (i, participant) in rows.iter().enumerate() {
+                div { class: "grid grid-cols-[1fr_160px_24px] gap-2 items-center mb-2",
+                    div { class: "text-sm text-zinc-100 truncate",
+                        {data.read().contact_by_id(&participant.contact_id).map(|c| c.full_name()).unwrap_or_else(|| "Unknown contact".to_string())}
+                        if i == 0 {
+                            span { class: "ml-2 text-[10px] text-zinc-500 uppercase tracking-wider", "Primary" }
+                        }
+                    }
+                    select {
+                        class: "{input_class}",
+                        onchange: move |e| participants.write()[i].role = DealRole::from_name(&e.value()),
+                        for role in DealRole::all() {
+                            option {
+                                value: "{role.display_name()}",
+                                selected: participant.role == role,
+                                "{role.display_name()}"
+                            }
+                        }
+                    }
                     button {
-                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
-                        onclick: move |_| modal.set(Modal::None),
-                        "✕"
+                        class: "text-zinc-500 hover:text-red-400 transition-colors",
+                        onclick: move |_| {
+                            participants.write().remove(i);
+                        },
+                        "×"
                     }
                 }
-                
-                div { class: "p-5 overflow-y-auto",
-                    // Activity type selector
-                    div { class: "mb-4",
-                        label { class: "block text-xs font-medium text-zinc-400 mb-2", "Type" }
-                        div { class: "flex gap-2 flex-wrap",
-                            for at in [ActivityType::Task, ActivityType::Call, ActivityType::Email, ActivityType::Meeting, ActivityType::Note] {
-                                button {
+            }
+
+            if !available.is_empty() {
+                select {
+                    class: "w-full px-3 py-2 bg-dark-700 border border-dashed border-zinc-700 rounded-md text-zinc-400 text-sm
+                            focus:border-accent outline-none transition-all",
+                    value: "",
+                    onchange: move |e| {
+                        let contact_id = e.value();
+                        if !contact_id.is_empty() {
+                            participants.write().push(DealParticipant { contact_id, role: DealRole::default() });
+                        }
+                    },
+                    option { value: "", "+ Add stakeholder..." }
+                    for c in &available {
+                        option { value: "{c.id}", "{c.full_name()}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn LineItemsEditor(items: Signal<Vec<LineItem>>, currency: Currency) -> Element {
+    let data = use_app_data();
+    let catalog_symbol = data.read().settings.reporting_currency.symbol().to_string();
+    let products = data.read().products.clone();
+    let rows = items.read().clone();
+    let mrr = Money::new(rows.iter().map(|i| i.mrr()).sum(), currency);
+
+    let input_class = "w-full px-2 py-1.5 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                       focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
+
+    rsx! {
+        div { class: "mb-4",
+            div { class: "flex items-center justify-between mb-2",
+                label { class: "block text-xs font-medium text-zinc-400", "Line Items" }
+                if mrr.amount > Decimal::ZERO {
+                    span { class: "text-xs text-zinc-500 font-mono", "MRR {mrr} • ARR {mrr.scale(Decimal::from(12))}" }
+                }
+            }
+
+            if !rows.is_empty() {
+                div { class: "grid grid-cols-[1fr_4rem_6rem_4rem_6rem_1.5rem] gap-2 text-[10px] text-zinc-500 uppercase tracking-wider mb-1",
+                    span { "Item" }
+                    span { "Qty" }
+                    span { "Unit Price" }
+                    span { "Disc. %" }
+                    span { "Billing" }
+                    span {}
+                }
+            }
+
+            for (i, item) in rows.into_iter().enumerate() {
+                div { class: "grid grid-cols-[1fr_4rem_6rem_4rem_6rem_1.5rem] gap-2 mb-2 items-center",
+                    input {
+                        class: "{input_class}",
+                        r#type: "text",
+                        value: "{item.name}",
+                        oninput: move |e| items.write()[i].name = e.value(),
+                    }
+                    input {
+                        class: "{input_class}",
+                        r#type: "number",
+                        min: "0",
+                        value: "{item.quantity}",
+                        oninput: move |e| {
+                            if let Ok(q) = e.value().parse() {
+                                items.write()[i].quantity = q;
+                            }
+                        },
+                    }
+                    input {
+                        class: "{input_class}",
+                        r#type: "number",
+                        value: "{item.unit_price}",
+                        oninput: move |e| {
+                            if let Ok(p) = e.value().parse() {
+                                items.write()[i].unit_price = p;
+                            }
+                        },
+                    }
+                    input {
+                        class: "{input_class}",
+                        r#type: "number",
+                        min: "0",
+                        max: "100",
+                        value: "{item.discount}",
+                        oninput: move |e| {
+                            if let Ok(d) = e.value().parse() {
+                                items.write()[i].discount = d;
+                            }
+                        },
+                    }
+                    select {
+                        class: "{input_class}",
+                        onchange: move |e| items.write()[i].billing = BillingCycle::from_name(&e.value()),
+                        for b in BillingCycle::all() {
+                            option {
+                                value: "{b.display_name()}",
+                                selected: item.billing == b,
+                                "{b.display_name()}"
+                            }
+                        }
+                    }
+                    button {
+                        class: "text-zinc-500 hover:text-red-400 transition-colors",
+                        onclick: move |_| {
+                            items.write().remove(i);
+                        },
+                        "×"
+                    }
+                }
+            }
+
+            select {
+                class: "w-full px-3 py-2 bg-dark-700 border border-dashed border-zinc-700 rounded-md text-zinc-400 text-sm
+                        focus:border-accent outline-none transition-all",
+                value: "",
+                onchange: move |e| {
+                    let choice = e.value();
+                    if choice == "custom" {
+                        items.write().push(LineItem::new(String::new(), Decimal::ZERO, BillingCycle::OneTime));
+                    } else if let Some(product) = data.read().product_by_id(&choice) {
+                        items.write().push(LineItem::from_product(product));
+                    }
+                },
+                option { value: "", "+ Add line item..." }
+                for p in &products {
+                    option { value: "{p.id}", "{p.name} ({catalog_symbol}{p.unit_price:.2} {p.billing})" }
+                }
+                option { value: "custom", "Custom item" }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Close Deal Modal
+// ============================================================================
+
+#[component]
+fn CloseDealModal(deal: Deal, stage: DealStage) -> Element {
+    let mut modal = use_modal();
+    let mut data = use_app_data();
+
+    let reasons = data.read().settings.close_reasons(stage).to_vec();
+    let title = if stage == DealStage::Won { "Mark Deal as Won" } else { "Mark Deal as Lost" };
+
+    let mut reason = use_signal(|| reasons.first().cloned().unwrap_or_default());
+    let mut note = use_signal(String::new);
+    let mut closed_on = use_signal(|| Utc::now().format("%Y-%m-%d").to_string());
+    let mut final_value = use_signal(|| deal.value.rounded().to_string());
+    let mut errors = use_signal(ValidationErrors::default);
+    let deal_id = deal.id.clone();
+
+    let handle_save = move |_| {
+        let Some(parsed_value) = Money::parse(&final_value.read(), deal.value.currency) else {
+            let mut problems = ValidationErrors::default();
+            problems.add("final_value", "Enter a number");
+            errors.set(problems);
+            return;
+        };
+        let closed_at = NaiveDate::parse_from_str(&closed_on.read(), "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc())
+            .unwrap_or_else(Utc::now);
+
+        let close = CloseInfo {
+            reason: reason.read().clone(),
+            note: if note.read().is_empty() { None } else { Some(note.read().clone()) },
+            closed_at,
+            final_value: parsed_value,
+        };
+
+        match close_deal(&mut data, &deal_id, stage, close) {
+            Ok(()) => modal.set(Modal::None),
+            Err(e) => errors.set(e),
+        }
+    };
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-lg max-h-[90vh] overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "{title}" }
+                        div { class: "text-sm text-zinc-500", "{deal.title}" }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    FormField { label: "Reason *", error: errors.read().message("reason"),
+                        select {
+                            class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                    focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                            onchange: move |e| reason.set(e.value()),
+                            for r in &reasons {
+                                option { 
+                                    value: "{r}",
+                                    selected: *reason.read() == *r,
+                                    "{r}"
+                                }
+                            }
+                        }
+                    }
+                    
+                    div { class: "grid grid-cols-2 gap-4",
+                        FormField { label: "Close Date",
+                            input {
+                                class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                        focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                r#type: "date",
+                                value: "{closed_on}",
+                                oninput: move |e| closed_on.set(e.value()),
+                            }
+                        }
+                        FormField { label: "Final Value", error: errors.read().message("final_value"),
+                            div { class: "flex items-center gap-2",
+                                span { class: "text-sm text-zinc-500", "{deal.value.currency.symbol()}" }
+                                input {
+                                    class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                            focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                    r#type: "number",
+                                    value: "{final_value}",
+                                    oninput: move |e| final_value.set(e.value()),
+                                }
+                            }
+                        }
+                    }
+                    
+                    FormField { label: "Note",
+                        textarea {
+                            class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                    placeholder-zinc-500 min-h-24 resize-y focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                            placeholder: "What made the difference?",
+                            value: "{note}",
+                            oninput: move |e| note.set(e.value()),
+                        }
+                    }
+                }
+                
+                div { class: "flex justify-end gap-3 px-5 py-4 border-t border-zinc-700",
+                    button {
+                        class: "px-4 py-2 bg-dark-700 border border-zinc-700 text-zinc-100 text-sm font-medium
+                                rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md 
+                                hover:bg-accent-dim transition-colors",
+                        "data-submit": true,
+                        onclick: handle_save,
+                        "{title}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Delete Modal
+// ============================================================================
+
+/// Confirms deleting a contact or deal, listing what references it and letting
+/// the user unlink, reassign or cascade to those records.
+#[component]
+fn DeleteModal(
+    kind: &'static str,
+    name: String,
+    references: References,
+    candidates: Vec<(String, String)>,
+    on_delete: EventHandler<DeleteMode>,
+) -> Element {
+    let mut modal = use_modal();
+    let choice = use_signal(|| "unlink".to_string());
+    let mut target = use_signal(|| candidates.first().map(|(id, _)| id.clone()).unwrap_or_default());
+
+    let handle_delete = move |_| {
+        let mode = match choice.read().as_str() {
+            "reassign" => DeleteMode::Reassign(target.read().clone()),
+            "cascade" => DeleteMode::Cascade,
+            _ => DeleteMode::Unlink,
+        };
+        on_delete.call(mode);
+        modal.set(Modal::None);
+    };
+
+    let referenced = !references.is_empty();
+    let linked = references.deals.len() + references.activities.len();
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-lg max-h-[90vh] flex flex-col overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "Delete {kind}" }
+                        div { class: "text-sm text-zinc-500", "{name}" }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    if !referenced {
+                        p { class: "text-sm text-zinc-400", "Nothing else references this {kind}." }
+                    } else {
+                        p { class: "text-sm text-zinc-400 mb-3", "{linked} record(s) reference this {kind}:" }
+                        div { class: "bg-dark-700 border border-zinc-700 rounded-lg px-3 py-2 mb-5 max-h-40 overflow-y-auto",
+                            for (_, title) in &references.deals {
+                                div { class: "text-sm text-zinc-300 py-0.5", "💼 {title}" }
+                            }
+                            for (_, title) in &references.activities {
+                                div { class: "text-sm text-zinc-300 py-0.5", "📋 {title}" }
+                            }
+                        }
+
+                        FormField { label: "Referencing records",
+                            div { class: "space-y-2",
+                                DeleteChoice {
+                                    value: "unlink",
+                                    label: "Unlink",
+                                    hint: "Keep them, without a {kind}",
+                                    choice: choice,
+                                }
+                                if !candidates.is_empty() {
+                                    DeleteChoice {
+                                        value: "reassign",
+                                        label: "Reassign",
+                                        hint: "Point them at another {kind}",
+                                        choice: choice,
+                                    }
+                                    if *choice.read() == "reassign" {
+                                        select {
+                                            class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                                    focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                            onchange: move |e| target.set(e.value()),
+                                            for (id, label) in &candidates {
+                                                option {
+                                                    value: "{id}",
+                                                    selected: *target.read() == *id,
+                                                    "{label}"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                DeleteChoice {
+                                    value: "cascade",
+                                    label: "Trash them too",
+                                    hint: if kind == "contact" {
+                                        "Deals only go where it's the primary contact; they can be restored from the Trash"
+                                    } else {
+                                        "They can be restored from the Trash"
+                                    },
+                                    choice: choice,
+                                }
+                            }
+                        }
+                    }
+
+                    if references.relationships > 0 {
+                        p { class: "text-xs text-zinc-500 mt-3",
+                            "{references.relationships} relationship(s) are kept in case the {kind} is restored."
+                        }
+                    }
+                }
+                
+                div { class: "flex justify-end gap-3 px-5 py-4 border-t border-zinc-700",
+                    button {
+                        class: "px-4 py-2 bg-dark-700 border border-zinc-700 text-zinc-100 text-sm font-medium
+                                rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 bg-red-500 text-zinc-100 text-sm font-medium rounded-md 
+                                hover:bg-red-600 transition-colors",
+                        onclick: handle_delete,
+                        "Move to Trash"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DeleteChoice(value: &'static str, label: &'static str, hint: String, choice: Signal<String>) -> Element {
+    let checked = *choice.read() == value;
+
+    rsx! {
+        label { class: "flex items-center gap-3 cursor-pointer",
+            input {
+                r#type: "radio",
+                name: "delete-mode",
+                class: "accent-accent",
+                checked: checked,
+                onchange: move |_| choice.set(value.to_string()),
+            }
+            span { class: "text-sm text-zinc-100", "{label}" }
+            span { class: "text-xs text-zinc-500", "{hint}" }
+        }
+    }
+}
+
+// ============================================================================
+// Deal Detail Modal
+// ============================================================================
+
+#[component]
+fn DealDetailModal(deal: Deal) -> Element {
+    let mut modal = use_modal();
+    let mut data = use_app_data();
+    let showing_history = use_signal(|| false);
+    let change_count = data.read().audit_for(RecordKind::Deal, &deal.id).len();
+    let stakeholders = data.read().deal_stakeholders(&deal);
+    let mut activities: Vec<Activity> = data
+        .read()
+        .activities_for_deal(&deal.id)
+        .into_iter()
+        .cloned()
+        .collect();
+    activities.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+    let mrr = deal.mrr();
+    let expected_close = deal
+        .expected_close
+        .map(|d| d.format("%b %d, %Y").to_string())
+        .unwrap_or_else(|| "—".to_string());
+    let other_stages: Vec<DealStage> = DealStage::all()
+        .into_iter()
+        .filter(|s| *s != deal.stage)
+        .collect();
+    let deal_id = deal.id.clone();
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-2xl max-h-[90vh] flex flex-col overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-start justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "{deal.title}" }
+                        div { class: "flex items-center gap-3 mt-1",
+                            span { class: "text-sm text-zinc-500", "{deal.company}" }
+                            span { class: "font-mono text-sm text-accent", "{deal.format_value()}" }
+                            span { 
+                                class: "text-xs px-2 py-0.5 rounded {deal.stage.badge_class()}",
+                                "{deal.stage}"
+                            }
+                        }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                // Actions
+                div { class: "flex items-center gap-2 px-5 py-3 border-b border-zinc-700",
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: {
+                            let id = deal_id.clone();
+                            move |_| modal.set(Modal::EditDeal(id.clone()))
+                        },
+                        "Edit"
+                    }
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: {
+                            let id = deal_id.clone();
+                            move |_| modal.set(Modal::NewDealActivity(id.clone()))
+                        },
+                        "+ Log Activity"
+                    }
+                    select {
+                        class: "px-3 py-1.5 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                focus:border-accent outline-none transition-all",
+                        value: "",
+                        onchange: {
+                            let id = deal_id.clone();
+                            move |e: Event<FormData>| {
+                                let stage = DealStage::all()
+                                    .into_iter()
+                                    .find(|s| s.display_name() == e.value());
+                                match stage {
+                                    Some(stage) if DealStage::active().contains(&stage) => {
+                                        update_deal_stage(&mut data, &id, stage);
+                                    }
+                                    Some(stage) => modal.set(Modal::CloseDeal(id.clone(), stage)),
+                                    None => {}
+                                }
+                            }
+                        },
+                        option { value: "", "Move to..." }
+                        for stage in other_stages {
+                            option { value: "{stage.display_name()}", "{stage.display_name()}" }
+                        }
+                    }
+                    div { class: "flex-1" }
+                    button {
+                        class: "px-3 py-1.5 text-sm text-red-400 rounded-md hover:bg-red-500/10 transition-colors",
+                        onclick: {
+                            let id = deal_id.clone();
+                            move |_| modal.set(Modal::DeleteDeal(id.clone()))
+                        },
+                        "Delete"
+                    }
+                }
+                
+                DetailTabs { showing_history, count: change_count }
+
+                div { class: "p-5 overflow-y-auto",
+                    if showing_history() {
+                        ChangeHistory { kind: RecordKind::Deal, record_id: deal_id.clone() }
+                    } else {
+                        if let Some(close) = &deal.close {
+                            div { class: "bg-dark-700 border border-zinc-700 rounded-lg p-3 mb-6",
+                                div { class: "flex items-center justify-between",
+                                    span { class: "text-sm font-medium text-zinc-100", "{deal.stage}: {close.reason}" }
+                                    span { class: "text-xs text-zinc-500 font-mono", "{close.closed_at.format(\"%b %d, %Y\")}" }
+                                }
+                                if let Some(note) = &close.note {
+                                    p { class: "text-sm text-zinc-400 mt-1", "{note}" }
+                                }
+                            }
+                        }
+
+                        div { class: "grid grid-cols-4 gap-4 mb-6",
+                            DealField { label: "Value", value: deal.value.format_precise() }
+                            DealField { label: "Weighted", value: deal.weighted_value().format_precise() }
+                            DealField { label: "Probability", value: format!("{}%", deal.probability) }
+                            DealField { label: "Expected close", value: expected_close }
+                            if mrr.amount > Decimal::ZERO {
+                                DealField { label: "MRR", value: mrr.format_precise() }
+                                DealField { label: "ARR", value: deal.arr().format_precise() }
+                            }
+                            DealField { label: "In stage for", value: format_duration(deal.time_in_current_stage()) }
+                            DealField { label: "Created", value: deal.created_at.format("%b %d, %Y").to_string() }
+                            DealField { label: "Updated", value: deal.updated_at.format("%b %d, %Y").to_string() }
+                        }
+
+                        if !stakeholders.is_empty() {
+                            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3",
+                                "Stakeholders"
+                            }
+                            div { class: "grid grid-cols-2 gap-2 mb-6",
+                                for (contact, role) in &stakeholders {
+                                    div { class: "flex items-center gap-3 bg-dark-700 border border-zinc-700 rounded-lg px-3 py-2",
+                                        div { class: "w-8 h-8 rounded-full bg-accent/10 flex items-center justify-center text-xs font-semibold text-accent",
+                                            "{contact.initials()}"
+                                        }
+                                        div { class: "min-w-0",
+                                            div { class: "text-sm text-zinc-100 truncate", "{contact.full_name()}" }
+                                            div { class: "text-xs text-zinc-500 truncate", "{role} • {contact.email}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if !deal.line_items.is_empty() {
+                            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3",
+                                "Line Items"
+                            }
+                            LineItemsTable { deal: deal.clone() }
+                        }
+
+                        div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3",
+                            "Activity ({activities.len()})"
+                        }
+                        div { class: "mb-6",
+                            if activities.is_empty() {
+                                p { class: "text-sm text-zinc-500", "No activities logged for this deal" }
+                            }
+                            for activity in activities {
+                                div {
+                                    class: "flex items-center gap-3 py-2 cursor-pointer hover:bg-dark-700/50 -mx-2 px-2 rounded transition-colors",
+                                    onclick: {
+                                        let id = activity.id.clone();
+                                        move |_| modal.set(Modal::EditActivity(id.clone()))
+                                    },
+                                    div { class: "w-7 h-7 rounded-full bg-dark-700 flex items-center justify-center text-xs",
+                                        "{activity.activity_type.icon()}"
+                                    }
+                                    div { class: "flex-1 min-w-0",
+                                        div {
+                                            class: if activity.completed { "text-sm text-zinc-500 line-through" } else { "text-sm text-zinc-100" },
+                                            "{activity.title}"
+                                        }
+                                        div { class: "text-xs text-zinc-500 truncate",
+                                            {activity.details.summary().unwrap_or_else(|| activity.format_date())}
+                                        }
+                                    }
+                                    if activity.is_overdue() {
+                                        span { class: "text-xs text-red-400", "Overdue" }
+                                    }
+                                }
+                            }
+                        }
+
+                        if let Some(notes) = &deal.notes {
+                            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3",
+                                "Notes"
+                            }
+                            p { class: "text-sm text-zinc-300 whitespace-pre-wrap mb-6", "{notes}" }
+                        }
+
+                        div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3",
+                            "Stage History"
+                        }
+                        StageTimeline { deal: deal.clone() }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DealField(label: &'static str, value: String) -> Element {
+    rsx! {
+        div {
+            div { class: "text-xs text-zinc-500 mb-1", "{label}" }
+            div { class: "font-mono text-sm text-zinc-100", "{value}" }
+        }
+    }
+}
+
+#[component]
+fn LineItemsTable(deal: Deal) -> Element {
+    let mrr = deal.mrr();
+    let symbol = deal.value.currency.symbol();
+
+    rsx! {
+        table { class: "w-full mb-6",
+            thead {
+                tr { class: "border-b border-zinc-700 text-[10px] text-zinc-500 uppercase tracking-wider",
+                    th { class: "text-left py-2", "Item" }
+                    th { class: "text-right py-2", "Qty" }
+                    th { class: "text-right py-2", "Unit" }
+                    th { class: "text-right py-2", "Disc." }
+                    th { class: "text-right py-2", "Billing" }
+                    th { class: "text-right py-2", "Total" }
+                }
+            }
+            tbody {
+                for item in &deal.line_items {
+                    tr { class: "border-b border-zinc-800 text-sm",
+                        td { class: "py-2 text-zinc-100", "{item.name}" }
+                        td { class: "py-2 text-right font-mono text-zinc-400", "{item.quantity}" }
+                        td { class: "py-2 text-right font-mono text-zinc-400", "{symbol}{item.unit_price:.2}" }
+                        td { class: "py-2 text-right font-mono text-zinc-400", "{item.discount}%" }
+                        td { class: "py-2 text-right text-zinc-400", "{item.billing}" }
+                        td { class: "py-2 text-right font-mono text-zinc-100", "{symbol}{item.total():.2}" }
+                    }
+                }
+            }
+        }
+        div { class: "flex justify-end gap-6 -mt-4 mb-6 text-xs text-zinc-500",
+            if mrr.amount > Decimal::ZERO {
+                span { "MRR " span { class: "font-mono text-zinc-100", "{mrr}" } }
+                span { "ARR " span { class: "font-mono text-zinc-100", "{deal.arr()}" } }
+            }
+            span { "Deal value " span { class: "font-mono text-accent", "{deal.value}" } }
+        }
+    }
+}
+
+#[component]
+fn StageTimeline(deal: Deal) -> Element {
+    let history = deal.stage_history.clone();
+    let last = history.len().saturating_sub(1);
+
+    rsx! {
+        div { class: "relative pl-5 border-l border-zinc-700 space-y-4",
+            for (i, change) in history.iter().enumerate() {
+                {
+                    let spent = match history.get(i + 1) {
+                        Some(next) => format_duration(next.at - change.at),
+                        None => format!("{} so far", format_duration(deal.time_in_current_stage())),
+                    };
+                    let label = match change.from {
+                        Some(from) => format!("{} → {}", from, change.to),
+                        None => format!("Created in {}", change.to),
+                    };
+                    let at = change.at.format("%b %d, %Y %H:%M").to_string();
+
+                    rsx! {
+                        div { class: "relative",
+                            span {
+                                class: "absolute -left-[25px] top-1.5 w-2.5 h-2.5 rounded-full ring-4 ring-dark-800",
+                                style: "background-color: {change.to.color()}",
+                            }
+                            div { class: "flex items-center gap-2",
+                                span { class: "text-sm text-zinc-100", "{label}" }
+                                if change.is_regression() {
+                                    span { class: "text-[10px] bg-amber-500/15 text-amber-400 px-1.5 py-0.5 rounded", "Regression" }
+                                }
+                                if i == last {
+                                    span { class: "text-[10px] bg-accent/10 text-accent px-1.5 py-0.5 rounded", "Current" }
+                                }
+                            }
+                            div { class: "text-xs text-zinc-500 font-mono mt-0.5",
+                                "{at} • {spent}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Activity Modal
+// ============================================================================
+
+#[component]
+fn ActivityModal(activity: Option<Activity>, deal_id: Option<String>) -> Element {
+    let mut modal = use_modal();
+    let mut data = use_app_data();
+    
+    let is_edit = activity.is_some();
+    let modal_title = if is_edit { "Edit Activity" } else { "New Activity" };
+    
+    let initial = activity.unwrap_or_else(|| {
+        // Logging from a deal links the deal and its primary contact up front
+        let mut activity = Activity::new(ActivityType::Task, String::new());
+        if let Some(deal) = deal_id.as_ref().and_then(|id| data.read().deal_by_id(id).cloned()) {
+            activity.contact_id = deal.primary_contact_id().map(String::from);
+            activity.deal_id = Some(deal.id);
+        }
+        activity
+    });
+    
+    let mut activity_type = use_signal(|| initial.activity_type);
+    let mut title = use_signal(|| initial.title.clone());
+    let mut description = use_signal(|| initial.description.clone().unwrap_or_default());
+    let mut contact_id = use_signal(|| initial.contact_id.clone());
+    let mut deal_id = use_signal(|| initial.deal_id.clone());
+    let mut due = use_signal(|| initial.due_date.map(to_local_input).unwrap_or_default());
+    let mut reminder = use_signal(|| initial.reminder);
+    let mut completed = use_signal(|| initial.completed);
+    let frequency = use_signal(|| None::<Frequency>);
+    let interval = use_signal(|| 1u32);
+    let end = use_signal(RecurrenceEnd::default);
+    let call = use_signal(|| match &initial.details {
+        ActivityDetails::Call(details) => details.clone(),
+        _ => CallDetails::default(),
+    });
+    let meeting = use_signal(|| match &initial.details {
+        ActivityDetails::Meeting(details) => details.clone(),
+        _ => MeetingDetails::default(),
+    });
+    let email = use_signal(|| match &initial.details {
+        ActivityDetails::Email(details) => details.clone(),
+        _ => EmailDetails::default(),
+    });
+    let mut errors = use_signal(ValidationErrors::default);
+    let series_id = initial.series_id().map(String::from);
+    let series_summary = initial.recurrence.as_ref().map(|r| r.describe()).unwrap_or_default();
+
+    let contacts = data.read().contacts.clone();
+    let deals = data.read().deals.clone();
+
+    // Stakeholders of the selected deal are listed first in the contact picker
+    let stakeholders: Vec<(Contact, DealRole)> = deal_id
+        .read()
+        .as_ref()
+        .and_then(|id| data.read().deal_by_id(id).map(|d| data.read().deal_stakeholders(d)))
+        .unwrap_or_default();
+    let other_contacts: Vec<Contact> = contacts
+        .iter()
+        .filter(|c| !stakeholders.iter().any(|(s, _)| s.id == c.id))
+        .cloned()
+        .collect();
+
+    let handle_save = move |_| {
+        let mut activity = Activity::new(
+            *activity_type.read(),
+            title.read().clone(),
+        );
+        
+        if is_edit {
+            activity.id = initial.id.clone();
+            activity.created_at = initial.created_at;
+            activity.recurrence = initial.recurrence.clone();
+            activity.completed = initial.completed;
+        }
+        
+        activity.description = if description.read().is_empty() { None } else { Some(description.read().clone()) };
+        activity.contact_id = contact_id.read().clone();
+        activity.deal_id = deal_id.read().clone();
+        activity.due_date = parse_local_datetime(&due.read());
+        let mut problems = ValidationErrors::default();
+        if activity.due_date.is_none() && !due.read().is_empty() {
+            problems.add("due_date", "Enter a valid date and time");
+        }
+        if activity.due_date.is_some() {
+            activity.reminder = *reminder.read();
+        }
+        // Keep the sent flag only while the reminder still points at the same moment
+        activity.reminder_sent = is_edit
+            && initial.reminder_sent
+            && activity.reminder_at() == initial.reminder_at();
+        if let Some(f) = *frequency.read() {
+            activity.recurrence = Some(Recurrence::new(f, *interval.read(), *end.read()));
+        }
+        activity.details = match activity.activity_type {
+            ActivityType::Call => ActivityDetails::Call(call.read().clone()),
+            ActivityType::Meeting => ActivityDetails::Meeting(meeting.read().clone()),
+            ActivityType::Email => ActivityDetails::Email(email.read().clone()),
+            _ => ActivityDetails::None,
+        };
+        
+        let id = activity.id.clone();
+        
+        if let Err(e) = activity.validate() {
+            problems.merge(e);
+        }
+        if !problems.is_empty() {
+            errors.set(problems);
+            return;
+        }
+        
+        let saved = if is_edit {
+            update_activity(&mut data, activity)
+        } else {
+            add_activity(&mut data, activity)
+        };
+        if let Err(e) = saved {
+            errors.set(e);
+            return;
+        }
+        
+        if is_edit {
+            // Completion goes through the toggle so recurring activities roll over
+            if *completed.read() != initial.completed {
+                toggle_activity_completed(&mut data, &id);
+            }
+        } else if *completed.read() {
+            toggle_activity_completed(&mut data, &id);
+        }
+        modal.set(Modal::None);
+    };
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-lg max-h-[90vh] overflow-y-auto shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    h3 { class: "text-base font-semibold text-zinc-100", "{modal_title}" }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    // Activity type selector
+                    div { class: "mb-4",
+                        label { class: "block text-xs font-medium text-zinc-400 mb-2", "Type" }
+                        div { class: "flex gap-2 flex-wrap",
+                            for at in [ActivityType::Task, ActivityType::Call, ActivityType::Email, ActivityType::Meeting, ActivityType::Note] {
+                                button {
                                     class: if *activity_type.read() == at { 
                                         "px-3 py-1.5 text-sm rounded-md bg-dark-600 border border-zinc-600 text-zinc-100" 
                                     } else { 
@@ -472,7 +1821,7 @@ fn ActivityModal() -> Element {
                         }
                     }
                     
-                    FormField { label: "Title *",
+                    FormField { label: "Title *", error: errors.read().message("title"),
                         input {
                             class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
                                     placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
@@ -493,6 +1842,16 @@ fn ActivityModal() -> Element {
                         }
                     }
                     
+                    match *activity_type.read() {
+                        ActivityType::Call => rsx! { CallFields { call: call } },
+                        ActivityType::Meeting => rsx! {
+                            MeetingFields { meeting: meeting }
+                            ErrorText { error: errors.read().message("meeting") }
+                        },
+                        ActivityType::Email => rsx! { EmailFields { email: email } },
+                        _ => rsx! {},
+                    }
+                    
                     div { class: "grid grid-cols-2 gap-4",
                         FormField { label: "Contact",
                             select {
@@ -503,8 +1862,33 @@ fn ActivityModal() -> Element {
                                     contact_id.set(if val.is_empty() { None } else { Some(val) });
                                 },
                                 option { value: "", "Select contact..." }
-                                for c in &contacts {
-                                    option { value: "{c.id}", "{c.full_name()}" }
+                                if stakeholders.is_empty() {
+                                    for c in &contacts {
+                                        option {
+                                            value: "{c.id}",
+                                            selected: contact_id.read().as_ref() == Some(&c.id),
+                                            "{c.full_name()}"
+                                        }
+                                    }
+                                } else {
+                                    optgroup { label: "Deal stakeholders",
+                                        for (c, role) in &stakeholders {
+                                            option {
+                                                value: "{c.id}",
+                                                selected: contact_id.read().as_ref() == Some(&c.id),
+                                                "{c.full_name()} ({role})"
+                                            }
+                                        }
+                                    }
+                                    optgroup { label: "Other contacts",
+                                        for c in &other_contacts {
+                                            option {
+                                                value: "{c.id}",
+                                                selected: contact_id.read().as_ref() == Some(&c.id),
+                                                "{c.full_name()}"
+                                            }
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -514,15 +1898,81 @@ fn ActivityModal() -> Element {
                                         focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
                                 onchange: move |e| {
                                     let val = e.value();
+                                    if contact_id.read().is_none() {
+                                        let primary = data
+                                            .read()
+                                            .deal_by_id(&val)
+                                            .and_then(|d| d.primary_contact_id().map(String::from));
+                                        contact_id.set(primary);
+                                    }
                                     deal_id.set(if val.is_empty() { None } else { Some(val) });
                                 },
                                 option { value: "", "Select deal..." }
                                 for d in &deals {
-                                    option { value: "{d.id}", "{d.title}" }
+                                    option {
+                                        value: "{d.id}",
+                                        selected: deal_id.read().as_ref() == Some(&d.id),
+                                        "{d.title}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    
+                    div { class: "grid grid-cols-2 gap-4",
+                        FormField { label: "Due", error: errors.read().message("due_date"),
+                            input {
+                                class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                        focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                                r#type: "datetime-local",
+                                value: "{due}",
+                                oninput: move |e| due.set(e.value()),
+                            }
+                        }
+                        FormField { label: "Reminder", error: errors.read().message("reminder"),
+                            select {
+                                class: "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                        focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all
+                                        disabled:opacity-50",
+                                disabled: due.read().is_empty(),
+                                onchange: move |e| reminder.set(Reminder::from_name(&e.value())),
+                                option { value: "", "No reminder" }
+                                for r in Reminder::all() {
+                                    option {
+                                        value: "{r.display_name()}",
+                                        selected: *reminder.read() == Some(r),
+                                        "{r.display_name()}"
+                                    }
                                 }
                             }
                         }
                     }
+                    
+                    if let Some(series_id) = series_id.clone() {
+                        div { class: "flex items-center justify-between mb-4 text-sm",
+                            span { class: "text-zinc-500",
+                                "↻ {series_summary}"
+                            }
+                            button {
+                                class: "text-zinc-400 hover:text-accent transition-colors",
+                                onclick: move |_| modal.set(Modal::EditSeries(series_id.clone())),
+                                "Edit series"
+                            }
+                        }
+                    } else {
+                        RecurrenceEditor { frequency: frequency, interval: interval, end: end }
+                        ErrorText { error: errors.read().message("recurrence") }
+                    }
+                    
+                    label { class: "flex items-center gap-2 text-sm text-zinc-300 cursor-pointer",
+                        input {
+                            r#type: "checkbox",
+                            class: "accent-accent",
+                            checked: *completed.read(),
+                            onchange: move |e| completed.set(e.checked()),
+                        }
+                        "Completed"
+                    }
                 }
                 
                 div { class: "flex justify-end gap-3 px-5 py-4 border-t border-zinc-700",
@@ -535,8 +1985,425 @@ fn ActivityModal() -> Element {
                     button {
                         class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md 
                                 hover:bg-accent-dim transition-colors",
+                        "data-submit": true,
+                        onclick: handle_save,
+                        if is_edit { "Save Changes" } else { "Create Activity" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CallFields(call: Signal<CallDetails>) -> Element {
+    let input_class = "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                       focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
+    let details = call.read().clone();
+
+    rsx! {
+        div { class: "grid grid-cols-3 gap-4",
+            FormField { label: "Direction",
+                select {
+                    class: "{input_class}",
+                    onchange: move |e| call.write().direction = Direction::from_name(&e.value()),
+                    for d in Direction::all() {
+                        option { value: "{d.display_name()}", selected: details.direction == d, "{d.display_name()}" }
+                    }
+                }
+            }
+            FormField { label: "Duration (min)",
+                input {
+                    class: "{input_class}",
+                    r#type: "number",
+                    min: "0",
+                    value: details.duration_minutes.map(|m| m.to_string()).unwrap_or_default(),
+                    oninput: move |e| call.write().duration_minutes = e.value().parse().ok(),
+                }
+            }
+            FormField { label: "Outcome",
+                select {
+                    class: "{input_class}",
+                    onchange: move |e| call.write().outcome = CallOutcome::from_name(&e.value()),
+                    option { value: "", "Not recorded" }
+                    for o in CallOutcome::all() {
+                        option { value: "{o.display_name()}", selected: details.outcome == Some(o), "{o.display_name()}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MeetingFields(meeting: Signal<MeetingDetails>) -> Element {
+    let data = use_app_data();
+    let input_class = "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                       focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
+    let details = meeting.read().clone();
+    let attendees: Vec<Contact> = details
+        .attendees
+        .iter()
+        .filter_map(|id| data.read().contact_by_id(id).cloned())
+        .collect();
+    let available: Vec<Contact> = data
+        .read()
+        .contacts
+        .iter()
+        .filter(|c| !details.attendees.contains(&c.id))
+        .cloned()
+        .collect();
+
+    rsx! {
+        div { class: "grid grid-cols-2 gap-4",
+            FormField { label: "Start",
+                input {
+                    class: "{input_class}",
+                    r#type: "datetime-local",
+                    value: details.start.map(to_local_input).unwrap_or_default(),
+                    oninput: move |e| meeting.write().start = parse_local_datetime(&e.value()),
+                }
+            }
+            FormField { label: "End",
+                input {
+                    class: "{input_class}",
+                    r#type: "datetime-local",
+                    value: details.end.map(to_local_input).unwrap_or_default(),
+                    oninput: move |e| meeting.write().end = parse_local_datetime(&e.value()),
+                }
+            }
+        }
+        FormField { label: "Location",
+            input {
+                class: "{input_class} placeholder-zinc-500",
+                r#type: "text",
+                placeholder: "Office, Zoom link, ...",
+                value: details.location.clone().unwrap_or_default(),
+                oninput: move |e| {
+                    let value = e.value();
+                    meeting.write().location = if value.is_empty() { None } else { Some(value) };
+                },
+            }
+        }
+        FormField { label: "Attendees",
+            div { class: "flex gap-2 flex-wrap mb-2",
+                for contact in attendees {
+                    span { class: "flex items-center gap-1 text-xs bg-dark-700 border border-zinc-700 px-2 py-1 rounded text-zinc-300",
+                        "{contact.full_name()}"
+                        button {
+                            class: "text-zinc-500 hover:text-red-400 transition-colors",
+                            onclick: move |_| meeting.write().attendees.retain(|id| *id != contact.id),
+                            "×"
+                        }
+                    }
+                }
+            }
+            if !available.is_empty() {
+                select {
+                    class: "w-full px-3 py-2 bg-dark-700 border border-dashed border-zinc-700 rounded-md text-zinc-400 text-sm
+                            focus:border-accent outline-none transition-all",
+                    value: "",
+                    onchange: move |e| {
+                        let id = e.value();
+                        if !id.is_empty() {
+                            meeting.write().attendees.push(id);
+                        }
+                    },
+                    option { value: "", "+ Add attendee..." }
+                    for c in &available {
+                        option { value: "{c.id}", "{c.full_name()}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn EmailFields(email: Signal<EmailDetails>) -> Element {
+    let input_class = "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                       focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
+    let details = email.read().clone();
+
+    rsx! {
+        div { class: "grid grid-cols-3 gap-4",
+            FormField { label: "Direction",
+                select {
+                    class: "{input_class}",
+                    onchange: move |e| email.write().direction = Direction::from_name(&e.value()),
+                    for d in Direction::all() {
+                        option { value: "{d.display_name()}", selected: details.direction == d, "{d.display_name()}" }
+                    }
+                }
+            }
+            div { class: "col-span-2",
+                FormField { label: "Subject",
+                    input {
+                        class: "{input_class}",
+                        r#type: "text",
+                        value: "{details.subject}",
+                        oninput: move |e| email.write().subject = e.value(),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RecurrenceEditor(
+    frequency: Signal<Option<Frequency>>,
+    interval: Signal<u32>,
+    end: Signal<RecurrenceEnd>,
+) -> Element {
+    let input_class = "w-full px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                       focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
+    let end_kind = match *end.read() {
+        RecurrenceEnd::Never => "never",
+        RecurrenceEnd::OnDate(_) => "date",
+        RecurrenceEnd::AfterCount(_) => "count",
+    };
+
+    rsx! {
+        div { class: "grid grid-cols-3 gap-4",
+            FormField { label: "Repeat",
+                select {
+                    class: "{input_class}",
+                    onchange: move |e| frequency.set(Frequency::from_name(&e.value())),
+                    option { value: "", "Does not repeat" }
+                    for f in Frequency::all() {
+                        option {
+                            value: "{f.display_name()}",
+                            selected: *frequency.read() == Some(f),
+                            "{f.display_name()}"
+                        }
+                    }
+                }
+            }
+            if frequency.read().is_some() {
+                FormField { label: "Every",
+                    input {
+                        class: "{input_class}",
+                        r#type: "number",
+                        min: "1",
+                        value: "{interval}",
+                        oninput: move |e| {
+                            if let Ok(n) = e.value().parse::<u32>() {
+                                interval.set(n.max(1));
+                            }
+                        },
+                    }
+                }
+                FormField { label: "Ends",
+                    select {
+                        class: "{input_class} mb-2",
+                        onchange: move |e| {
+                            end.set(match e.value().as_str() {
+                                "date" => RecurrenceEnd::OnDate(Utc::now() + chrono::Duration::days(90)),
+                                "count" => RecurrenceEnd::AfterCount(10),
+                                _ => RecurrenceEnd::Never,
+                            });
+                        },
+                        option { value: "never", selected: end_kind == "never", "Never" }
+                        option { value: "date", selected: end_kind == "date", "On date" }
+                        option { value: "count", selected: end_kind == "count", "After N times" }
+                    }
+                    match *end.read() {
+                        RecurrenceEnd::OnDate(date) => rsx! {
+                            input {
+                                class: "{input_class}",
+                                r#type: "date",
+                                value: "{date.format(\"%Y-%m-%d\")}",
+                                onchange: move |e| {
+                                    if let Some(d) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d")
+                                        .ok()
+                                        .and_then(|d| d.and_hms_opt(23, 59, 59))
+                                    {
+                                        end.set(RecurrenceEnd::OnDate(d.and_utc()));
+                                    }
+                                },
+                            }
+                        },
+                        RecurrenceEnd::AfterCount(count) => rsx! {
+                            input {
+                                class: "{input_class}",
+                                r#type: "number",
+                                min: "1",
+                                value: "{count}",
+                                oninput: move |e| {
+                                    if let Ok(n) = e.value().parse::<u32>() {
+                                        end.set(RecurrenceEnd::AfterCount(n.max(1)));
+                                    }
+                                },
+                            }
+                        },
+                        RecurrenceEnd::Never => rsx! {},
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SeriesModal(series_id: String) -> Element {
+    let mut modal = use_modal();
+    let mut data = use_app_data();
+
+    // Open occurrences carry the current rule; fall back to any occurrence
+    let rule = {
+        let data_ref = data.read();
+        let series: Vec<&Activity> = data_ref
+            .activities
+            .iter()
+            .filter(|a| a.series_id() == Some(series_id.as_str()))
+            .collect();
+        series
+            .iter()
+            .find(|a| !a.completed)
+            .or(series.first())
+            .and_then(|a| a.recurrence.clone().map(|r| (a.title.clone(), r)))
+    };
+
+    let frequency = use_signal(|| rule.as_ref().map(|(_, r)| r.frequency));
+    let interval = use_signal(|| rule.as_ref().map(|(_, r)| r.interval).unwrap_or(1));
+    let end = use_signal(|| rule.as_ref().map(|(_, r)| r.end).unwrap_or_default());
+
+    let Some((title, _)) = rule else {
+        return rsx! {};
+    };
+
+    let save_id = series_id.clone();
+    let handle_save = move |_| {
+        match *frequency.read() {
+            Some(f) => update_series(&mut data, &save_id, f, *interval.read(), *end.read()),
+            None => cancel_series(&mut data, &save_id),
+        }
+        modal.set(Modal::None);
+    };
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-lg max-h-[90vh] overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "Edit Series" }
+                        p { class: "text-xs text-zinc-500 mt-0.5", "{title}" }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    RecurrenceEditor { frequency: frequency, interval: interval, end: end }
+                    p { class: "text-xs text-zinc-500", "Changes apply to every open occurrence. Completed ones are left as they are." }
+                }
+                
+                div { class: "flex items-center justify-between gap-3 px-5 py-4 border-t border-zinc-700",
+                    button {
+                        class: "text-sm text-red-400 hover:text-red-300 transition-colors",
+                        onclick: move |_| {
+                            cancel_series(&mut data, &series_id);
+                            modal.set(Modal::None);
+                        },
+                        "Stop Series"
+                    }
+                    div { class: "flex gap-3",
+                        button {
+                            class: "px-4 py-2 bg-dark-700 border border-zinc-700 text-zinc-100 text-sm font-medium
+                                    rounded-md hover:bg-zinc-700 transition-colors",
+                            onclick: move |_| modal.set(Modal::None),
+                            "Cancel"
+                        }
+                        button {
+                            class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md 
+                                    hover:bg-accent-dim transition-colors",
+                            "data-submit": true,
                         onclick: handle_save,
-                        "Create Activity"
+                            "Save Series"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Org Chart Modal
+// ============================================================================
+
+#[component]
+fn OrgChartModal(company: String) -> Element {
+    let mut modal = use_modal();
+    let data = use_app_data();
+    let chart = data.read().org_chart(&company);
+
+    rsx! {
+        div { 
+            class: "fixed inset-0 bg-black/70 backdrop-blur-sm flex items-center justify-center z-50",
+            onclick: move |_| modal.set(Modal::None),
+            
+            div { 
+                class: "bg-dark-800 border border-zinc-700 rounded-xl w-full max-w-2xl max-h-[90vh] flex flex-col overflow-hidden shadow-2xl",
+                onclick: |e| e.stop_propagation(),
+                
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-700",
+                    div {
+                        h3 { class: "text-base font-semibold text-zinc-100", "{company}" }
+                        p { class: "text-xs text-zinc-500 mt-0.5", "Org chart from reporting lines and positions" }
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        onclick: move |_| modal.set(Modal::None),
+                        "✕"
+                    }
+                }
+                
+                div { class: "p-5 overflow-y-auto",
+                    if chart.is_empty() {
+                        p { class: "text-sm text-zinc-500", "No contacts at this company" }
+                    }
+                    for node in chart {
+                        OrgChartNode { node: node }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn OrgChartNode(node: OrgNode) -> Element {
+    rsx! {
+        div { class: "mb-2",
+            div { class: "flex items-center gap-3 bg-dark-700 border border-zinc-700 rounded-lg px-3 py-2",
+                div { class: "w-8 h-8 rounded-full bg-accent/10 flex items-center justify-center text-xs font-semibold text-accent",
+                    "{node.contact.initials()}"
+                }
+                div { class: "flex-1 min-w-0",
+                    div { class: "text-sm text-zinc-100 truncate", "{node.contact.full_name()}" }
+                    if let Some(position) = &node.contact.position {
+                        div { class: "text-xs text-zinc-500 truncate", "{position}" }
+                    }
+                }
+                if !node.reports.is_empty() {
+                    span { class: "text-xs text-zinc-500", "{node.reports.len()} reports" }
+                }
+            }
+            if !node.reports.is_empty() {
+                div { class: "ml-4 pl-4 pt-2 border-l border-zinc-700",
+                    for report in node.reports {
+                        OrgChartNode { node: report }
                     }
                 }
             }
@@ -551,10 +2418,35 @@ fn ActivityModal() -> Element {
 #[component]
 fn SearchModal() -> Element {
     let mut modal = use_modal();
+    let mut navigator = use_navigator();
     let mut search_query = use_search_query();
+    let mut highlighted = use_signal(|| 0usize);
     let data = use_app_data();
     
     let results = search(&data.read(), &search_query.read());
+    let selected = (*highlighted.read()).min(results.len().saturating_sub(1));
+
+    let mut open = move |result: &SearchResult| {
+        search_query.set(String::new());
+        modal.set(Modal::None);
+        navigator.open(result);
+    };
+
+    let handle_key = {
+        let results = results.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == Key::Enter {
+                e.prevent_default();
+                if let Some(result) = results.get(selected) {
+                    open(result);
+                }
+            } else if let Some(next) = arrow_step(&e.key(), selected, results.len()) {
+                e.prevent_default();
+                highlighted.set(next);
+                scroll_into_view(&format!("search-result-{}", next));
+            }
+        }
+    };
 
     rsx! {
         div { 
@@ -579,7 +2471,11 @@ fn SearchModal() -> Element {
                             r#type: "text",
                             placeholder: "Search contacts, deals, activities...",
                             value: "{search_query}",
-                            oninput: move |e| search_query.set(e.value()),
+                            oninput: move |e| {
+                                search_query.set(e.value());
+                                highlighted.set(0);
+                            },
+                            onkeydown: handle_key,
                             autofocus: true,
                         }
                         span { class: "font-mono text-[10px] text-zinc-500 bg-dark-600 px-1.5 py-0.5 rounded", "ESC" }
@@ -598,13 +2494,13 @@ fn SearchModal() -> Element {
                             "Start typing to search..."
                         }
                     } else {
-                        for result in results {
+                        for (i, result) in results.into_iter().enumerate() {
                             SearchResultItem {
-                                result: result,
-                                on_select: move |_| {
-                                    search_query.set(String::new());
-                                    modal.set(Modal::None);
-                                },
+                                id: "search-result-{i}",
+                                result: result.clone(),
+                                highlighted: i == selected,
+                                on_hover: move |_| highlighted.set(i),
+                                on_select: move |_| open(&result),
                             }
                         }
                     }
@@ -615,16 +2511,26 @@ fn SearchModal() -> Element {
 }
 
 #[component]
-fn SearchResultItem(result: SearchResult, on_select: EventHandler<MouseEvent>) -> Element {
+fn SearchResultItem(
+    id: String,
+    result: SearchResult,
+    highlighted: bool,
+    on_hover: EventHandler<MouseEvent>,
+    on_select: EventHandler<MouseEvent>,
+) -> Element {
     let (icon, type_label) = match &result {
         SearchResult::Contact(_) => ("◎", "Contact"),
         SearchResult::Deal(_) => ("◈", "Deal"),
         SearchResult::Activity(_) => ("◇", "Activity"),
     };
 
+    let bg = if highlighted { "bg-dark-700" } else { "" };
+
     rsx! {
         div {
-            class: "flex items-center gap-4 px-4 py-3 cursor-pointer hover:bg-dark-700 transition-colors border-b border-zinc-800 last:border-b-0",
+            id: "{id}",
+            class: "flex items-center gap-4 px-4 py-3 cursor-pointer transition-colors border-b border-zinc-800 last:border-b-0 {bg}",
+            onmouseenter: move |e| on_hover.call(e),
             onclick: move |e| on_select.call(e),
             
             div { class: "w-8 h-8 rounded-full bg-dark-700 flex items-center justify-center text-zinc-400",
@@ -648,11 +2554,35 @@ fn SearchResultItem(result: SearchResult, on_select: EventHandler<MouseEvent>) -
 // ============================================================================
 
 #[component]
-fn FormField(label: &'static str, children: Element) -> Element {
+fn FormField(label: &'static str, #[props(default)] error: Option<String>, children: Element) -> Element {
     rsx! {
         div { class: "mb-4",
             label { class: "block text-xs font-medium text-zinc-400 mb-2", "{label}" }
             {children}
+            ErrorText { error: error }
+        }
+    }
+}
+
+#[component]
+fn ErrorText(error: Option<String>) -> Element {
+    rsx! {
+        if let Some(error) = error {
+            p { class: "text-xs text-red-400 mt-1", "{error}" }
         }
     }
 }
+
+/// Parses the value of a `datetime-local` input, interpreted in the local timezone.
+fn parse_local_datetime(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Formats a timestamp for a `datetime-local` input in the local timezone.
+fn to_local_input(value: DateTime<Utc>) -> String {
+    value.with_timezone(&Local).format("%Y-%m-%dT%H:%M").to_string()
+}
@@ -8,6 +8,7 @@ pub fn Sidebar() -> Element {
 
     let pending_tasks = data.read().pending_tasks_count();
     let active_deals = data.read().active_deals_count();
+    let trashed = data.read().trash.len();
 
     rsx! {
         aside { 
@@ -62,6 +63,29 @@ pub fn Sidebar() -> Element {
                     }
                 }
 
+                // System Section
+                div { class: "mb-6",
+                    div { class: "text-[10px] font-semibold text-zinc-500 uppercase tracking-wider mb-2 px-3",
+                        "System"
+                    }
+
+                    NavItem {
+                        label: "Trash",
+                        icon: "⌫",
+                        active: *current_view.read() == View::Trash,
+                        onclick: move |_| current_view.set(View::Trash),
+                        badge: if trashed > 0 { Some(trashed.to_string()) } else { None },
+                    }
+
+                    NavItem {
+                        label: "Settings",
+                        icon: "⚙",
+                        active: *current_view.read() == View::Settings,
+                        onclick: move |_| current_view.set(View::Settings),
+                        badge: None,
+                    }
+                }
+
                 // Quick Stats
                 div { class: "mb-6",
                     div { class: "text-[10px] font-semibold text-zinc-500 uppercase tracking-wider mb-2 px-3",
@@ -73,11 +97,11 @@ pub fn Sidebar() -> Element {
                     }
                     QuickStat {
                         label: "Pipeline Value",
-                        value: format_currency(data.read().total_pipeline_value()),
+                        value: data.read().total_pipeline_value().format_compact(),
                     }
                     QuickStat {
                         label: "Won This Period",
-                        value: format_currency(data.read().won_deals_value()),
+                        value: data.read().won_deals_value().format_compact(),
                     }
                 }
             }
@@ -138,13 +162,3 @@ fn QuickStat(label: &'static str, value: String) -> Element {
         }
     }
 }
-
-fn format_currency(value: f64) -> String {
-    if value >= 1_000_000.0 {
-        format!("${:.1}M", value / 1_000_000.0)
-    } else if value >= 1_000.0 {
-        format!("${:.0}K", value / 1_000.0)
-    } else {
-        format!("${:.0}", value)
-    }
-}
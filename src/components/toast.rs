@@ -0,0 +1,104 @@
+use crate::state::{
+    SaveProblem, overwrite_file, reload_data, retry_save, undo, use_app_data, use_save_problem,
+};
+use dioxus::prelude::*;
+use std::time::Duration;
+
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// Offers an "Undo" button for a few seconds after a delete or merge.
+#[component]
+pub fn UndoToast() -> Element {
+    let mut data = use_app_data();
+    let mut dismissed = use_signal(|| 0u64);
+
+    // Hide the toast once its step has been on screen long enough
+    use_effect(move || {
+        let revision = data.read().history.revision();
+        spawn(async move {
+            tokio::time::sleep(TOAST_DURATION).await;
+            if *dismissed.peek() < revision {
+                dismissed.set(revision);
+            }
+        });
+    });
+
+    let history = &data.read().history;
+    let revision = history.revision();
+    let Some(command) = history
+        .last()
+        .filter(|c| c.destructive && revision > dismissed())
+    else {
+        return rsx! {};
+    };
+    let label = command.label.clone();
+
+    rsx! {
+        div { class: "fixed bottom-6 left-1/2 -translate-x-1/2 z-50 flex items-center gap-4 px-4 py-3 bg-dark-700 border border-zinc-700 rounded-lg shadow-lg",
+            span { class: "text-sm text-zinc-100", "{label}" }
+            button {
+                class: "text-sm font-medium text-accent hover:text-accent-dim transition-colors",
+                onclick: move |_| {
+                    undo(&mut data);
+                    dismissed.set(revision);
+                },
+                "Undo"
+            }
+            button {
+                class: "text-zinc-500 hover:text-zinc-300 transition-colors",
+                onclick: move |_| dismissed.set(revision),
+                "×"
+            }
+        }
+    }
+}
+
+/// Stays up while the data file doesn't hold what's on screen: after another
+/// program wrote it, or when a save failed.
+#[component]
+pub fn SaveToast() -> Element {
+    let mut data = use_app_data();
+    let mut problem = use_save_problem();
+
+    let Some(current) = problem() else {
+        return rsx! {};
+    };
+    let (message, conflict) = match &current {
+        SaveProblem::Conflict => (
+            "The data file was changed by another program. Your changes aren't saved yet.".to_string(),
+            true,
+        ),
+        SaveProblem::Failed(error) => (format!("Couldn't save: {}", error), false),
+    };
+
+    rsx! {
+        div { class: "fixed bottom-6 right-6 z-50 flex items-center gap-4 max-w-lg px-4 py-3 bg-dark-700 border border-red-500/40 rounded-lg shadow-lg",
+            span { class: "text-sm text-zinc-100", "{message}" }
+            if conflict {
+                button {
+                    class: "text-sm font-medium text-accent hover:text-accent-dim transition-colors whitespace-nowrap",
+                    title: "Show the file as it is now, dropping your unsaved changes",
+                    onclick: move |_| reload_data(&mut data),
+                    "Reload"
+                }
+                button {
+                    class: "text-sm font-medium text-red-400 hover:text-red-300 transition-colors whitespace-nowrap",
+                    title: "Save your version over the other program's changes",
+                    onclick: move |_| overwrite_file(&mut data),
+                    "Overwrite"
+                }
+            } else {
+                button {
+                    class: "text-sm font-medium text-accent hover:text-accent-dim transition-colors",
+                    onclick: move |_| retry_save(&mut data),
+                    "Retry"
+                }
+                button {
+                    class: "text-zinc-500 hover:text-zinc-300 transition-colors",
+                    onclick: move |_| problem.set(None),
+                    "×"
+                }
+            }
+        }
+    }
+}
@@ -1,21 +1,21 @@
 use dioxus::prelude::*;
-use crate::state::{View, Modal, use_current_view, use_modal, use_search_query};
+use crate::state::{View, Modal, redo, undo, use_app_data, use_current_view, use_modal, use_navigator, use_search_query};
 
 #[component]
 pub fn TopBar() -> Element {
     let current_view = use_current_view();
     let mut modal = use_modal();
+    let mut navigator = use_navigator();
     let mut search_query = use_search_query();
+    let mut data = use_app_data();
 
-    let title = match *current_view.read() {
-        View::Dashboard => "Dashboard",
-        View::Contacts => "Contacts",
-        View::Deals => "Deals",
-        View::Activities => "Activities",
-    };
+    let undo_label = data.read().history.undo_label().map(|l| format!("Undo {} (Ctrl+Z)", l));
+    let redo_label = data.read().history.redo_label().map(|l| format!("Redo {} (Ctrl+Shift+Z)", l));
+
+    let title = current_view.read().display_name().to_string();
 
     let new_button_label = match *current_view.read() {
-        View::Dashboard => None,
+        View::Dashboard | View::Trash | View::Settings => None,
         View::Contacts => Some("New Contact"),
         View::Deals => Some("New Deal"),
         View::Activities => Some("New Activity"),
@@ -41,23 +41,40 @@ pub fn TopBar() -> Element {
                     onfocus: move |_| modal.set(Modal::Search),
                     oninput: move |e| search_query.set(e.value()),
                 }
-                span { class: "font-mono text-[10px] text-zinc-500 bg-dark-600 px-1.5 py-0.5 rounded", "⌘K" }
+                span {
+                    class: "font-mono text-[10px] text-zinc-500 bg-dark-600 px-1.5 py-0.5 rounded cursor-pointer hover:text-zinc-300",
+                    title: "Command palette (Ctrl/⌘+K)",
+                    onclick: move |_| modal.set(Modal::CommandPalette),
+                    "⌘K"
+                }
             }
 
             // Actions
             div { class: "flex gap-2",
+                button {
+                    class: "px-2.5 py-2 text-sm text-zinc-400 rounded-md hover:bg-dark-700 hover:text-zinc-100 transition-colors disabled:opacity-40 disabled:pointer-events-none",
+                    title: undo_label.clone().unwrap_or_else(|| "Nothing to undo".to_string()),
+                    disabled: undo_label.is_none(),
+                    onclick: move |_| {
+                        undo(&mut data);
+                    },
+                    "↶"
+                }
+                button {
+                    class: "px-2.5 py-2 text-sm text-zinc-400 rounded-md hover:bg-dark-700 hover:text-zinc-100 transition-colors disabled:opacity-40 disabled:pointer-events-none",
+                    title: redo_label.clone().unwrap_or_else(|| "Nothing to redo".to_string()),
+                    disabled: redo_label.is_none(),
+                    onclick: move |_| {
+                        redo(&mut data);
+                    },
+                    "↷"
+                }
                 if let Some(label) = new_button_label {
                     button {
                         class: "inline-flex items-center gap-2 px-4 py-2 bg-accent text-dark-900 text-sm font-medium 
                                 rounded-md hover:bg-accent-dim transition-colors",
-                        onclick: move |_| {
-                            match *current_view.read() {
-                                View::Contacts => modal.set(Modal::NewContact),
-                                View::Deals => modal.set(Modal::NewDeal),
-                                View::Activities => modal.set(Modal::NewActivity),
-                                _ => {}
-                            }
-                        },
+                        title: "{label} (N)",
+                        onclick: move |_| navigator.new_record(),
                         span { "+" }
                         span { "{label}" }
                     }
@@ -9,14 +9,18 @@ use dioxus::prelude::*;
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
 mod components;
-mod models;
+mod notifications;
 mod pages;
+mod shortcuts;
 mod state;
+mod webhooks;
 
-use components::{ModalContainer, Sidebar, TopBar};
+use dcrm_core::models;
+
+use components::{ModalContainer, SaveToast, Sidebar, TopBar, UndoToast};
 use models::load_data;
-use pages::{ActivitiesPage, ContactsPage, DashboardPage, DealsPage};
-use state::{Modal, View};
+use pages::{ActivitiesPage, ContactsPage, DashboardPage, DealsPage, SettingsPage, TrashPage};
+use state::{Modal, SaveProblem, View};
 
 fn main() {
     dioxus::launch(App);
@@ -31,12 +35,25 @@ fn App() -> Element {
     let current_view = use_signal(|| View::Dashboard);
     let modal = use_signal(|| Modal::None);
     let search_query = use_signal(String::new);
+    let selected_contact = use_signal(|| None::<String>);
+    let save_problem = use_signal(|| None::<SaveProblem>);
 
     // Provide context
     use_context_provider(|| app_data);
     use_context_provider(|| current_view);
     use_context_provider(|| modal);
     use_context_provider(|| search_query);
+    use_context_provider(|| selected_contact);
+    use_context_provider(|| save_problem);
+
+    // Reminder notifications
+    notifications::use_reminders();
+
+    // Ctrl+K palette, Escape, Enter, undo and the rest of the keyboard layer
+    shortcuts::use_shortcuts();
+
+    // Outgoing webhooks
+    webhooks::use_webhooks();
 
     rsx! {
         // Tailwind v4 CSS (auto-generated by DX from tailwind.css)
@@ -59,11 +76,19 @@ fn App() -> Element {
                     View::Contacts => rsx! { ContactsPage {} },
                     View::Deals => rsx! { DealsPage {} },
                     View::Activities => rsx! { ActivitiesPage {} },
+                    View::Trash => rsx! { TrashPage {} },
+                    View::Settings => rsx! { SettingsPage {} },
                 }
             }
 
             // Modal Container
             ModalContainer {}
+
+            // Undo prompt after deletes
+            UndoToast {}
+
+            // Saves that didn't go through
+            SaveToast {}
         }
     }
 }
@@ -0,0 +1,36 @@
+//! Desktop notifications for activity reminders
+
+use crate::models::Activity;
+use crate::state::{take_due_reminders, use_app_data};
+use dioxus::prelude::*;
+use notify_rust::Notification;
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub fn show_reminder(activity: &Activity) {
+    let body = match activity.format_due() {
+        Some(due) => format!("{} • due {}", activity.activity_type, due),
+        None => activity.activity_type.to_string(),
+    };
+
+    let _ = Notification::new()
+        .appname("DCRM")
+        .summary(&activity.title)
+        .body(&body)
+        .show();
+}
+
+/// Polls for due reminders for as long as the app is running.
+pub fn use_reminders() {
+    let mut data = use_app_data();
+
+    use_future(move || async move {
+        loop {
+            for activity in take_due_reminders(&mut data) {
+                show_reminder(&activity);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
@@ -8,9 +8,9 @@ pub fn ActivitiesPage() -> Element {
     let mut modal = use_modal();
     let mut filter = use_signal(|| ActivityFilter::All);
 
-    let activities: Vec<Activity> = {
+    let activity_ids: Vec<String> = {
         let data_ref = data.read();
-        let mut acts: Vec<_> = data_ref.activities.clone();
+        let mut acts: Vec<&Activity> = data_ref.activities.iter().collect();
 
         // Apply filter
         match *filter.read() {
@@ -21,15 +21,17 @@ pub fn ActivitiesPage() -> Element {
             ActivityFilter::Meetings => acts.retain(|a| a.activity_type == ActivityType::Meeting),
             ActivityFilter::Notes => acts.retain(|a| a.activity_type == ActivityType::Note),
             ActivityFilter::Pending => acts.retain(|a| !a.completed),
+            ActivityFilter::Overdue => acts.retain(|a| a.is_overdue()),
             ActivityFilter::Completed => acts.retain(|a| a.completed),
         }
 
         // Sort by created date, newest first
         acts.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        acts
+        acts.into_iter().map(|a| a.id.clone()).collect()
     };
 
     let pending_count = data.read().pending_tasks_count();
+    let overdue_count = data.read().overdue_count();
 
     rsx! {
         div { class: "flex-1 overflow-hidden p-6 flex flex-col",
@@ -42,6 +44,11 @@ pub fn ActivitiesPage() -> Element {
                             "{pending_count} pending"
                         }
                     }
+                    if overdue_count > 0 {
+                        span { class: "text-xs bg-red-500/15 text-red-400 px-2 py-0.5 rounded-full",
+                            "{overdue_count} overdue"
+                        }
+                    }
                 }
                 button {
                     class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md hover:bg-accent-dim transition-colors",
@@ -62,6 +69,11 @@ pub fn ActivitiesPage() -> Element {
                     active: *filter.read() == ActivityFilter::Pending,
                     onclick: move |_| filter.set(ActivityFilter::Pending),
                 }
+                FilterTab {
+                    label: "Overdue",
+                    active: *filter.read() == ActivityFilter::Overdue,
+                    onclick: move |_| filter.set(ActivityFilter::Overdue),
+                }
                 FilterTab {
                     label: "Completed",
                     active: *filter.read() == ActivityFilter::Completed,
@@ -99,7 +111,7 @@ pub fn ActivitiesPage() -> Element {
 
             // Activity List
             div { class: "bg-dark-800 border border-zinc-800 rounded-xl overflow-hidden flex-1",
-                if activities.is_empty() {
+                if activity_ids.is_empty() {
                     div { class: "flex flex-col items-center justify-center py-16 text-center",
                         div { class: "w-16 h-16 bg-dark-700 rounded-full flex items-center justify-center text-2xl text-zinc-500 mb-4",
                             "◇"
@@ -121,8 +133,8 @@ pub fn ActivitiesPage() -> Element {
                                 }
                             }
                             tbody {
-                                for activity in activities {
-                                    ActivityRow { activity: activity }
+                                for activity_id in activity_ids {
+                                    ActivityRow { key: "{activity_id}", activity_id: activity_id.clone() }
                                 }
                             }
                         }
@@ -142,6 +154,7 @@ enum ActivityFilter {
     Meetings,
     Notes,
     Pending,
+    Overdue,
     Completed,
 }
 
@@ -163,9 +176,12 @@ fn FilterTab(label: &'static str, active: bool, onclick: EventHandler<MouseEvent
 }
 
 #[component]
-fn ActivityRow(activity: Activity) -> Element {
+fn ActivityRow(activity_id: String) -> Element {
     let mut data = use_app_data();
-    let activity_id = activity.id.clone();
+    let mut modal = use_modal();
+    let Some(activity) = data.read().activity_by_id(&activity_id).cloned() else {
+        return rsx! {};
+    };
 
     let contact_name = activity
         .contact_id
@@ -184,6 +200,13 @@ fn ActivityRow(activity: Activity) -> Element {
         (None, None) => "—".to_string(),
     };
 
+    let overdue = activity.is_overdue();
+    let due_color = if overdue { "text-red-400" } else { "text-zinc-400" };
+    let reminder_label = activity
+        .reminder
+        .map(|r| r.display_name().to_string())
+        .unwrap_or_default();
+
     let row_opacity = if activity.completed { "opacity-50" } else { "" };
     let title_decoration = if activity.completed {
         "line-through text-zinc-500"
@@ -216,10 +239,31 @@ fn ActivityRow(activity: Activity) -> Element {
 
             // Title & Description
             td { class: "px-4 py-3",
-                div { class: "font-medium text-sm {title_decoration}", "{activity.title}" }
+                div {
+                    class: "font-medium text-sm cursor-pointer hover:text-accent transition-colors {title_decoration}",
+                    onclick: {
+                        let id = activity_id.clone();
+                        move |_| modal.set(Modal::EditActivity(id.clone()))
+                    },
+                    "{activity.title}"
+                }
                 if let Some(desc) = &activity.description {
                     div { class: "text-sm text-zinc-500 truncate max-w-xs", "{desc}" }
                 }
+                if let Some(summary) = activity.details.summary() {
+                    div { class: "text-xs text-zinc-400 truncate max-w-xs", "{summary}" }
+                }
+                if let Some(rule) = &activity.recurrence {
+                    button {
+                        class: "text-xs text-zinc-500 hover:text-accent transition-colors",
+                        title: "Edit series",
+                        onclick: {
+                            let series_id = rule.series_id.clone();
+                            move |_| modal.set(Modal::EditSeries(series_id.clone()))
+                        },
+                        "↻ {rule.describe()} • #{rule.occurrence}"
+                    }
+                }
             }
 
             // Type
@@ -237,18 +281,41 @@ fn ActivityRow(activity: Activity) -> Element {
 
             // Date
             td { class: "px-4 py-3",
-                span { class: "text-sm text-zinc-500 font-mono", "{activity.format_date()}" }
+                if let Some(due) = activity.format_due() {
+                    div { class: "flex items-center gap-2",
+                        span { class: "text-sm font-mono {due_color}", "{due}" }
+                        if activity.reminder.is_some() && !activity.completed {
+                            span { class: "text-xs", title: "{reminder_label}", "🔔" }
+                        }
+                    }
+                    if overdue {
+                        div { class: "text-xs text-red-400", "Overdue" }
+                    }
+                } else {
+                    span { class: "text-sm text-zinc-500 font-mono", "{activity.format_date()}" }
+                }
             }
 
             // Actions
             td { class: "px-4 py-3",
-                button {
-                    class: "w-8 h-8 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:text-red-400 transition-colors",
-                    onclick: {
-                        let id = activity_id.clone();
-                        move |_| delete_activity(&mut data, &id)
-                    },
-                    "×"
+                div { class: "flex items-center",
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded text-zinc-500 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
+                        title: "Edit",
+                        onclick: {
+                            let id = activity_id.clone();
+                            move |_| modal.set(Modal::EditActivity(id.clone()))
+                        },
+                        "✎"
+                    }
+                    button {
+                        class: "w-8 h-8 flex items-center justify-center rounded text-zinc-500 hover:bg-red-500/10 hover:text-red-400 transition-colors",
+                        onclick: {
+                            let id = activity_id.clone();
+                            move |_| delete_activity(&mut data, &id)
+                        },
+                        "×"
+                    }
                 }
             }
         }
@@ -1,34 +1,91 @@
 use dioxus::prelude::*;
-use crate::models::Contact;
-use crate::state::{use_app_data, use_modal, Modal, delete_contact};
+use crate::components::{ChangeHistory, DetailTabs};
+use crate::models::{Contact, ContactRelationship, RecordKind, RelationshipKind};
+use crate::shortcuts::{list_step, scroll_into_view};
+use crate::state::{use_app_data, use_modal, use_selected_contact, Modal, add_relationship, delete_relationship};
 
 #[component]
 pub fn ContactsPage() -> Element {
     let data = use_app_data();
     let mut modal = use_modal();
-    let mut selected_id = use_signal(|| None::<String>);
+    let mut selected_id = use_selected_contact();
+    let mut tag_filter = use_signal(|| None::<String>);
 
-    let contacts = data.read().contacts.clone();
+    let tags = data.read().all_tags();
+    let contact_ids: Vec<String> = match tag_filter.read().as_deref() {
+        Some(tag) => data.read().contacts_with_tag(tag).iter().map(|c| c.id.clone()).collect(),
+        None => data.read().contacts.iter().map(|c| c.id.clone()).collect(),
+    };
     let selected_contact = selected_id.read().as_ref().and_then(|id| {
         data.read().contact_by_id(id).cloned()
     });
 
+    // Up and down move the selection, Enter edits the selected contact
+    let handle_key = {
+        let contact_ids = contact_ids.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == Key::Enter {
+                if let Some(id) = selected_id.read().clone() {
+                    e.prevent_default();
+                    modal.set(Modal::EditContact(id));
+                }
+                return;
+            }
+            let current = selected_id.read().as_ref().and_then(|id| contact_ids.iter().position(|c| c == id));
+            if let Some(next) = list_step(&e.key(), current, contact_ids.len()) {
+                e.prevent_default();
+                selected_id.set(Some(contact_ids[next].clone()));
+                scroll_into_view(&format!("contact-{}", contact_ids[next]));
+            }
+        }
+    };
+
     rsx! {
         div { class: "flex h-full overflow-hidden",
             // Contact List
             div { class: "w-[360px] min-w-[360px] border-r border-zinc-800 flex flex-col overflow-hidden",
                 div { class: "p-4 border-b border-zinc-800",
                     div { class: "flex items-center justify-between",
-                        span { class: "text-sm text-zinc-500", "{contacts.len()} contacts" }
-                        button {
-                            class: "px-3 py-1.5 bg-accent text-dark-900 text-sm font-medium rounded-md hover:bg-accent-dim transition-colors",
-                            onclick: move |_| modal.set(Modal::NewContact),
-                            "+ New"
+                        span { class: "text-sm text-zinc-500", "{contact_ids.len()} contacts" }
+                        div { class: "flex items-center gap-2",
+                            button {
+                                class: "px-3 py-1.5 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                                onclick: move |_| modal.set(Modal::Duplicates),
+                                "Duplicates"
+                            }
+                            button {
+                                class: "px-3 py-1.5 bg-accent text-dark-900 text-sm font-medium rounded-md hover:bg-accent-dim transition-colors",
+                                onclick: move |_| modal.set(Modal::NewContact),
+                                "+ New"
+                            }
+                        }
+                    }
+                    if !tags.is_empty() {
+                        select {
+                            class: "w-full mt-3 px-3 py-1.5 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                    focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                            onchange: move |e| {
+                                let tag = e.value();
+                                tag_filter.set(if tag.is_empty() { None } else { Some(tag) });
+                            },
+                            option { value: "", "All tags" }
+                            for tag in &tags {
+                                option {
+                                    value: "{tag}",
+                                    selected: tag_filter.read().as_deref() == Some(tag.as_str()),
+                                    "{tag}"
+                                }
+                            }
                         }
                     }
                 }
-                div { class: "flex-1 overflow-y-auto",
-                    if contacts.is_empty() {
+                div {
+                    class: "flex-1 overflow-y-auto outline-none",
+                    tabindex: 0,
+                    onkeydown: handle_key,
+                    if contact_ids.is_empty() && tag_filter.read().is_some() {
+                        div { class: "py-16 px-8 text-center text-sm text-zinc-500", "No contacts with this tag" }
+                    } else if contact_ids.is_empty() {
                         div { class: "flex flex-col items-center justify-center py-16 px-8 text-center",
                             div { class: "w-16 h-16 bg-dark-700 rounded-full flex items-center justify-center text-2xl text-zinc-500 mb-4",
                                 "◎"
@@ -42,12 +99,13 @@ pub fn ContactsPage() -> Element {
                             }
                         }
                     } else {
-                        for contact in contacts {
+                        for id in contact_ids {
                             ContactListItem {
-                                contact: contact.clone(),
-                                selected: selected_id.read().as_ref() == Some(&contact.id),
+                                key: "{id}",
+                                contact_id: id.clone(),
+                                selected: selected_id.read().as_ref() == Some(&id),
                                 onclick: {
-                                    let id = contact.id.clone();
+                                    let id = id.clone();
                                     move |_| selected_id.set(Some(id.clone()))
                                 },
                             }
@@ -79,15 +137,21 @@ pub fn ContactsPage() -> Element {
 
 #[component]
 fn ContactListItem(
-    contact: Contact,
+    contact_id: String,
     selected: bool,
     onclick: EventHandler<MouseEvent>,
 ) -> Element {
+    let data = use_app_data();
+    let store = data.read();
+    let Some(contact) = store.contact_by_id(&contact_id) else {
+        return rsx! {};
+    };
     let bg = if selected { "bg-dark-700" } else { "hover:bg-dark-700/50" };
     let border = if selected { "border-l-2 border-l-accent" } else { "border-l-2 border-l-transparent" };
 
     rsx! {
         div {
+            id: "contact-{contact_id}",
             class: "flex items-center gap-4 p-4 cursor-pointer transition-colors border-b border-zinc-800 {bg} {border}",
             onclick: move |e| onclick.call(e),
             
@@ -114,6 +178,7 @@ fn ContactListItem(
 fn ContactDetail(contact: Contact, on_close: EventHandler<MouseEvent>) -> Element {
     let mut data = use_app_data();
     let mut modal = use_modal();
+    let showing_history = use_signal(|| false);
     let contact_id = contact.id.clone();
 
     let activities = data.read().activities_for_contact(&contact.id)
@@ -121,11 +186,14 @@ fn ContactDetail(contact: Contact, on_close: EventHandler<MouseEvent>) -> Elemen
         .cloned()
         .collect::<Vec<_>>();
 
-    let deals = data.read().deals.iter()
-        .filter(|d| d.contact_id.as_ref() == Some(&contact.id))
+    let deals = data.read().deals_for_contact(&contact.id)
+        .into_iter()
         .cloned()
         .collect::<Vec<_>>();
 
+    let relationships = data.read().relationships_for(&contact.id);
+    let change_count = data.read().audit_for(RecordKind::Contact, &contact.id).len();
+
     rsx! {
         div { class: "bg-dark-800 border-l border-zinc-800 h-full flex flex-col overflow-hidden",
             // Header
@@ -150,6 +218,16 @@ fn ContactDetail(contact: Contact, on_close: EventHandler<MouseEvent>) -> Elemen
                         },
                         "Edit"
                     }
+                    if let Some(company) = &contact.company {
+                        button {
+                            class: "px-3 py-1.5 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                            onclick: {
+                                let company = company.clone();
+                                move |_| modal.set(Modal::OrgChart(company.clone()))
+                            },
+                            "Org Chart"
+                        }
+                    }
                     button {
                         class: "w-8 h-8 flex items-center justify-center rounded-md text-zinc-400 hover:bg-zinc-700 hover:text-zinc-100 transition-colors",
                         onclick: move |e| on_close.call(e),
@@ -158,95 +236,188 @@ fn ContactDetail(contact: Contact, on_close: EventHandler<MouseEvent>) -> Elemen
                 }
             }
 
+            DetailTabs { showing_history, count: change_count }
+
             // Body
             div { class: "flex-1 overflow-y-auto p-5",
-                // Contact Info Section
-                DetailSection { title: "Contact Information",
-                    DetailRow { label: "Email", value: contact.email.clone() }
+                if showing_history() {
+                    ChangeHistory { kind: RecordKind::Contact, record_id: contact_id.clone() }
+                } else {
+                    // Contact Info Section
+                    DetailSection { title: "Contact Information",
+                        DetailRow { label: "Email", value: contact.email.clone() }
                     
-                    if let Some(phone) = &contact.phone {
-                        DetailRow { label: "Phone", value: phone.clone() }
-                    }
+                        if let Some(phone) = &contact.phone {
+                            DetailRow { label: "Phone", value: phone.clone() }
+                        }
                     
-                    if let Some(company) = &contact.company {
-                        DetailRow { label: "Company", value: company.clone() }
+                        if let Some(company) = &contact.company {
+                            DetailRow { label: "Company", value: company.clone() }
+                        }
                     }
-                }
 
-                // Tags
-                if !contact.tags.is_empty() {
-                    DetailSection { title: "Tags",
-                        div { class: "flex gap-2 flex-wrap",
-                            for tag in &contact.tags {
-                                span { class: "text-xs bg-dark-700 border border-zinc-700 px-2 py-1 rounded text-zinc-400",
-                                    "{tag}"
+                    // Relationships
+                    DetailSection { title: "Relationships",
+                        for (relationship_id, label, other) in &relationships {
+                            div { class: "flex items-center justify-between py-2",
+                                span { class: "text-sm text-zinc-500", "{label}" }
+                                div { class: "flex items-center gap-3",
+                                    span { class: "text-sm text-zinc-100", "{other.full_name()}" }
+                                    button {
+                                        class: "text-zinc-500 hover:text-red-400 transition-colors",
+                                        onclick: {
+                                            let id = relationship_id.clone();
+                                            move |_| delete_relationship(&mut data, &id)
+                                        },
+                                        "×"
+                                    }
                                 }
                             }
                         }
+                        RelationshipForm { contact_id: contact_id.clone() }
                     }
-                }
 
-                // Associated Deals
-                if !deals.is_empty() {
-                    DetailSection { title: format!("Deals ({})", deals.len()).leak(),
-                        for deal in &deals {
-                            div { 
-                                class: "bg-dark-700 border border-zinc-700 rounded-lg p-3 mb-2",
-                                div { class: "font-medium text-sm text-zinc-100 mb-1", "{deal.title}" }
-                                div { class: "flex items-center justify-between",
-                                    span { class: "font-mono text-sm text-accent", "{deal.format_value()}" }
-                                    span { 
-                                        class: "text-xs px-2 py-0.5 rounded {deal.stage.badge_class()}",
-                                        "{deal.stage}"
+                    // Tags
+                    if !contact.tags.is_empty() {
+                        DetailSection { title: "Tags",
+                            div { class: "flex gap-2 flex-wrap",
+                                for tag in &contact.tags {
+                                    span { class: "text-xs bg-dark-700 border border-zinc-700 px-2 py-1 rounded text-zinc-400",
+                                        "{tag}"
                                     }
                                 }
                             }
                         }
                     }
-                }
 
-                // Recent Activities
-                DetailSection { title: format!("Activity ({})", activities.len()).leak(),
-                    if activities.is_empty() {
-                        p { class: "text-sm text-zinc-500", "No activities recorded" }
-                    } else {
-                        div { class: "space-y-1",
-                            for activity in activities.iter().take(5) {
-                                div { class: "flex items-center gap-3 py-2",
-                                    div { class: "w-7 h-7 rounded-full bg-dark-700 flex items-center justify-center text-xs",
-                                        "{activity.activity_type.icon()}"
+                    // Associated Deals
+                    if !deals.is_empty() {
+                        DetailSection { title: format!("Deals ({})", deals.len()).leak(),
+                            for deal in &deals {
+                                div { 
+                                    class: "bg-dark-700 border border-zinc-700 rounded-lg p-3 mb-2",
+                                    div { class: "flex items-center justify-between mb-1",
+                                        span { class: "font-medium text-sm text-zinc-100", "{deal.title}" }
+                                        if let Some(role) = deal.role_of(&contact.id) {
+                                            span { class: "text-xs text-zinc-500", "{role}" }
+                                        }
                                     }
-                                    div { class: "flex-1",
-                                        div { class: "text-sm text-zinc-100", "{activity.title}" }
-                                        div { class: "text-xs text-zinc-500", "{activity.format_date()}" }
+                                    div { class: "flex items-center justify-between",
+                                        span { class: "font-mono text-sm text-accent", "{deal.format_value()}" }
+                                        span { 
+                                            class: "text-xs px-2 py-0.5 rounded {deal.stage.badge_class()}",
+                                            "{deal.stage}"
+                                        }
                                     }
                                 }
                             }
                         }
                     }
-                }
 
-                // Notes
-                if let Some(notes) = &contact.notes {
-                    DetailSection { title: "Notes",
-                        p { class: "text-sm text-zinc-300", "{notes}" }
+                    // Recent Activities
+                    DetailSection { title: format!("Activity ({})", activities.len()).leak(),
+                        if activities.is_empty() {
+                            p { class: "text-sm text-zinc-500", "No activities recorded" }
+                        } else {
+                            div { class: "space-y-1",
+                                for activity in activities.iter().take(5) {
+                                    div {
+                                        class: "flex items-center gap-3 py-2 cursor-pointer hover:bg-dark-700/50 -mx-2 px-2 rounded transition-colors",
+                                        onclick: {
+                                            let id = activity.id.clone();
+                                            move |_| modal.set(Modal::EditActivity(id.clone()))
+                                        },
+                                        div { class: "w-7 h-7 rounded-full bg-dark-700 flex items-center justify-center text-xs",
+                                            "{activity.activity_type.icon()}"
+                                        }
+                                        div { class: "flex-1",
+                                            div { class: "text-sm text-zinc-100", "{activity.title}" }
+                                            div { class: "text-xs text-zinc-500", "{activity.format_date()}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    // Notes
+                    if let Some(notes) = &contact.notes {
+                        DetailSection { title: "Notes",
+                            p { class: "text-sm text-zinc-300", "{notes}" }
+                        }
+                    }
+
+                    // Danger Zone
+                    div { class: "mt-8",
+                        button {
+                            class: "text-sm text-red-400 hover:text-red-300 transition-colors",
+                            onclick: {
+                                let id = contact_id.clone();
+                                move |_| modal.set(Modal::DeleteContact(id.clone()))
+                            },
+                            "Delete Contact"
+                        }
                     }
                 }
+            }
+        }
+    }
+}
 
-                // Danger Zone
-                div { class: "mt-8",
-                    button {
-                        class: "text-sm text-red-400 hover:text-red-300 transition-colors",
-                        onclick: {
-                            let id = contact_id.clone();
-                            move |_| {
-                                delete_contact(&mut data, &id);
-                            }
-                        },
-                        "Delete Contact"
+#[component]
+fn RelationshipForm(contact_id: String) -> Element {
+    let mut data = use_app_data();
+    let mut kind = use_signal(|| RelationshipKind::ManagerOf);
+    let mut other_id = use_signal(String::new);
+
+    let others: Vec<Contact> = data
+        .read()
+        .contacts
+        .iter()
+        .filter(|c| c.id != contact_id)
+        .cloned()
+        .collect();
+
+    let select_class = "px-2 py-1.5 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                        focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all";
+
+    rsx! {
+        div { class: "flex gap-2 mt-2",
+            select {
+                class: "{select_class}",
+                onchange: move |e| kind.set(RelationshipKind::from_name(&e.value())),
+                for k in RelationshipKind::all() {
+                    option {
+                        value: "{k.display_name()}",
+                        selected: *kind.read() == k,
+                        "{k.display_name()}"
                     }
                 }
             }
+            select {
+                class: "flex-1 min-w-0 {select_class}",
+                value: "{other_id}",
+                onchange: move |e| other_id.set(e.value()),
+                option { value: "", "Select contact..." }
+                for c in &others {
+                    option { value: "{c.id}", "{c.full_name()}" }
+                }
+            }
+            button {
+                class: "px-3 py-1.5 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                onclick: move |_| {
+                    let other = other_id.read().clone();
+                    if other.is_empty() {
+                        return;
+                    }
+                    add_relationship(
+                        &mut data,
+                        ContactRelationship::new(contact_id.clone(), other, *kind.read()),
+                    );
+                    other_id.set(String::new());
+                },
+                "Link"
+            }
         }
     }
 }
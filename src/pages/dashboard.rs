@@ -1,4 +1,5 @@
-use crate::models::{Activity, ActivityType, DealStage};
+use crate::models::{Activity, ActivityType, DealStage, Money, format_duration};
+use chrono::Duration;
 use crate::state::{Modal, use_app_data, use_modal};
 use dioxus::prelude::*;
 
@@ -12,6 +13,19 @@ pub fn DashboardPage() -> Element {
     let pipeline_value = data.read().total_pipeline_value();
     let won_value = data.read().won_deals_value();
     let pending_tasks = data.read().pending_tasks_count();
+    let regressions = data.read().stage_regressions().len();
+    let win_rate = data
+        .read()
+        .win_rate()
+        .map(|r| format!("{:.0}%", r))
+        .unwrap_or_else(|| "—".to_string());
+    let activity_stats = data.read().activity_stats();
+    let connect_rate = activity_stats
+        .connect_rate()
+        .map(|r| format!("{:.0}%", r))
+        .unwrap_or_else(|| "—".to_string());
+    let won_reasons = data.read().close_reason_breakdown(DealStage::Won);
+    let lost_reasons = data.read().close_reason_breakdown(DealStage::Lost);
     let recent_activities: Vec<Activity> = data
         .read()
         .recent_activities(5)
@@ -37,13 +51,13 @@ pub fn DashboardPage() -> Element {
                 }
                 StatCard {
                     label: "Pipeline Value",
-                    value: format_currency(pipeline_value),
+                    value: pipeline_value.format_compact(),
                     icon: "◆",
                     color: "text-accent",
                 }
                 StatCard {
                     label: "Won Revenue",
-                    value: format_currency(won_value),
+                    value: won_value.format_compact(),
                     icon: "✓",
                     color: "text-emerald-400",
                 }
@@ -61,7 +75,7 @@ pub fn DashboardPage() -> Element {
                             PipelineStageRow {
                                 stage: stage,
                                 count: data.read().deals_by_stage(stage).len(),
-                                value: data.read().deals_by_stage(stage).iter().map(|d| d.value).sum::<f64>(),
+                                value: data.read().stage_value(stage),
                             }
                         }
                     }
@@ -86,6 +100,7 @@ pub fn DashboardPage() -> Element {
                             div { class: "space-y-1",
                                 for activity in recent_activities {
                                     ActivityRow {
+                                        id: activity.id.clone(),
                                         icon: activity.activity_type.icon().to_string(),
                                         title: activity.title.clone(),
                                         meta: activity.format_date(),
@@ -118,10 +133,12 @@ pub fn DashboardPage() -> Element {
                 }
                 div { class: "p-5",
                     {
-                        let tasks: Vec<_> = data.read().activities.iter()
+                        let mut tasks: Vec<_> = data.read().activities.iter()
                             .filter(|a| a.activity_type == ActivityType::Task && !a.completed)
                             .cloned()
                             .collect();
+                        // Soonest due first, undated tasks last
+                        tasks.sort_by_key(|t| (t.due_date.is_none(), t.due_date));
 
                         if tasks.is_empty() {
                             rsx! {
@@ -140,7 +157,8 @@ pub fn DashboardPage() -> Element {
                                         TaskRow {
                                             id: task.id.clone(),
                                             title: task.title.clone(),
-                                            due: task.due_date.map(|d| d.format("%b %d").to_string()),
+                                            due: task.format_due(),
+                                            overdue: task.is_overdue(),
                                         }
                                     }
                                 }
@@ -149,6 +167,66 @@ pub fn DashboardPage() -> Element {
                     }
                 }
             }
+
+            // Win/Loss Analysis
+            div { class: "bg-dark-800 border border-zinc-800 rounded-xl overflow-hidden mt-6",
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-800",
+                    h3 { class: "text-sm font-semibold text-zinc-100", "Win/Loss Analysis" }
+                    span { class: "text-xs text-zinc-500", "Win rate " span { class: "font-mono text-zinc-100", "{win_rate}" } }
+                }
+                div { class: "grid grid-cols-2 gap-6 p-5",
+                    ReasonBreakdown { title: "Won", color: "bg-emerald-500", reasons: won_reasons }
+                    ReasonBreakdown { title: "Lost", color: "bg-red-500", reasons: lost_reasons }
+                }
+            }
+
+            // Stage Velocity
+            div { class: "bg-dark-800 border border-zinc-800 rounded-xl overflow-hidden mt-6",
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-800",
+                    h3 { class: "text-sm font-semibold text-zinc-100", "Stage Velocity" }
+                    span { class: "text-xs text-zinc-500",
+                        "{regressions} stage regressions"
+                    }
+                }
+                div { class: "grid grid-cols-4 gap-4 p-5",
+                    for stage in DealStage::active() {
+                        div {
+                            div { class: "text-xs text-zinc-500 mb-1", "Avg. in {stage.display_name()}" }
+                            div { class: "font-mono text-sm font-semibold text-zinc-100",
+                                {data.read().average_time_in_stage(stage).map(format_duration).unwrap_or_else(|| "—".to_string())}
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Calls, Meetings & Emails
+            div { class: "bg-dark-800 border border-zinc-800 rounded-xl overflow-hidden mt-6",
+                div { class: "flex items-center justify-between px-5 py-4 border-b border-zinc-800",
+                    h3 { class: "text-sm font-semibold text-zinc-100", "Calls, Meetings & Emails" }
+                    span { class: "text-xs text-zinc-500",
+                        for (outcome, count) in activity_stats.calls_by_outcome.iter() {
+                            span { class: "ml-3", "{outcome} " span { class: "font-mono text-zinc-100", "{count}" } }
+                        }
+                    }
+                }
+                div { class: "grid grid-cols-5 gap-4 p-5",
+                    ActivityMetric { label: "Calls logged", value: activity_stats.calls.to_string() }
+                    ActivityMetric { label: "Connect rate", value: connect_rate }
+                    ActivityMetric {
+                        label: "Talk time",
+                        value: format_duration(Duration::minutes(activity_stats.talk_minutes as i64)),
+                    }
+                    ActivityMetric {
+                        label: "Time in meetings",
+                        value: format!("{} ({})", format_duration(activity_stats.meeting_time), activity_stats.meetings),
+                    }
+                    ActivityMetric {
+                        label: "Emails sent / received",
+                        value: format!("{} / {}", activity_stats.emails_sent, activity_stats.emails_received),
+                    }
+                }
+            }
         }
     }
 }
@@ -172,9 +250,10 @@ fn StatCard(
 }
 
 #[component]
-fn PipelineStageRow(stage: DealStage, count: usize, value: f64) -> Element {
+fn PipelineStageRow(stage: DealStage, count: usize, value: Money) -> Element {
+    let formatted = value.format_compact();
     let total_pipeline = 600000.0;
-    let percentage = ((value / total_pipeline) * 100.0).min(100.0);
+    let percentage = ((value.to_f64() / total_pipeline) * 100.0).min(100.0);
 
     let color_class = match stage {
         DealStage::Lead => "bg-blue-500",
@@ -193,7 +272,7 @@ fn PipelineStageRow(stage: DealStage, count: usize, value: f64) -> Element {
                     span { class: "text-sm font-medium text-zinc-100", "{stage.display_name()}" }
                     span { class: "text-xs text-zinc-500", "({count})" }
                 }
-                span { class: "font-mono text-sm text-accent", "{format_currency(value)}" }
+                span { class: "font-mono text-sm text-accent", "{formatted}" }
             }
             // Progress bar
             div { class: "h-1 bg-dark-700 rounded-full overflow-hidden",
@@ -207,11 +286,52 @@ fn PipelineStageRow(stage: DealStage, count: usize, value: f64) -> Element {
 }
 
 #[component]
-fn ActivityRow(icon: String, title: String, meta: String, completed: bool) -> Element {
+fn ActivityMetric(label: &'static str, value: String) -> Element {
+    rsx! {
+        div {
+            div { class: "text-xs text-zinc-500 mb-1", "{label}" }
+            div { class: "font-mono text-sm font-semibold text-zinc-100", "{value}" }
+        }
+    }
+}
+
+#[component]
+fn ReasonBreakdown(title: &'static str, color: &'static str, reasons: Vec<(String, usize, Money)>) -> Element {
+    let total: usize = reasons.iter().map(|(_, count, _)| count).sum();
+
+    rsx! {
+        div {
+            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3", "{title}" }
+            if reasons.is_empty() {
+                div { class: "text-sm text-zinc-500", "No closed deals yet" }
+            }
+            for (reason, count, value) in reasons {
+                div { class: "py-2",
+                    div { class: "flex items-center justify-between mb-1",
+                        span { class: "text-sm text-zinc-100", "{reason}" }
+                        span { class: "text-xs text-zinc-500 font-mono", "{count} • {value.format_compact()}" }
+                    }
+                    div { class: "h-1 bg-dark-700 rounded-full overflow-hidden",
+                        div {
+                            class: "h-full {color} rounded-full",
+                            style: "width: {count as f64 / total as f64 * 100.0}%",
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ActivityRow(id: String, icon: String, title: String, meta: String, completed: bool) -> Element {
+    let mut modal = use_modal();
     let opacity = if completed { "opacity-50" } else { "" };
 
     rsx! {
-        div { class: "flex items-center gap-4 py-3 border-b border-zinc-800 last:border-b-0 {opacity}",
+        div {
+            class: "flex items-center gap-4 py-3 border-b border-zinc-800 last:border-b-0 cursor-pointer {opacity}",
+            onclick: move |_| modal.set(Modal::EditActivity(id.clone())),
             div { class: "w-8 h-8 rounded-full bg-dark-700 flex items-center justify-center text-sm",
                 "{icon}"
             }
@@ -224,32 +344,32 @@ fn ActivityRow(icon: String, title: String, meta: String, completed: bool) -> El
 }
 
 #[component]
-fn TaskRow(id: String, title: String, due: Option<String>) -> Element {
+fn TaskRow(id: String, title: String, due: Option<String>, overdue: bool) -> Element {
     let mut data = use_app_data();
+    let mut modal = use_modal();
+    let task_id = id.clone();
 
     rsx! {
         div {
             class: "flex items-center gap-4 py-3 border-b border-zinc-800 last:border-b-0 cursor-pointer hover:bg-dark-700/50 -mx-2 px-2 rounded transition-colors",
-            onclick: move |_| {
-                crate::state::toggle_activity_completed(&mut data, &id);
-            },
-            div { class: "w-5 h-5 border-2 border-zinc-600 rounded flex items-center justify-center hover:border-accent transition-colors" }
+            onclick: move |_| modal.set(Modal::EditActivity(id.clone())),
+            div {
+                class: "w-5 h-5 border-2 border-zinc-600 rounded flex items-center justify-center hover:border-accent transition-colors",
+                onclick: move |e| {
+                    e.stop_propagation();
+                    crate::state::toggle_activity_completed(&mut data, &task_id);
+                },
+            }
             div { class: "flex-1",
                 div { class: "text-sm text-zinc-100", "{title}" }
                 if let Some(d) = due {
-                    div { class: "text-xs text-zinc-500", "Due: {d}" }
+                    if overdue {
+                        div { class: "text-xs text-red-400", "Overdue: {d}" }
+                    } else {
+                        div { class: "text-xs text-zinc-500", "Due: {d}" }
+                    }
                 }
             }
         }
     }
 }
-
-fn format_currency(value: f64) -> String {
-    if value >= 1_000_000.0 {
-        format!("${:.1}M", value / 1_000_000.0)
-    } else if value >= 1_000.0 {
-        format!("${:.0}K", value / 1_000.0)
-    } else {
-        format!("${:.0}", value)
-    }
-}
@@ -1,11 +1,24 @@
-use crate::models::{Deal, DealStage};
-use crate::state::{Modal, delete_deal, update_deal_stage, use_app_data, use_modal};
+use crate::models::{Deal, DealStage, export_deals_csv, write_export};
+use crate::state::{Modal, move_deal, update_deal_stage, use_app_data, use_modal};
 use dioxus::prelude::*;
 
 #[component]
 pub fn DealsPage() -> Element {
     let data = use_app_data();
     let mut modal = use_modal();
+    let mut export_status = use_signal(|| None::<String>);
+    // Card being dragged, and the card to refocus after a keyboard move remounts it
+    let dragging = use_signal(|| None::<String>);
+    let focused = use_signal(|| None::<String>);
+
+    let handle_export = move |_| {
+        let csv = export_deals_csv(&data.read());
+        let status = match write_export("dcrm-deals.csv", &csv) {
+            Ok(path) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        };
+        export_status.set(Some(status));
+    };
 
     rsx! {
         div { class: "flex-1 overflow-hidden p-6 flex flex-col",
@@ -14,21 +27,31 @@ pub fn DealsPage() -> Element {
                 div { class: "flex gap-8",
                     PipelineStat {
                         label: "Total Pipeline",
-                        value: format_currency(data.read().total_pipeline_value()),
+                        value: data.read().total_pipeline_value().format_compact(),
                     }
                     PipelineStat {
                         label: "Weighted Value",
-                        value: format_currency(data.read().weighted_pipeline_value()),
+                        value: data.read().weighted_pipeline_value().format_compact(),
                     }
                     PipelineStat {
                         label: "Active Deals",
                         value: data.read().active_deals_count().to_string(),
                     }
                 }
-                button {
-                    class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md hover:bg-accent-dim transition-colors",
-                    onclick: move |_| modal.set(Modal::NewDeal),
-                    "+ New Deal"
+                div { class: "flex items-center gap-3",
+                    if let Some(status) = export_status.read().as_ref() {
+                        span { class: "text-xs text-zinc-500", "{status}" }
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: handle_export,
+                        "Export CSV"
+                    }
+                    button {
+                        class: "px-4 py-2 bg-accent text-dark-900 text-sm font-medium rounded-md hover:bg-accent-dim transition-colors",
+                        onclick: move |_| modal.set(Modal::NewDeal),
+                        "+ New Deal"
+                    }
                 }
             }
 
@@ -38,6 +61,8 @@ pub fn DealsPage() -> Element {
                     PipelineColumn {
                         stage: stage,
                         deals: data.read().deals_by_stage(stage).into_iter().cloned().collect(),
+                        dragging: dragging,
+                        focused: focused,
                     }
                 }
 
@@ -45,6 +70,7 @@ pub fn DealsPage() -> Element {
                 ClosedDealsColumn {
                     won_deals: data.read().deals_by_stage(DealStage::Won).into_iter().cloned().collect(),
                     lost_deals: data.read().deals_by_stage(DealStage::Lost).into_iter().cloned().collect(),
+                    dragging: dragging,
                 }
             }
         }
@@ -62,8 +88,15 @@ fn PipelineStat(label: &'static str, value: String) -> Element {
 }
 
 #[component]
-fn PipelineColumn(stage: DealStage, deals: Vec<Deal>) -> Element {
-    let total_value: f64 = deals.iter().map(|d| d.value).sum();
+fn PipelineColumn(
+    stage: DealStage,
+    deals: Vec<Deal>,
+    dragging: Signal<Option<String>>,
+    focused: Signal<Option<String>>,
+) -> Element {
+    let mut data = use_app_data();
+    let mut drag_over = use_signal(|| false);
+    let total_value = data.read().stage_value(stage).format_compact();
 
     let dot_color = match stage {
         DealStage::Lead => "bg-blue-500",
@@ -74,8 +107,28 @@ fn PipelineColumn(stage: DealStage, deals: Vec<Deal>) -> Element {
         DealStage::Lost => "bg-red-500",
     };
 
+    let border = if *drag_over.read() && dragging.read().is_some() {
+        "border-accent"
+    } else {
+        "border-zinc-800"
+    };
+
     rsx! {
-        div { class: "min-w-[280px] w-[280px] bg-dark-800 border border-zinc-800 rounded-xl flex flex-col max-h-full",
+        div {
+            class: "min-w-[280px] w-[280px] bg-dark-800 border {border} rounded-xl flex flex-col max-h-full transition-colors",
+            ondragover: move |e| {
+                e.prevent_default();
+                drag_over.set(true);
+            },
+            ondragleave: move |_| drag_over.set(false),
+            // Drops on a card are handled there first; anything left lands at the bottom
+            ondrop: move |e| {
+                e.prevent_default();
+                drag_over.set(false);
+                if let Some(id) = dragging.take() {
+                    move_deal(&mut data, &id, stage, None);
+                }
+            },
             // Column Header
             div { class: "flex items-center justify-between p-4 border-b border-zinc-800",
                 div { class: "flex items-center gap-2",
@@ -84,14 +137,20 @@ fn PipelineColumn(stage: DealStage, deals: Vec<Deal>) -> Element {
                 }
                 div { class: "flex items-center gap-2",
                     span { class: "text-xs bg-dark-700 px-2 py-0.5 rounded-full text-zinc-500", "{deals.len()}" }
-                    span { class: "text-xs text-zinc-500 font-mono", "{format_currency(total_value)}" }
+                    span { class: "text-xs text-zinc-500 font-mono", "{total_value}" }
                 }
             }
 
             // Column Body
             div { class: "flex-1 overflow-y-auto p-3 space-y-3",
-                for deal in deals {
-                    DealCard { deal: deal }
+                for (index, deal) in deals.into_iter().enumerate() {
+                    DealCard {
+                        key: "{deal.id}",
+                        deal: deal,
+                        index: index,
+                        dragging: dragging,
+                        focused: focused,
+                    }
                 }
             }
         }
@@ -99,27 +158,96 @@ fn PipelineColumn(stage: DealStage, deals: Vec<Deal>) -> Element {
 }
 
 #[component]
-fn DealCard(deal: Deal) -> Element {
-    let data = use_app_data();
+fn DealCard(
+    deal: Deal,
+    index: usize,
+    dragging: Signal<Option<String>>,
+    focused: Signal<Option<String>>,
+) -> Element {
+    let mut data = use_app_data();
     let mut modal = use_modal();
     let deal_id = deal.id.clone();
+    let stage = deal.stage;
     let mut show_actions = use_signal(|| false);
 
     let contact_name = deal
-        .contact_id
-        .as_ref()
+        .primary_contact_id()
         .and_then(|id| data.read().contact_by_id(id).map(|c| c.full_name()));
+    let more_contacts = deal.participants.len().saturating_sub(1);
 
     rsx! {
         div {
             class: "bg-dark-700 border border-zinc-700 rounded-lg p-3 cursor-pointer hover:border-zinc-600
-                    hover:-translate-y-0.5 hover:shadow-lg transition-all",
+                    hover:-translate-y-0.5 hover:shadow-lg transition-all outline-none focus:border-accent",
+            title: "Drag to move, or focus and use the arrow keys",
+            tabindex: "0",
+            draggable: "true",
             onmouseenter: move |_| show_actions.set(true),
             onmouseleave: move |_| show_actions.set(false),
             onclick: {
                 let id = deal_id.clone();
                 move |_| modal.set(Modal::DealDetail(id.clone()))
             },
+            ondragstart: {
+                let id = deal_id.clone();
+                move |_| dragging.set(Some(id.clone()))
+            },
+            ondragend: move |_| dragging.set(None),
+            // Dropping onto a card places the dragged deal just above it
+            ondrop: {
+                let id = deal_id.clone();
+                move |e| {
+                    e.prevent_default();
+                    let Some(dragged) = dragging.take() else { return };
+                    if dragged == id {
+                        return;
+                    }
+                    let index = data
+                        .read()
+                        .deals_by_stage(stage)
+                        .into_iter()
+                        .filter(|d| d.id != dragged)
+                        .position(|d| d.id == id);
+                    move_deal(&mut data, &dragged, stage, index);
+                }
+            },
+            onkeydown: {
+                let id = deal_id.clone();
+                move |e: KeyboardEvent| {
+                    let active = DealStage::active();
+                    let column = active.iter().position(|s| *s == stage);
+                    match e.key() {
+                        Key::ArrowUp => move_deal(&mut data, &id, stage, Some(index.saturating_sub(1))),
+                        Key::ArrowDown => move_deal(&mut data, &id, stage, Some(index + 1)),
+                        Key::ArrowLeft => {
+                            if let Some(target) = column.and_then(|c| c.checked_sub(1)) {
+                                focused.set(Some(id.clone()));
+                                move_deal(&mut data, &id, active[target], None);
+                            }
+                        }
+                        Key::ArrowRight => {
+                            if let Some(target) = column.map(|c| c + 1).filter(|c| *c < active.len()) {
+                                focused.set(Some(id.clone()));
+                                move_deal(&mut data, &id, active[target], None);
+                            }
+                        }
+                        Key::Enter => modal.set(Modal::DealDetail(id.clone())),
+                        _ => return,
+                    }
+                    e.prevent_default();
+                }
+            },
+            onmounted: {
+                let id = deal_id.clone();
+                move |e: MountedEvent| {
+                    if focused.read().as_deref() == Some(id.as_str()) {
+                        focused.set(None);
+                        spawn(async move {
+                            let _ = e.set_focus(true).await;
+                        });
+                    }
+                }
+            },
 
             div { class: "flex items-start justify-between",
                 div { class: "font-medium text-sm text-zinc-100 pr-2", "{deal.title}" }
@@ -136,6 +264,9 @@ fn DealCard(deal: Deal) -> Element {
                 if let Some(name) = contact_name {
                     span { class: "text-zinc-600", " • {name}" }
                 }
+                if more_contacts > 0 {
+                    span { class: "text-zinc-600", " +{more_contacts}" }
+                }
             }
 
             div { class: "flex items-center justify-between",
@@ -149,6 +280,7 @@ fn DealCard(deal: Deal) -> Element {
 #[component]
 fn DealQuickActions(deal_id: String, current_stage: DealStage) -> Element {
     let mut data = use_app_data();
+    let mut modal = use_modal();
     let mut show_menu = use_signal(|| false);
 
     let next_stages: Vec<DealStage> = DealStage::all()
@@ -194,7 +326,11 @@ fn DealQuickActions(deal_id: String, current_stage: DealStage) -> Element {
                                         let id = deal_id.clone();
                                         move |e| {
                                             e.stop_propagation();
-                                            update_deal_stage(&mut data, &id, stage);
+                                            if DealStage::active().contains(&stage) {
+                                                update_deal_stage(&mut data, &id, stage);
+                                            } else {
+                                                modal.set(Modal::CloseDeal(id.clone(), stage));
+                                            }
                                             show_menu.set(false);
                                         }
                                     },
@@ -213,7 +349,8 @@ fn DealQuickActions(deal_id: String, current_stage: DealStage) -> Element {
                             let id = deal_id.clone();
                             move |e| {
                                 e.stop_propagation();
-                                delete_deal(&mut data, &id);
+                                show_menu.set(false);
+                                modal.set(Modal::DeleteDeal(id.clone()));
                             }
                         },
                         "Delete"
@@ -225,45 +362,70 @@ fn DealQuickActions(deal_id: String, current_stage: DealStage) -> Element {
 }
 
 #[component]
-fn ClosedDealsColumn(won_deals: Vec<Deal>, lost_deals: Vec<Deal>) -> Element {
-    let won_value: f64 = won_deals.iter().map(|d| d.value).sum();
-    let lost_value: f64 = lost_deals.iter().map(|d| d.value).sum();
+fn ClosedDealsColumn(won_deals: Vec<Deal>, lost_deals: Vec<Deal>, dragging: Signal<Option<String>>) -> Element {
+    let data = use_app_data();
+    let mut modal = use_modal();
+    let mut over = use_signal(|| None::<DealStage>);
+    let won_value = data.read().stage_value(DealStage::Won).format_compact();
+    let lost_value = data.read().stage_value(DealStage::Lost).format_compact();
+
+    // Closing still goes through the close modal so a reason is captured
+    let drop_to = move |stage: DealStage| {
+        move |e: DragEvent| {
+            e.prevent_default();
+            over.set(None);
+            if let Some(id) = dragging.take() {
+                modal.set(Modal::CloseDeal(id, stage));
+            }
+        }
+    };
+    let highlight = move |stage: DealStage| {
+        if *over.read() == Some(stage) && dragging.read().is_some() {
+            "bg-dark-600"
+        } else {
+            ""
+        }
+    };
 
     rsx! {
-        div { class: "min-w-[280px] w-[280px] bg-dark-700 border border-zinc-800 rounded-xl flex flex-col",
+        div { class: "min-w-[280px] w-[280px] bg-dark-700 border border-zinc-800 rounded-xl flex flex-col overflow-hidden",
             // Won Section
-            div { class: "flex items-center justify-between p-4",
+            div {
+                class: "flex items-center justify-between p-4 transition-colors {highlight(DealStage::Won)}",
+                ondragover: move |e| {
+                    e.prevent_default();
+                    over.set(Some(DealStage::Won));
+                },
+                ondragleave: move |_| over.set(None),
+                ondrop: drop_to(DealStage::Won),
                 div { class: "flex items-center gap-2",
                     span { class: "w-2 h-2 rounded-full bg-emerald-500" }
                     span { class: "font-medium text-sm text-emerald-400", "Won" }
                 }
                 div { class: "flex items-center gap-2",
                     span { class: "text-xs bg-dark-600 px-2 py-0.5 rounded-full text-zinc-500", "{won_deals.len()}" }
-                    span { class: "text-xs text-emerald-400 font-mono", "{format_currency(won_value)}" }
+                    span { class: "text-xs text-emerald-400 font-mono", "{won_value}" }
                 }
             }
 
             // Lost Section
-            div { class: "flex items-center justify-between p-4 border-t border-zinc-800",
+            div {
+                class: "flex items-center justify-between p-4 border-t border-zinc-800 transition-colors {highlight(DealStage::Lost)}",
+                ondragover: move |e| {
+                    e.prevent_default();
+                    over.set(Some(DealStage::Lost));
+                },
+                ondragleave: move |_| over.set(None),
+                ondrop: drop_to(DealStage::Lost),
                 div { class: "flex items-center gap-2",
                     span { class: "w-2 h-2 rounded-full bg-red-500" }
                     span { class: "font-medium text-sm text-red-400", "Lost" }
                 }
                 div { class: "flex items-center gap-2",
                     span { class: "text-xs bg-dark-600 px-2 py-0.5 rounded-full text-zinc-500", "{lost_deals.len()}" }
-                    span { class: "text-xs text-red-400 font-mono", "{format_currency(lost_value)}" }
+                    span { class: "text-xs text-red-400 font-mono", "{lost_value}" }
                 }
             }
         }
     }
 }
-
-fn format_currency(value: f64) -> String {
-    if value >= 1_000_000.0 {
-        format!("${:.1}M", value / 1_000_000.0)
-    } else if value >= 1_000.0 {
-        format!("${:.0}K", value / 1_000.0)
-    } else {
-        format!("${:.0}", value)
-    }
-}
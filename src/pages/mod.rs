@@ -2,8 +2,12 @@ mod dashboard;
 mod contacts;
 mod deals;
 mod activities;
+mod settings;
+mod trash;
 
 pub use dashboard::DashboardPage;
 pub use contacts::ContactsPage;
 pub use deals::DealsPage;
 pub use activities::ActivitiesPage;
+pub use settings::SettingsPage;
+pub use trash::TrashPage;
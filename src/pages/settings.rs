@@ -0,0 +1,528 @@
+use crate::models::{
+    BillingCycle, Currency, DealStage, DeliveryStatus, Product, ValidationErrors, Webhook, WebhookEvent,
+    export_audit_csv, write_export,
+};
+use crate::state::{
+    add_product, add_webhook, delete_product, delete_webhook, toggle_webhook, update_settings, use_app_data,
+};
+use chrono::Local;
+use dioxus::prelude::*;
+use rust_decimal::Decimal;
+
+#[component]
+pub fn SettingsPage() -> Element {
+    rsx! {
+        div { class: "flex-1 overflow-y-auto p-6",
+            div { class: "max-w-3xl space-y-6",
+                SettingsSection {
+                    title: "Currency",
+                    description: "Pipeline totals and reports are converted into the reporting currency.",
+                    CurrencySettings {}
+                }
+                SettingsSection {
+                    title: "Product Catalog",
+                    description: "Price book used to build deal line items.",
+                    ProductCatalog {}
+                }
+                SettingsSection {
+                    title: "Close Reasons",
+                    description: "Offered when a deal is marked as won or lost.",
+                    div { class: "grid grid-cols-2 gap-6",
+                        ReasonList { stage: DealStage::Won }
+                        ReasonList { stage: DealStage::Lost }
+                    }
+                }
+                SettingsSection {
+                    title: "Trash",
+                    description: "Deleted records are kept in the trash so they can be restored.",
+                    TrashSettings {}
+                }
+                SettingsSection {
+                    title: "Change History",
+                    description: "Edits to contacts, deals and activities are logged field by field.",
+                    HistorySettings {}
+                }
+                SettingsSection {
+                    title: "Webhooks",
+                    description: "POSTs signed JSON to your endpoints when records change. Failed deliveries are retried with backoff.",
+                    WebhookSettings {}
+                }
+                SettingsSection {
+                    title: "Delivery Log",
+                    description: "Recent webhook deliveries, newest first.",
+                    DeliveryLog {}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SettingsSection(title: &'static str, description: &'static str, children: Element) -> Element {
+    rsx! {
+        div { class: "bg-dark-800 border border-zinc-800 rounded-xl overflow-hidden",
+            div { class: "px-5 py-4 border-b border-zinc-800",
+                h3 { class: "text-sm font-semibold text-zinc-100", "{title}" }
+                p { class: "text-xs text-zinc-500 mt-0.5", "{description}" }
+            }
+            div { class: "p-5", {children} }
+        }
+    }
+}
+
+#[component]
+fn CurrencySettings() -> Element {
+    let mut data = use_app_data();
+    let settings = data.read().settings.clone();
+    let reporting = settings.reporting_currency;
+
+    rsx! {
+        div {
+            div { class: "flex items-center justify-between mb-4",
+                span { class: "text-sm text-zinc-400", "Reporting currency" }
+                select {
+                    class: "px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                            focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    onchange: move |e| {
+                        let mut settings = data.read().settings.clone();
+                        settings.set_reporting_currency(Currency::from_code(&e.value()));
+                        update_settings(&mut data, settings);
+                    },
+                    for c in Currency::all() {
+                        option {
+                            value: "{c.code()}",
+                            selected: reporting == c,
+                            "{c.symbol()} {c.code()}"
+                        }
+                    }
+                }
+            }
+
+            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3", "Exchange Rates" }
+            div { class: "space-y-2",
+                for c in Currency::all().into_iter().filter(|c| *c != reporting) {
+                    div { class: "flex items-center gap-3",
+                        span { class: "w-20 text-sm text-zinc-100 font-mono", "1 {c.code()} =" }
+                        input {
+                            class: "w-32 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm font-mono
+                                    focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                            r#type: "number",
+                            step: "any",
+                            min: "0",
+                            value: "{settings.rate(c)}",
+                            onchange: move |e| {
+                                if let Ok(rate) = e.value().parse::<Decimal>()
+                                    && rate > Decimal::ZERO
+                                {
+                                    let mut settings = data.read().settings.clone();
+                                    settings.exchange_rates.insert(c, rate);
+                                    update_settings(&mut data, settings);
+                                }
+                            },
+                        }
+                        span { class: "text-sm text-zinc-500 font-mono", "{reporting.code()}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TrashSettings() -> Element {
+    let mut data = use_app_data();
+    let days = data.read().settings.trash_retention_days;
+
+    rsx! {
+        div { class: "flex items-center justify-between",
+            div {
+                span { class: "text-sm text-zinc-400", "Purge after" }
+                p { class: "text-xs text-zinc-500 mt-0.5", "Checked at startup. Use 0 to keep items until you empty the trash." }
+            }
+            div { class: "flex items-center gap-2",
+                input {
+                    class: "w-20 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm font-mono
+                            focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    r#type: "number",
+                    min: "0",
+                    value: "{days}",
+                    onchange: move |e| {
+                        if let Ok(days) = e.value().parse::<u32>() {
+                            let mut settings = data.read().settings.clone();
+                            settings.trash_retention_days = days;
+                            update_settings(&mut data, settings);
+                        }
+                    },
+                }
+                span { class: "text-sm text-zinc-500", "days" }
+            }
+        }
+    }
+}
+
+#[component]
+fn HistorySettings() -> Element {
+    let mut data = use_app_data();
+    let mut export_status = use_signal(|| None::<String>);
+    let user_name = data.read().settings.user_name.clone();
+    let placeholder = data.read().actor();
+    let entry_count = data.read().audit_log.len();
+
+    let handle_export = move |_| {
+        let csv = export_audit_csv(&data.read().audit_log);
+        let status = match write_export("dcrm-history.csv", &csv) {
+            Ok(path) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        };
+        export_status.set(Some(status));
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex items-center justify-between",
+                div {
+                    span { class: "text-sm text-zinc-400", "Your name" }
+                    p { class: "text-xs text-zinc-500 mt-0.5", "Shown as the author of your changes." }
+                }
+                input {
+                    class: "w-56 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                            focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    r#type: "text",
+                    placeholder: "{placeholder}",
+                    value: "{user_name}",
+                    onchange: move |e| {
+                        let mut settings = data.read().settings.clone();
+                        settings.user_name = e.value().trim().to_string();
+                        update_settings(&mut data, settings);
+                    },
+                }
+            }
+            div { class: "flex items-center justify-between",
+                span { class: "text-sm text-zinc-400", "{entry_count} changes recorded" }
+                div { class: "flex items-center gap-3",
+                    if let Some(status) = export_status.read().as_ref() {
+                        span { class: "text-xs text-zinc-500", "{status}" }
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: handle_export,
+                        "Export CSV"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ReasonList(stage: DealStage) -> Element {
+    let mut data = use_app_data();
+    let mut new_reason = use_signal(String::new);
+
+    let reasons = data.read().settings.close_reasons(stage).to_vec();
+    let label = if stage == DealStage::Won { "Won" } else { "Lost" };
+
+    let mut set_reasons = move |reasons: Vec<String>| {
+        let mut settings = data.read().settings.clone();
+        match stage {
+            DealStage::Lost => settings.lost_reasons = reasons,
+            _ => settings.won_reasons = reasons,
+        }
+        update_settings(&mut data, settings);
+    };
+
+    rsx! {
+        div {
+            div { class: "text-xs font-semibold text-zinc-500 uppercase tracking-wider mb-3", "{label}" }
+
+            div { class: "space-y-1 mb-3",
+                for (i, reason) in reasons.iter().enumerate() {
+                    div { class: "flex items-center justify-between px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md",
+                        span { class: "text-sm text-zinc-100", "{reason}" }
+                        button {
+                            class: "text-zinc-500 hover:text-red-400 transition-colors",
+                            onclick: {
+                                let mut remaining = reasons.clone();
+                                move |_| {
+                                    remaining.remove(i);
+                                    set_reasons(remaining.clone());
+                                }
+                            },
+                            "×"
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex gap-2",
+                input {
+                    class: "flex-1 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                            placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    r#type: "text",
+                    placeholder: "Add a reason...",
+                    value: "{new_reason}",
+                    oninput: move |e| new_reason.set(e.value()),
+                }
+                button {
+                    class: "px-3 py-2 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                    onclick: {
+                        let reasons = reasons.clone();
+                        move |_| {
+                            let reason = new_reason.read().trim().to_string();
+                            if reason.is_empty() || reasons.contains(&reason) {
+                                return;
+                            }
+                            let mut updated = reasons.clone();
+                            updated.push(reason);
+                            set_reasons(updated);
+                            new_reason.set(String::new());
+                        }
+                    },
+                    "Add"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ProductCatalog() -> Element {
+    let mut data = use_app_data();
+    let mut name = use_signal(String::new);
+    let mut price = use_signal(String::new);
+    let mut billing = use_signal(|| BillingCycle::OneTime);
+
+    let products = data.read().products.clone();
+    let symbol = data.read().settings.reporting_currency.symbol().to_string();
+
+    let handle_add = move |_| {
+        let product_name = name.read().trim().to_string();
+        if product_name.is_empty() {
+            return;
+        }
+        let unit_price = price.read().parse().unwrap_or(Decimal::ZERO);
+        add_product(&mut data, Product::new(product_name, unit_price, *billing.read()));
+        name.set(String::new());
+        price.set(String::new());
+    };
+
+    rsx! {
+        div {
+            if products.is_empty() {
+                p { class: "text-sm text-zinc-500 mb-3", "No products yet" }
+            } else {
+                div { class: "space-y-1 mb-3",
+                    for product in products {
+                        div { class: "flex items-center gap-4 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md",
+                            span { class: "flex-1 text-sm text-zinc-100", "{product.name}" }
+                            span { class: "text-xs text-zinc-500", "{product.billing}" }
+                            span { class: "font-mono text-sm text-accent", "{symbol}{product.unit_price:.2}" }
+                            button {
+                                class: "text-zinc-500 hover:text-red-400 transition-colors",
+                                onclick: {
+                                    let id = product.id.clone();
+                                    move |_| delete_product(&mut data, &id)
+                                },
+                                "×"
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex gap-2",
+                input {
+                    class: "flex-1 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                            placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    r#type: "text",
+                    placeholder: "Product name",
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                input {
+                    class: "w-28 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                            placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    r#type: "number",
+                    placeholder: "Price",
+                    value: "{price}",
+                    oninput: move |e| price.set(e.value()),
+                }
+                select {
+                    class: "px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                            focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                    onchange: move |e| billing.set(BillingCycle::from_name(&e.value())),
+                    for b in BillingCycle::all() {
+                        option {
+                            value: "{b.display_name()}",
+                            selected: *billing.read() == b,
+                            "{b.display_name()}"
+                        }
+                    }
+                }
+                button {
+                    class: "px-3 py-2 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                    onclick: handle_add,
+                    "Add"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn WebhookSettings() -> Element {
+    let mut data = use_app_data();
+    let mut url = use_signal(String::new);
+    let mut secret = use_signal(String::new);
+    let mut events = use_signal(WebhookEvent::all);
+    let mut errors = use_signal(ValidationErrors::default);
+
+    let webhooks = data.read().settings.webhooks.clone();
+
+    let handle_add = move |_| {
+        let webhook = Webhook::new(url.read().trim().to_string(), secret.read().trim().to_string(), events.read().clone());
+        match add_webhook(&mut data, webhook) {
+            Ok(()) => {
+                url.set(String::new());
+                secret.set(String::new());
+                events.set(WebhookEvent::all());
+                errors.set(ValidationErrors::default());
+            }
+            Err(e) => errors.set(e),
+        }
+    };
+
+    rsx! {
+        div {
+            if webhooks.is_empty() {
+                p { class: "text-sm text-zinc-500 mb-4", "No webhooks yet" }
+            } else {
+                div { class: "space-y-1 mb-4",
+                    for webhook in webhooks {
+                        div { class: "flex items-center gap-4 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md",
+                            input {
+                                r#type: "checkbox",
+                                class: "accent-accent",
+                                title: "Enabled",
+                                checked: webhook.enabled,
+                                onchange: {
+                                    let id = webhook.id.clone();
+                                    move |_| toggle_webhook(&mut data, &id)
+                                },
+                            }
+                            div { class: "flex-1 min-w-0",
+                                div {
+                                    class: if webhook.enabled { "text-sm text-zinc-100 font-mono truncate" } else { "text-sm text-zinc-500 font-mono truncate" },
+                                    "{webhook.url}"
+                                }
+                                div { class: "text-xs text-zinc-500 truncate",
+                                    {webhook.events.iter().map(|e| e.key()).collect::<Vec<_>>().join(", ")}
+                                }
+                            }
+                            button {
+                                class: "text-zinc-500 hover:text-red-400 transition-colors",
+                                onclick: {
+                                    let id = webhook.id.clone();
+                                    move |_| delete_webhook(&mut data, &id)
+                                },
+                                "×"
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "space-y-3",
+                div { class: "flex gap-2",
+                    input {
+                        class: "flex-1 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                        r#type: "url",
+                        placeholder: "https://example.com/hooks/dcrm",
+                        value: "{url}",
+                        oninput: move |e| url.set(e.value()),
+                    }
+                    input {
+                        class: "w-40 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-zinc-100 text-sm
+                                placeholder-zinc-500 focus:border-accent focus:ring-1 focus:ring-accent/20 outline-none transition-all",
+                        r#type: "password",
+                        placeholder: "Signing secret",
+                        value: "{secret}",
+                        oninput: move |e| secret.set(e.value()),
+                    }
+                    button {
+                        class: "px-3 py-2 text-sm bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: handle_add,
+                        "Add"
+                    }
+                }
+                for message in ["url", "secret", "events"].into_iter().filter_map(|f| errors.read().message(f)) {
+                    p { class: "text-xs text-red-400", "{message}" }
+                }
+                div { class: "grid grid-cols-2 gap-x-4 gap-y-1",
+                    for event in WebhookEvent::all() {
+                        label { class: "flex items-center gap-2 text-sm text-zinc-400",
+                            input {
+                                r#type: "checkbox",
+                                class: "accent-accent",
+                                checked: events.read().contains(&event),
+                                onchange: move |_| {
+                                    let mut selected = events.read().clone();
+                                    if selected.contains(&event) {
+                                        selected.retain(|e| *e != event);
+                                    } else {
+                                        selected.push(event);
+                                    }
+                                    events.set(selected);
+                                },
+                            }
+                            "{event}"
+                            span { class: "text-xs text-zinc-600 font-mono", "{event.key()}" }
+                        }
+                    }
+                }
+                p { class: "text-xs text-zinc-500",
+                    "Each request carries X-Dcrm-Event, X-Dcrm-Delivery and X-Dcrm-Signature-256, the HMAC-SHA256 of the body under the secret."
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DeliveryLog() -> Element {
+    let data = use_app_data();
+    let deliveries: Vec<_> = data.read().recent_deliveries().take(50).cloned().collect();
+
+    rsx! {
+        if deliveries.is_empty() {
+            p { class: "text-sm text-zinc-500", "Nothing sent yet" }
+        } else {
+            div { class: "space-y-1",
+                for delivery in deliveries {
+                    div {
+                        key: "{delivery.id}",
+                        class: "flex items-center gap-4 px-3 py-2 bg-dark-700 border border-zinc-700 rounded-md text-sm",
+                        span { class: "w-28 text-xs text-zinc-500 font-mono",
+                            {delivery.created_at.with_timezone(&Local).format("%b %d %H:%M:%S").to_string()}
+                        }
+                        span { class: "w-36 text-zinc-100 font-mono text-xs", "{delivery.event.key()}" }
+                        span { class: "flex-1 min-w-0 text-xs text-zinc-500 truncate", "{delivery.url}" }
+                        span { class: "text-xs text-zinc-500 truncate max-w-48",
+                            {delivery.last_attempt().map(|a| a.summary()).unwrap_or_default()}
+                        }
+                        span {
+                            class: match delivery.status {
+                                DeliveryStatus::Delivered => "text-xs text-green-400",
+                                DeliveryStatus::Failed => "text-xs text-red-400",
+                                DeliveryStatus::Pending => "text-xs text-amber-400",
+                            },
+                            title: "{delivery.attempts.len()} attempts",
+                            "{delivery.status}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
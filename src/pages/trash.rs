@@ -0,0 +1,121 @@
+use crate::models::TrashEntry;
+use crate::state::{empty_trash, purge_record, restore_record, use_app_data};
+use chrono::{Duration, Utc};
+use dioxus::prelude::*;
+
+#[component]
+pub fn TrashPage() -> Element {
+    let mut data = use_app_data();
+    let entries = data.read().trash.entries();
+    let retention = data.read().settings.trash_retention_days;
+
+    rsx! {
+        div { class: "flex-1 overflow-hidden p-6 flex flex-col",
+            // Header
+            div { class: "flex items-center justify-between mb-6",
+                div {
+                    h2 { class: "text-lg font-semibold text-zinc-100", "Trash" }
+                    p { class: "text-sm text-zinc-500 mt-0.5",
+                        if retention > 0 {
+                            "Items are permanently deleted after {retention} days."
+                        } else {
+                            "Items stay here until you delete them."
+                        }
+                    }
+                }
+                if !entries.is_empty() {
+                    button {
+                        class: "px-4 py-2 text-sm text-red-400 border border-red-500/30 rounded-md hover:bg-red-500/10 transition-colors",
+                        onclick: move |_| empty_trash(&mut data),
+                        "Empty Trash"
+                    }
+                }
+            }
+
+            div { class: "bg-dark-800 border border-zinc-800 rounded-xl overflow-hidden flex-1",
+                if entries.is_empty() {
+                    div { class: "flex flex-col items-center justify-center py-16 text-center",
+                        div { class: "w-16 h-16 bg-dark-700 rounded-full flex items-center justify-center text-2xl text-zinc-500 mb-4",
+                            "⌫"
+                        }
+                        div { class: "text-zinc-100 font-medium mb-1", "Trash is empty" }
+                        div { class: "text-sm text-zinc-500", "Deleted contacts, deals and activities show up here" }
+                    }
+                } else {
+                    div { class: "overflow-y-auto max-h-full",
+                        table { class: "w-full",
+                            thead {
+                                tr { class: "border-b border-zinc-800 bg-dark-700",
+                                    th { class: "text-left px-4 py-3 text-xs font-semibold text-zinc-500 uppercase tracking-wider", "Item" }
+                                    th { class: "text-left px-4 py-3 text-xs font-semibold text-zinc-500 uppercase tracking-wider", "Type" }
+                                    th { class: "text-left px-4 py-3 text-xs font-semibold text-zinc-500 uppercase tracking-wider", "Deleted" }
+                                    th { class: "text-left px-4 py-3 text-xs font-semibold text-zinc-500 uppercase tracking-wider", "Purged" }
+                                    th { class: "px-4 py-3" }
+                                }
+                            }
+                            tbody {
+                                for entry in entries {
+                                    TrashRow { key: "{entry.id}", entry: entry, retention: retention }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TrashRow(entry: TrashEntry, retention: u32) -> Element {
+    let mut data = use_app_data();
+    let kind = entry.kind;
+
+    let purge_on = if retention > 0 {
+        (entry.deleted_at + Duration::days(retention as i64))
+            .format("%b %d, %Y")
+            .to_string()
+    } else {
+        "Never".to_string()
+    };
+    let days_ago = (Utc::now() - entry.deleted_at).num_days();
+    let deleted = match days_ago {
+        0 => "Today".to_string(),
+        1 => "Yesterday".to_string(),
+        n => format!("{} days ago", n),
+    };
+
+    rsx! {
+        tr { class: "border-b border-zinc-800 hover:bg-dark-700/50 transition-colors",
+            td { class: "px-4 py-3",
+                div { class: "flex items-center gap-3",
+                    span { class: "text-base", "{kind.icon()}" }
+                    span { class: "text-sm text-zinc-100", "{entry.title}" }
+                }
+            }
+            td { class: "px-4 py-3 text-sm text-zinc-400", "{kind}" }
+            td { class: "px-4 py-3 text-sm text-zinc-400", "{deleted}" }
+            td { class: "px-4 py-3 text-sm text-zinc-500 font-mono", "{purge_on}" }
+            td { class: "px-4 py-3",
+                div { class: "flex items-center justify-end gap-2",
+                    button {
+                        class: "px-3 py-1 text-xs bg-dark-700 border border-zinc-700 text-zinc-100 rounded-md hover:bg-zinc-700 transition-colors",
+                        onclick: {
+                            let id = entry.id.clone();
+                            move |_| restore_record(&mut data, kind, &id)
+                        },
+                        "Restore"
+                    }
+                    button {
+                        class: "px-3 py-1 text-xs text-red-400 rounded-md hover:bg-red-500/10 transition-colors",
+                        onclick: {
+                            let id = entry.id.clone();
+                            move |_| purge_record(&mut data, kind, &id)
+                        },
+                        "Delete forever"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! App-wide keyboard shortcuts
+
+use crate::state::{Modal, View, redo, undo, use_app_data, use_modal, use_navigator, use_search_query};
+use dioxus::prelude::*;
+
+/// Every shortcut, grouped for the cheat sheet.
+pub const SHORTCUTS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "General",
+        &[
+            ("Ctrl/⌘ K", "Command palette"),
+            ("/", "Search"),
+            ("N", "New record in the current view"),
+            ("Esc", "Close the dialog or clear the search"),
+            ("Enter", "Submit the open form"),
+            ("Ctrl/⌘ Z", "Undo"),
+            ("Ctrl/⌘ Shift Z", "Redo"),
+            ("?", "Show these shortcuts"),
+        ],
+    ),
+    (
+        "Navigation",
+        &[
+            ("Ctrl/⌘ 1", "Dashboard"),
+            ("Ctrl/⌘ 2", "Contacts"),
+            ("Ctrl/⌘ 3", "Deals"),
+            ("Ctrl/⌘ 4", "Activities"),
+            ("Ctrl/⌘ 5", "Trash"),
+            ("Ctrl/⌘ 6", "Settings"),
+        ],
+    ),
+    (
+        "Lists",
+        &[
+            ("↑ ↓", "Move through contacts, search results and commands"),
+            ("Home End", "First or last contact"),
+            ("Enter", "Open the selected item"),
+        ],
+    ),
+    (
+        "Deal board",
+        &[
+            ("↑ ↓", "Reorder the focused deal"),
+            ("← →", "Move the focused deal to the previous or next stage"),
+            ("Enter", "Open the focused deal"),
+        ],
+    ),
+];
+
+/// Forwards shortcuts from the window. Ctrl/Cmd+K, the view keys and Escape work
+/// everywhere; Enter in a text field clicks the open form's `data-submit` button;
+/// single keys and undo are left to text fields while one has focus.
+const LISTENER: &str = r#"
+window.addEventListener("keydown", (e) => {
+    const target = e.target;
+    const tag = target && target.tagName;
+    const typing = tag === "INPUT" || tag === "TEXTAREA" || tag === "SELECT" || (target && target.isContentEditable);
+    const mod = e.ctrlKey || e.metaKey;
+    const key = e.key.toLowerCase();
+
+    if (e.key === "Escape") {
+        dioxus.send("escape");
+    } else if (mod && key === "k") {
+        e.preventDefault();
+        dioxus.send("palette");
+    } else if (mod && !e.shiftKey && !e.altKey && key >= "1" && key <= "6") {
+        e.preventDefault();
+        dioxus.send("view:" + key);
+    } else if (e.key === "Enter" && tag === "INPUT" && !mod) {
+        const submit = document.querySelector("[data-submit]");
+        if (submit) {
+            e.preventDefault();
+            submit.click();
+        }
+    } else if (typing) {
+        return;
+    } else if (mod && key === "z") {
+        e.preventDefault();
+        dioxus.send(e.shiftKey ? "redo" : "undo");
+    } else if (mod && key === "y") {
+        e.preventDefault();
+        dioxus.send("redo");
+    } else if (mod || e.altKey) {
+        return;
+    } else if (e.key === "?") {
+        e.preventDefault();
+        dioxus.send("shortcuts");
+    } else if (e.key === "/") {
+        e.preventDefault();
+        dioxus.send("search");
+    } else if (key === "n") {
+        e.preventDefault();
+        dioxus.send("new");
+    }
+});
+"#;
+
+/// The keyboard layer for as long as the app is running.
+pub fn use_shortcuts() {
+    let mut data = use_app_data();
+    let mut modal = use_modal();
+    let mut search_query = use_search_query();
+    let mut navigator = use_navigator();
+
+    use_future(move || async move {
+        let mut eval = document::eval(LISTENER);
+        while let Ok(action) = eval.recv::<String>().await {
+            let open = modal.peek().clone();
+            match action.as_str() {
+                "undo" => {
+                    undo(&mut data);
+                }
+                "redo" => {
+                    redo(&mut data);
+                }
+                "palette" => {
+                    let next = if open == Modal::CommandPalette { Modal::None } else { Modal::CommandPalette };
+                    modal.set(next);
+                }
+                "escape" => {
+                    if open == Modal::Search || (open == Modal::None && !search_query.peek().is_empty()) {
+                        search_query.set(String::new());
+                    }
+                    if open != Modal::None {
+                        modal.set(Modal::None);
+                    }
+                }
+                // The rest only apply when no dialog is in the way
+                _ if !matches!(open, Modal::None | Modal::Shortcuts) => {}
+                "shortcuts" => {
+                    let next = if open == Modal::Shortcuts { Modal::None } else { Modal::Shortcuts };
+                    modal.set(next);
+                }
+                "search" => modal.set(Modal::Search),
+                "new" => navigator.new_record(),
+                view => {
+                    let index = view.strip_prefix("view:").and_then(|n| n.parse::<usize>().ok());
+                    if let Some(view) = index.and_then(|n| View::all().get(n.wrapping_sub(1)).cloned()) {
+                        navigator.go(view);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Where an arrow, Home or End key moves a highlight in a list of `len` items,
+/// or `None` for other keys.
+pub fn list_step(key: &Key, current: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let last = len - 1;
+    match key {
+        Key::ArrowDown => Some(current.map_or(0, |i| (i + 1).min(last))),
+        Key::ArrowUp => Some(current.map_or(last, |i| i.saturating_sub(1))),
+        Key::Home => Some(0),
+        Key::End => Some(last),
+        _ => None,
+    }
+}
+
+/// Like [`list_step`] for a list driven from a text field, which keeps Home and End
+/// for its cursor.
+pub fn arrow_step(key: &Key, current: usize, len: usize) -> Option<usize> {
+    match key {
+        Key::ArrowUp | Key::ArrowDown => list_step(key, Some(current), len),
+        _ => None,
+    }
+}
+
+/// Scrolls the element with `id` into view if it's outside its scroll area.
+pub fn scroll_into_view(id: &str) {
+    document::eval(&format!(
+        "document.getElementById({:?})?.scrollIntoView({{ block: \"nearest\" }});",
+        id
+    ));
+}
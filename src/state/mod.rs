@@ -1,4 +1,10 @@
-use crate::models::{Activity, AppData, Contact, Deal, DealStage, save_data};
+use crate::models::{
+    Activity, AppData, CloseInfo, Contact, ContactRelationship, Deal, DealStage, DeleteMode, Delivery,
+    DeliveryStatus, FileChanged, Frequency, Product, RecordKind, RecurrenceEnd, Settings, ValidationErrors,
+    Webhook, data_path, open_data, overwrite_data, save_data,
+};
+use dcrm_core::actions;
+use dcrm_core::search::SearchResult;
 use dioxus::prelude::*;
 
 // ============================================================================
@@ -11,6 +17,33 @@ pub enum View {
     Contacts,
     Deals,
     Activities,
+    Trash,
+    Settings,
+}
+
+impl View {
+    /// In sidebar order, which is also the order of the Ctrl+1–6 shortcuts.
+    pub fn all() -> Vec<View> {
+        vec![
+            View::Dashboard,
+            View::Contacts,
+            View::Deals,
+            View::Activities,
+            View::Trash,
+            View::Settings,
+        ]
+    }
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            View::Dashboard => "Dashboard",
+            View::Contacts => "Contacts",
+            View::Deals => "Deals",
+            View::Activities => "Activities",
+            View::Trash => "Trash",
+            View::Settings => "Settings",
+        }
+    }
 }
 
 impl Default for View {
@@ -24,12 +57,23 @@ pub enum Modal {
     None,
     NewContact,
     EditContact(String),
+    DeleteContact(String),
+    Duplicates,
+    MergeContacts(String, String),
     NewDeal,
     EditDeal(String),
+    CloseDeal(String, DealStage),
+    DeleteDeal(String),
     NewActivity,
+    NewDealActivity(String),
+    EditActivity(String),
     ContactDetail(String),
     DealDetail(String),
+    OrgChart(String),
+    EditSeries(String),
     Search,
+    CommandPalette,
+    Shortcuts,
 }
 
 impl Default for Modal {
@@ -58,182 +102,328 @@ pub fn use_search_query() -> Signal<String> {
     use_context::<Signal<String>>()
 }
 
+/// The contact shown on the Contacts page.
+pub fn use_selected_contact() -> Signal<Option<String>> {
+    use_context::<Signal<Option<String>>>()
+}
+
+/// Why the data file doesn't hold what's on screen, if it doesn't.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveProblem {
+    /// Another program (the CLI or the API server) wrote the file since the app
+    /// last read it. The app keeps its own version until the user picks one.
+    Conflict,
+    Failed(String),
+}
+
+pub fn use_save_problem() -> Signal<Option<SaveProblem>> {
+    use_context::<Signal<Option<SaveProblem>>>()
+}
+
 // ============================================================================
-// Actions
+// Navigation
 // ============================================================================
 
-pub fn add_contact(data: &mut Signal<AppData>, contact: Contact) {
-    data.write().contacts.push(contact);
-    let _ = save_data(&data.read());
+/// Moves around the app from shortcuts, search and the command palette.
+#[derive(Clone, Copy)]
+pub struct Navigator {
+    view: Signal<View>,
+    modal: Signal<Modal>,
+    selected_contact: Signal<Option<String>>,
 }
 
-pub fn update_contact(data: &mut Signal<AppData>, contact: Contact) {
-    if let Some(existing) = data
-        .write()
-        .contacts
-        .iter_mut()
-        .find(|c| c.id == contact.id)
-    {
-        *existing = contact;
+pub fn use_navigator() -> Navigator {
+    Navigator {
+        view: use_current_view(),
+        modal: use_modal(),
+        selected_contact: use_selected_contact(),
     }
-    let _ = save_data(&data.read());
 }
 
-pub fn delete_contact(data: &mut Signal<AppData>, id: &str) {
-    data.write().contacts.retain(|c| c.id != id);
-    let _ = save_data(&data.read());
+impl Navigator {
+    pub fn go(&mut self, view: View) {
+        self.modal.set(Modal::None);
+        self.view.set(view);
+    }
+
+    /// Contacts are selected on the Contacts page; deals and activities open in their modal.
+    pub fn open(&mut self, result: &SearchResult) {
+        match result {
+            SearchResult::Contact(contact) => {
+                self.selected_contact.set(Some(contact.id.clone()));
+                self.go(View::Contacts);
+            }
+            SearchResult::Deal(deal) => self.modal.set(Modal::DealDetail(deal.id.clone())),
+            SearchResult::Activity(activity) => self.modal.set(Modal::EditActivity(activity.id.clone())),
+        }
+    }
+
+    /// Opens the form for a new record of the current view's kind, if it has one.
+    pub fn new_record(&mut self) {
+        let modal = match *self.view.peek() {
+            View::Contacts => Modal::NewContact,
+            View::Deals => Modal::NewDeal,
+            View::Activities => Modal::NewActivity,
+            View::Dashboard | View::Trash | View::Settings => return,
+        };
+        self.modal.set(modal);
+    }
 }
 
-pub fn add_deal(data: &mut Signal<AppData>, deal: Deal) {
-    data.write().deals.push(deal);
-    let _ = save_data(&data.read());
+// ============================================================================
+// Actions
+// ============================================================================
+//
+// Each action runs the matching `dcrm_core::actions` change and saves.
+
+fn save(data: &mut Signal<AppData>) {
+    let problem = match save_data(&mut data.write()) {
+        Ok(()) => None,
+        Err(e) if e.is::<FileChanged>() => Some(SaveProblem::Conflict),
+        Err(e) => Some(SaveProblem::Failed(e.to_string())),
+    };
+    report(problem);
 }
 
-pub fn update_deal(data: &mut Signal<AppData>, deal: Deal) {
-    if let Some(existing) = data.write().deals.iter_mut().find(|d| d.id == deal.id) {
-        *existing = deal;
+/// Shows `problem` in the save toast, or clears it.
+fn report(problem: Option<SaveProblem>) {
+    let mut status = consume_context::<Signal<Option<SaveProblem>>>();
+    if *status.peek() != problem {
+        status.set(problem);
     }
-    let _ = save_data(&data.read());
 }
 
-pub fn update_deal_stage(data: &mut Signal<AppData>, deal_id: &str, new_stage: DealStage) {
-    if let Some(deal) = data.write().deals.iter_mut().find(|d| d.id == deal_id) {
-        deal.stage = new_stage;
-        deal.updated_at = chrono::Utc::now();
-
-        // Update probability based on stage
-        deal.probability = match new_stage {
-            DealStage::Lead => 10,
-            DealStage::Qualified => 25,
-            DealStage::Proposal => 50,
-            DealStage::Negotiation => 75,
-            DealStage::Won => 100,
-            DealStage::Lost => 0,
-        };
+/// Writes the data again, after a failed save.
+pub fn retry_save(data: &mut Signal<AppData>) {
+    save(data);
+}
+
+/// Replaces what's on screen with the data file, dropping changes the app
+/// couldn't save and the undo history.
+pub fn reload_data(data: &mut Signal<AppData>) {
+    match open_data(&data_path()) {
+        Ok(fresh) => {
+            data.set(fresh);
+            report(None);
+        }
+        Err(e) => report(Some(SaveProblem::Failed(format!("Couldn't reload the data file: {}", e)))),
     }
-    let _ = save_data(&data.read());
 }
 
-pub fn delete_deal(data: &mut Signal<AppData>, id: &str) {
-    data.write().deals.retain(|d| d.id != id);
-    let _ = save_data(&data.read());
+/// Saves the app's data over changes another program made to the file.
+pub fn overwrite_file(data: &mut Signal<AppData>) {
+    let problem = overwrite_data(&mut data.write()).err().map(|e| SaveProblem::Failed(e.to_string()));
+    report(problem);
 }
 
-pub fn add_activity(data: &mut Signal<AppData>, activity: Activity) {
-    data.write().activities.push(activity);
-    let _ = save_data(&data.read());
+pub fn add_contact(data: &mut Signal<AppData>, contact: Contact) -> Result<(), ValidationErrors> {
+    actions::add_contact(&mut data.write(), contact)?;
+    save(data);
+    Ok(())
 }
 
-pub fn update_activity(data: &mut Signal<AppData>, activity: Activity) {
-    if let Some(existing) = data
-        .write()
-        .activities
-        .iter_mut()
-        .find(|a| a.id == activity.id)
-    {
-        *existing = activity;
-    }
-    let _ = save_data(&data.read());
+pub fn update_contact(data: &mut Signal<AppData>, contact: Contact) -> Result<(), ValidationErrors> {
+    actions::update_contact(&mut data.write(), contact)?;
+    save(data);
+    Ok(())
+}
+
+pub fn delete_contact(data: &mut Signal<AppData>, id: &str, mode: DeleteMode) {
+    actions::delete_contact(&mut data.write(), id, mode);
+    save(data);
+}
+
+pub fn merge_contacts(
+    data: &mut Signal<AppData>,
+    merged: Contact,
+    duplicate_id: &str,
+) -> Result<(), ValidationErrors> {
+    actions::merge_contacts(&mut data.write(), merged, duplicate_id)?;
+    save(data);
+    Ok(())
+}
+
+pub fn add_relationship(data: &mut Signal<AppData>, relationship: ContactRelationship) {
+    actions::add_relationship(&mut data.write(), relationship);
+    save(data);
+}
+
+pub fn delete_relationship(data: &mut Signal<AppData>, id: &str) {
+    actions::delete_relationship(&mut data.write(), id);
+    save(data);
+}
+
+pub fn add_deal(data: &mut Signal<AppData>, deal: Deal) -> Result<(), ValidationErrors> {
+    actions::add_deal(&mut data.write(), deal)?;
+    save(data);
+    Ok(())
+}
+
+pub fn update_deal(data: &mut Signal<AppData>, deal: Deal) -> Result<(), ValidationErrors> {
+    actions::update_deal(&mut data.write(), deal)?;
+    save(data);
+    Ok(())
+}
+
+pub fn update_deal_stage(data: &mut Signal<AppData>, deal_id: &str, new_stage: DealStage) {
+    actions::update_deal_stage(&mut data.write(), deal_id, new_stage);
+    save(data);
+}
+
+pub fn close_deal(
+    data: &mut Signal<AppData>,
+    deal_id: &str,
+    stage: DealStage,
+    close: CloseInfo,
+) -> Result<(), ValidationErrors> {
+    actions::close_deal(&mut data.write(), deal_id, stage, close)?;
+    save(data);
+    Ok(())
+}
+
+pub fn move_deal(data: &mut Signal<AppData>, deal_id: &str, stage: DealStage, index: Option<usize>) {
+    actions::move_deal(&mut data.write(), deal_id, stage, index);
+    save(data);
+}
+
+pub fn delete_deal(data: &mut Signal<AppData>, id: &str, mode: DeleteMode) {
+    actions::delete_deal(&mut data.write(), id, mode);
+    save(data);
+}
+
+pub fn add_activity(data: &mut Signal<AppData>, activity: Activity) -> Result<(), ValidationErrors> {
+    actions::add_activity(&mut data.write(), activity)?;
+    save(data);
+    Ok(())
+}
+
+pub fn update_activity(data: &mut Signal<AppData>, activity: Activity) -> Result<(), ValidationErrors> {
+    actions::update_activity(&mut data.write(), activity)?;
+    save(data);
+    Ok(())
 }
 
 pub fn toggle_activity_completed(data: &mut Signal<AppData>, id: &str) {
-    if let Some(activity) = data.write().activities.iter_mut().find(|a| a.id == id) {
-        activity.completed = !activity.completed;
-        activity.updated_at = chrono::Utc::now();
+    actions::toggle_activity_completed(&mut data.write(), id);
+    save(data);
+}
+
+pub fn update_series(
+    data: &mut Signal<AppData>,
+    series_id: &str,
+    frequency: Frequency,
+    interval: u32,
+    end: RecurrenceEnd,
+) {
+    actions::update_series(&mut data.write(), series_id, frequency, interval, end);
+    save(data);
+}
+
+pub fn cancel_series(data: &mut Signal<AppData>, series_id: &str) {
+    actions::cancel_series(&mut data.write(), series_id);
+    save(data);
+}
+
+pub fn take_due_reminders(data: &mut Signal<AppData>) -> Vec<Activity> {
+    let now = chrono::Utc::now();
+    // Only take the write lock (and re-render) when something is actually due
+    if !data.read().activities.iter().any(|a| a.reminder_due(now)) {
+        return Vec::new();
     }
-    let _ = save_data(&data.read());
+
+    let due = actions::take_due_reminders(&mut data.write(), now);
+    save(data);
+    due
 }
 
 pub fn delete_activity(data: &mut Signal<AppData>, id: &str) {
-    data.write().activities.retain(|a| a.id != id);
-    let _ = save_data(&data.read());
+    actions::delete_activity(&mut data.write(), id);
+    save(data);
 }
 
-// ============================================================================
-// Search Functionality
-// ============================================================================
+pub fn restore_record(data: &mut Signal<AppData>, kind: RecordKind, id: &str) {
+    actions::restore_record(&mut data.write(), kind, id);
+    save(data);
+}
 
-use fuzzy_matcher::FuzzyMatcher;
-use fuzzy_matcher::skim::SkimMatcherV2;
+pub fn purge_record(data: &mut Signal<AppData>, kind: RecordKind, id: &str) {
+    actions::purge_record(&mut data.write(), kind, id);
+    save(data);
+}
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum SearchResult {
-    Contact(Contact),
-    Deal(Deal),
-    Activity(Activity),
+pub fn empty_trash(data: &mut Signal<AppData>) {
+    actions::empty_trash(&mut data.write());
+    save(data);
 }
 
-impl SearchResult {
-    pub fn title(&self) -> String {
-        match self {
-            SearchResult::Contact(c) => c.full_name(),
-            SearchResult::Deal(d) => d.title.clone(),
-            SearchResult::Activity(a) => a.title.clone(),
-        }
-    }
+pub fn add_product(data: &mut Signal<AppData>, product: Product) {
+    actions::add_product(&mut data.write(), product);
+    save(data);
+}
 
-    pub fn subtitle(&self) -> String {
-        match self {
-            SearchResult::Contact(c) => c.company.clone().unwrap_or_else(|| c.email.clone()),
-            SearchResult::Deal(d) => format!("{} • {}", d.company, d.format_value()),
-            SearchResult::Activity(a) => a.activity_type.display_name().to_string(),
-        }
-    }
+pub fn delete_product(data: &mut Signal<AppData>, id: &str) {
+    actions::delete_product(&mut data.write(), id);
+    save(data);
+}
 
-    pub fn result_type(&self) -> &str {
-        match self {
-            SearchResult::Contact(_) => "Contact",
-            SearchResult::Deal(_) => "Deal",
-            SearchResult::Activity(_) => "Activity",
-        }
-    }
+pub fn update_settings(data: &mut Signal<AppData>, settings: Settings) {
+    actions::update_settings(&mut data.write(), settings);
+    save(data);
+}
+
+pub fn add_webhook(data: &mut Signal<AppData>, webhook: Webhook) -> Result<(), ValidationErrors> {
+    actions::add_webhook(&mut data.write(), webhook)?;
+    save(data);
+    Ok(())
+}
+
+pub fn toggle_webhook(data: &mut Signal<AppData>, id: &str) {
+    actions::toggle_webhook(&mut data.write(), id);
+    save(data);
+}
+
+pub fn delete_webhook(data: &mut Signal<AppData>, id: &str) {
+    actions::delete_webhook(&mut data.write(), id);
+    save(data);
 }
 
-pub fn search(data: &AppData, query: &str) -> Vec<SearchResult> {
-    if query.is_empty() {
+/// Moves queued webhook events into the delivery log and returns them for sending.
+pub fn take_deliveries(data: &mut Signal<AppData>) -> Vec<(Webhook, Delivery)> {
+    // Only take the write lock (and re-render) when something is queued
+    if data.read().pending_events().is_empty() {
         return Vec::new();
     }
 
-    let matcher = SkimMatcherV2::default();
-    let mut results: Vec<(i64, SearchResult)> = Vec::new();
-
-    // Search contacts
-    for contact in &data.contacts {
-        let search_str = format!(
-            "{} {} {} {}",
-            contact.first_name,
-            contact.last_name,
-            contact.email,
-            contact.company.as_deref().unwrap_or("")
-        );
-        if let Some(score) = matcher.fuzzy_match(&search_str, query) {
-            results.push((score, SearchResult::Contact(contact.clone())));
-        }
-    }
+    let jobs = data.write().take_deliveries();
+    save(data);
+    jobs
+}
 
-    // Search deals
-    for deal in &data.deals {
-        let search_str = format!("{} {}", deal.title, deal.company);
-        if let Some(score) = matcher.fuzzy_match(&search_str, query) {
-            results.push((score, SearchResult::Deal(deal.clone())));
-        }
+/// Deliveries left pending when the app or the CLI last exited, for sending again.
+pub fn resume_deliveries(data: &mut Signal<AppData>) -> Vec<(Webhook, Delivery)> {
+    if !data.read().deliveries.iter().any(|d| d.status == DeliveryStatus::Pending) {
+        return Vec::new();
     }
 
-    // Search activities
-    for activity in &data.activities {
-        let search_str = format!(
-            "{} {}",
-            activity.title,
-            activity.description.as_deref().unwrap_or("")
-        );
-        if let Some(score) = matcher.fuzzy_match(&search_str, query) {
-            results.push((score, SearchResult::Activity(activity.clone())));
-        }
-    }
+    let jobs = data.write().resume_deliveries();
+    save(data);
+    jobs
+}
 
-    // Sort by score descending
-    results.sort_by(|a, b| b.0.cmp(&a.0));
+pub fn record_delivery(data: &mut Signal<AppData>, delivery: Delivery) {
+    data.write().record_delivery(delivery);
+    save(data);
+}
+
+/// Reverts the latest change. Returns what was undone, for the status toast.
+pub fn undo(data: &mut Signal<AppData>) -> Option<String> {
+    let label = data.write().undo()?;
+    save(data);
+    Some(label)
+}
 
-    // Return top 10 results
-    results.into_iter().take(10).map(|(_, r)| r).collect()
+pub fn redo(data: &mut Signal<AppData>) -> Option<String> {
+    let label = data.write().redo()?;
+    save(data);
+    Some(label)
 }
@@ -0,0 +1,41 @@
+//! Sends webhook deliveries in the background
+
+use crate::models::{AppData, Delivery, Webhook};
+use crate::state::{record_delivery, resume_deliveries, take_deliveries, use_app_data};
+use dcrm_webhooks::RetryPolicy;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sends queued webhook events for as long as the app is running, starting with
+/// any left pending by an earlier run. Each delivery retries on its own blocking
+/// thread and updates the log when it finishes.
+pub fn use_webhooks() {
+    let mut data = use_app_data();
+
+    use_future(move || async move {
+        for job in resume_deliveries(&mut data) {
+            send(data, job);
+        }
+        loop {
+            for job in take_deliveries(&mut data) {
+                send(data, job);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+fn send(mut data: Signal<AppData>, (webhook, mut delivery): (Webhook, Delivery)) {
+    spawn(async move {
+        let sent = tokio::task::spawn_blocking(move || {
+            dcrm_webhooks::deliver(&webhook, &mut delivery, &RetryPolicy::default());
+            delivery
+        })
+        .await;
+        if let Ok(delivery) = sent {
+            record_delivery(&mut data, delivery);
+        }
+    });
+}